@@ -0,0 +1,76 @@
+use chord_progression_parser::parser::options::ParseOptions;
+use chord_progression_parser::parser::{parse_input, parse_with_options};
+use chord_progression_parser::tokenizer::lexer::Lexer;
+use chord_progression_parser::tokenizer::tokenize;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+const BARS: usize = 10_000;
+const BARS_PER_LINE: usize = 4;
+const LINES_PER_SECTION: usize = 4;
+
+/// songbook of 10k bars, with the meta infos, extensions, denominators and comments
+fn corpus() -> String {
+    const BARS_OF_LINE: [&str; 8] = [
+        "[key=C]C",
+        "Am(9)",
+        "F/A",
+        "G(7,13)",
+        "Em7, A7",
+        "[tempo=120]Dm(11)",
+        "G/B, C",
+        "Bm(b5,7)/F#",
+    ];
+
+    let mut corpus = String::new();
+    for bar in 0..BARS {
+        if bar % (BARS_PER_LINE * LINES_PER_SECTION) == 0 {
+            if bar > 0 {
+                corpus.push_str("\n\n");
+            }
+            let section = bar / (BARS_PER_LINE * LINES_PER_SECTION);
+            corpus.push_str(&format!("@section=Part{section}\n// verse {section}\n"));
+        } else if bar % BARS_PER_LINE == 0 {
+            corpus.push('\n');
+        } else {
+            corpus.push_str(" - ");
+        }
+        corpus.push_str(BARS_OF_LINE[bar % BARS_OF_LINE.len()]);
+    }
+
+    corpus
+}
+
+fn throughput(c: &mut Criterion) {
+    let input = corpus();
+    let options = ParseOptions::default();
+    assert_eq!(
+        parse_input(&input, &options).unwrap().len(),
+        BARS.div_ceil(BARS_PER_LINE * LINES_PER_SECTION)
+    );
+
+    let mut group = c.benchmark_group("10k bars");
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.sample_size(20);
+
+    // the tokens borrowing the texts from the input
+    group.bench_function("lexer", |b| {
+        b.iter(|| Lexer::new(black_box(&input)).count())
+    });
+    // the tokens with the texts copied
+    group.bench_function("tokenize", |b| {
+        b.iter(|| tokenize(black_box(&input)).unwrap())
+    });
+    // all the tokens are collected, then parsed
+    group.bench_function("tokenize + parse", |b| {
+        b.iter(|| parse_with_options(&tokenize(black_box(&input)).unwrap(), &options).unwrap())
+    });
+    // the tokens are parsed as the lexer yields them
+    group.bench_function("parse_input", |b| {
+        b.iter(|| parse_input(black_box(&input), &options).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, throughput);
+criterion_main!(benches);
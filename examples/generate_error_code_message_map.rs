@@ -0,0 +1,10 @@
+//! Print resources/error_code_message_map.ts generated from the messages in src/locale
+//!
+//! ```sh
+//! cargo run --example generate_error_code_message_map > resources/error_code_message_map.ts
+//! ```
+use chord_progression_parser::locale::typescript::error_code_message_map;
+
+fn main() {
+    print!("{}", error_code_message_map());
+}
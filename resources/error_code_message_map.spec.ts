@@ -1,5 +1,8 @@
 import { describe, expect, it, test } from "bun:test";
-import { ERROR_CODE_MESSAGE_MAP } from "./error_code_message_map";
+import {
+  ERROR_CODE_MESSAGE_MAP,
+  LINT_RULE_MESSAGE_MAP,
+} from "./error_code_message_map";
 
 describe("ERROR_CODE_MESSAGE_MAP", () => {
   it("has unique error messages", () => {
@@ -19,3 +22,17 @@ describe("ERROR_CODE_MESSAGE_MAP", () => {
     expect(japaneseErrorMessages).toEqual([...new Set(japaneseErrorMessages)]);
   });
 });
+
+describe("LINT_RULE_MESSAGE_MAP", () => {
+  it("has unique lint messages", () => {
+    const englishLintMessages = Object.values(LINT_RULE_MESSAGE_MAP).map(
+      (lintMessage) => lintMessage.en
+    );
+    const japaneseLintMessages = Object.values(LINT_RULE_MESSAGE_MAP).map(
+      (lintMessage) => lintMessage.ja
+    );
+
+    expect(englishLintMessages).toEqual([...new Set(englishLintMessages)]);
+    expect(japaneseLintMessages).toEqual([...new Set(japaneseLintMessages)]);
+  });
+});
@@ -1,4 +1,5 @@
-// to use this file: make generate-error-code-rs
+// NOTE: Do not edit this file manually, it is generated from src/locale by `make generate-error-code-message-map-ts`
+
 const ErrorGenreSummary = {
   SMIK: "SectionMetaInfo key",
   SMIV: "SectionMetaInfo value",
@@ -11,6 +12,11 @@ const ErrorGenreSummary = {
   TKN: "Token",
   BS: "Base",
   BL: "BreakLine",
+  TRP: "Transpose",
+  NAV: "Navigation mark",
+  INC: "Include",
+  DEF: "Chord macro definition",
+  SEM: "Semantic",
   OTHER: "Other",
 } as const;
 
@@ -37,6 +43,10 @@ export const ERROR_CODE_MESSAGE_MAP = {
       en: "SectionMetaInfoKey should not contains line break",
       ja: "セクションメタ情報のキーに改行を含めることはできません",
     },
+    "SMIK-3": {
+      en: "SectionMetaInfoKey is duplicated in the section",
+      ja: "セクションメタ情報のキーがセクション内で重複しています",
+    },
   },
   SMIV: {
     "SMIV-1": {
@@ -51,6 +61,30 @@ export const ERROR_CODE_MESSAGE_MAP = {
       en: "SectionMetaInfoValue of repeat needs to be number",
       ja: "セクションメタ情報の値のrepeatの値は数値である必要があります",
     },
+    "SMIV-4": {
+      en: "Section referenced by use is not found",
+      ja: "useで参照しているセクションが見つかりません",
+    },
+    "SMIV-5": {
+      en: "Section references are cyclic",
+      ja: "セクションの参照が循環しています",
+    },
+    "SMIV-6": {
+      en: "SectionMetaInfoValue of use needs to be a section name with an optional transposition",
+      ja: "useの値はセクション名（移調は任意）である必要があります",
+    },
+    "SMIV-7": {
+      en: "Section with use should not have its own chords",
+      ja: "useを指定したセクションには独自のコードを書けません",
+    },
+    "SMIV-8": {
+      en: "SectionMetaInfoValue does not match the registered validator",
+      ja: "セクションメタ情報の値が登録された検証ルールに一致しません",
+    },
+    "SMIV-9": {
+      en: "SectionMetaInfoValue of repeat should be 1 or more",
+      ja: "repeatの値は1以上である必要があります",
+    },
   },
   CIMK: {
     "CIMK-1": {
@@ -65,6 +99,22 @@ export const ERROR_CODE_MESSAGE_MAP = {
       en: "MetaInfoKey is invalid",
       ja: "コードメタ情報のキーが不正です",
     },
+    "CIMK-4": {
+      en: "MetaInfoKey needs a value",
+      ja: "コードメタ情報のキーには値が必要です",
+    },
+    "CIMK-5": {
+      en: "MetaInfoKey should not have a value",
+      ja: "コードメタ情報のキーに値は指定できません",
+    },
+    "CIMK-6": {
+      en: "MetaInfoKey is duplicated in the chord",
+      ja: "コードメタ情報のキーがコード内で重複しています",
+    },
+    "CIMK-7": {
+      en: "MetaInfo should be followed by a chord in the same section",
+      ja: "メタ情報の後には同じセクション内にコードが必要です",
+    },
   },
   CIMV: {
     "CIMV-1": {
@@ -83,6 +133,26 @@ export const ERROR_CODE_MESSAGE_MAP = {
       en: "MetaInfoValue is invalid",
       ja: "コードメタ情報の値が不正です",
     },
+    "CIMV-5": {
+      en: "MetaInfoValue of time should be a time signature",
+      ja: "timeの値は拍子である必要があります",
+    },
+    "CIMV-6": {
+      en: "MetaInfoValue of tempo should be BPM from 1 to 999",
+      ja: "tempoの値は1から999までのBPMである必要があります",
+    },
+    "CIMV-7": {
+      en: "MetaInfoValue of dynamics should be one of pp, p, mp, mf, f and ff",
+      ja: "dynamicsの値はpp、p、mp、mf、f、ffのいずれかである必要があります",
+    },
+    "CIMV-8": {
+      en: "MetaInfoValue of articulation should be one of accent, staccato, tenuto, marcato and stab",
+      ja: "articulationの値はaccent、staccato、tenuto、marcato、stabのいずれかである必要があります",
+    },
+    "CIMV-9": {
+      en: "MetaInfoValue does not match the registered validator",
+      ja: "コードメタ情報の値が登録された検証ルールに一致しません",
+    },
   },
   CHB: {
     "CHB-1": {
@@ -93,6 +163,14 @@ export const ERROR_CODE_MESSAGE_MAP = {
       en: "Bar should not contains line break",
       ja: "コードブロックに改行を含めることはできません",
     },
+    "CHB-3": {
+      en: "Bar has more chords than the beats of the time signature",
+      ja: "小節のコードの数が拍子の拍数より多いです",
+    },
+    "CHB-4": {
+      en: "Comma at the end of the bar is ignored",
+      ja: "小節末尾のカンマは無視されます",
+    },
   },
   CHO: {
     "CHO-1": {
@@ -107,6 +185,18 @@ export const ERROR_CODE_MESSAGE_MAP = {
       en: "Chord should not be empty",
       ja: "コードは空にできません",
     },
+    "CHO-4": {
+      en: "Polychord must be closed with }",
+      ja: "ポリコードは } で閉じる必要があります",
+    },
+    "CHO-5": {
+      en: "Polychord must have upper and lower chords separated by |",
+      ja: "ポリコードは上下のコードを | で区切る必要があります",
+    },
+    "CHO-6": {
+      en: "Extensions of polychord must be inside the braces",
+      ja: "ポリコードのテンションは波括弧の中に書く必要があります",
+    },
   },
   DEN: {
     "DEN-1": {
@@ -154,14 +244,139 @@ export const ERROR_CODE_MESSAGE_MAP = {
       ja: "連続した空行は許可されていません",
     },
   },
+  TRP: {
+    "TRP-1": {
+      en: "Key to transpose from is not specified",
+      ja: "移調元のキーが指定されていません",
+    },
+    "TRP-2": {
+      en: "Key to transpose to is invalid",
+      ja: "移調先のキーが不正です",
+    },
+    "TRP-3": {
+      en: "Key to transpose to is in a different mode from the key to transpose from",
+      ja: "移調先のキーの旋法が移調元のキーと異なります",
+    },
+  },
+  NAV: {
+    "NAV-1": {
+      en: "Invalid navigation mark",
+      ja: "ナビゲーション記号が不正です",
+    },
+    "NAV-2": {
+      en: "Repeat start |: is not closed with :|",
+      ja: "リピート開始 |: が :| で閉じられていません",
+    },
+    "NAV-3": {
+      en: "Repeat end :| has no matching |:",
+      ja: "リピート終了 :| に対応する |: がありません",
+    },
+    "NAV-4": {
+      en: "Repeats should not be nested",
+      ja: "リピートを入れ子にすることはできません",
+    },
+    "NAV-5": {
+      en: "<segno> is not found for D.S.",
+      ja: "D.S. に対応する <segno> がありません",
+    },
+    "NAV-6": {
+      en: "<coda> is not found for the jump to coda",
+      ja: "コーダへのジャンプに対応する <coda> がありません",
+    },
+    "NAV-7": {
+      en: "<fine> is not found for al Fine",
+      ja: "al Fine に対応する <fine> がありません",
+    },
+  },
+  INC: {
+    "INC-1": {
+      en: "Included file cannot be loaded",
+      ja: "インクルードするファイルを読み込めません",
+    },
+    "INC-2": {
+      en: "Includes are cyclic",
+      ja: "インクルードが循環しています",
+    },
+    "INC-3": {
+      en: "Section with include should not have other meta infos or chords",
+      ja: "includeを指定したセクションには他のメタ情報やコードを書けません",
+    },
+  },
+  DEF: {
+    "DEF-1": {
+      en: "Chord macro is not defined",
+      ja: "コードマクロが定義されていません",
+    },
+    "DEF-2": {
+      en: "Chord macro is already defined",
+      ja: "コードマクロが既に定義されています",
+    },
+    "DEF-3": {
+      en: "Chord macro should be defined as one chord",
+      ja: "コードマクロは1つのコードとして定義する必要があります",
+    },
+    "DEF-4": {
+      en: "Chord macro name is invalid",
+      ja: "コードマクロ名が不正です",
+    },
+  },
+  SEM: {
+    "SEM-1": {
+      en: "Key change has no effect because it is the current key",
+      ja: "現在のキーと同じため、キーの変更に効果がありません",
+    },
+    "SEM-2": {
+      en: "Key change is reverted at the next chord",
+      ja: "キーの変更が次のコードで元に戻っています",
+    },
+  },
   OTHER: {
     "OTHER-1": {
       en: "Unknown error",
       ja: "不明なエラーです",
     },
+    "OTHER-2": {
+      en: "Input is larger than the maximum size",
+      ja: "入力が最大サイズを超えています",
+    },
+    "OTHER-3": {
+      en: "Argument is invalid",
+      ja: "引数が不正です",
+    },
   },
 } as const satisfies UniqueErrorCodeAndMessageMap;
 
+export const LINT_RULE_MESSAGE_MAP = {
+  "bars-per-line": {
+    en: "Number of bars differs from the other lines of the section",
+    ja: "この行の小節数がセクション内の他の行と異なります",
+  },
+  "chord-out-of-key": {
+    en: "Root of the chord is not in the scale of the key",
+    ja: "コードのルートがキーのスケールにありません",
+  },
+  "unknown-chord": {
+    en: "Unidentified chord (?) is left",
+    ja: "未確定のコード(?)が残っています",
+  },
+  "redundant-same": {
+    en: "% repeating the chord in the same bar is redundant",
+    ja: "同じ小節のコードを繰り返す%は不要です",
+  },
+  "extension-order": {
+    en: "Extensions are not in the order of the degrees",
+    ja: "テンションが度数の順に並んでいません",
+  },
+  "unnamed-section": {
+    en: "Section has no name (@section)",
+    ja: "セクションに名前(@section)がありません",
+  },
+  "unreachable-volta": {
+    en: "Bars after the volta are never played",
+    ja: "このカッコ以降の小節は演奏されません",
+  },
+} as const;
+
 type ExtractKeys<T> = T extends T ? keyof T : never;
 
 export type ErrorCode = ExtractKeys<
@@ -178,3 +393,13 @@ export function getErrorMessage({
   const [genreName, _] = errorCode.split("-") as [ShortErrorGenre, string];
   return (ERROR_CODE_MESSAGE_MAP[genreName] as any)?.[errorCode]?.[lang];
 }
+
+export function getLintMessage({
+  rule,
+  lang,
+}: {
+  rule: keyof typeof LINT_RULE_MESSAGE_MAP;
+  lang: "en" | "ja";
+}): string | undefined {
+  return LINT_RULE_MESSAGE_MAP[rule]?.[lang];
+}
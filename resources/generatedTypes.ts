@@ -1,14 +1,26 @@
+import type { ErrorCode } from "./error_code_message_map";
+
 /*
  Generated by typeshare 1.9.2
 */
 
 export type SectionMeta = 
 	| { type: "section", value: string }
-	| { type: "repeat", value: number };
+	| { type: "repeat", value: number }
+	/** the chord blocks are cloned from the referenced section */
+	| { type: "use", value: SectionReference }
+	/** path of the file whose sections are included here */
+	| { type: "include", value: string }
+	/** meta info of a key registered in the `MetaRegistry`, like `@mood=calm` */
+	| { type: "custom", value: {
+	key: string;
+	value: string;
+}};
 
 export type ChordBlock = 
 	| { type: "bar", value: Bar }
-	| { type: "br", value?: undefined };
+	| { type: "br", value?: undefined }
+	| { type: "navigation", value: NavigationMark };
 
 export interface Section {
 	metaInfos: SectionMeta[];
@@ -18,10 +30,25 @@ export interface Section {
 export type Ast = Section[];
 
 export type ChordInfoMeta = 
-	| { type: "key", value: Key };
+	| { type: "key", value: Key }
+	/** carried forward to the following bars */
+	| { type: "time", value: TimeSignature }
+	/** BPM, like `[tempo=120]` */
+	| { type: "tempo", value: number }
+	| { type: "ritardando", value?: undefined }
+	| { type: "accelerando", value?: undefined }
+	| { type: "fermata", value?: undefined }
+	| { type: "dynamics", value: Dynamics }
+	| { type: "articulation", value: Articulation }
+	/** meta info of a key registered in the `MetaRegistry`, like `[capo=2]` */
+	| { type: "custom", value: {
+	key: string;
+	value: string;
+}};
 
 export type ChordExpression = 
 	| { type: "chord", value: Chord }
+	| { type: "polyChord", value: PolyChord }
 	| { type: "unIdentified", value?: undefined }
 	| { type: "noChord", value?: undefined }
 	| { type: "same", value?: undefined };
@@ -47,6 +74,9 @@ export enum Base {
 export enum Accidental {
 	Sharp = "#",
 	Flat = "b",
+	DoubleSharp = "##",
+	DoubleFlat = "bb",
+	Natural = "♮",
 }
 
 export enum ChordType {
@@ -54,18 +84,23 @@ export enum ChordType {
 	Major = "M",
 	Augmented = "aug",
 	Diminished = "dim",
+	/** power chord like `C5`, of the root and the fifth only */
+	Power = "5",
 }
 
 export enum Extension {
+	FlatTwo = "b2",
 	Two = "2",
 	Three = "3",
 	FlatThree = "b3",
 	Four = "4",
+	SharpFour = "#4",
 	FlatFive = "b5",
 	Five = "5",
 	SharpFive = "#5",
 	FlatSix = "b6",
 	Six = "6",
+	SixNine = "6/9",
 	Seven = "7",
 	FlatNine = "b9",
 	Nine = "9",
@@ -80,12 +115,17 @@ export enum Extension {
 	MajorNine = "M9",
 	MajorEleven = "M11",
 	MajorThirteen = "M13",
+	Add2 = "add2",
+	Add4 = "add4",
 	Add9 = "add9",
 	Add11 = "add11",
 	Add13 = "add13",
 	Sus2 = "sus2",
 	Sus4 = "sus4",
 	HalfDiminish = "o",
+	Altered = "alt",
+	Omit3 = "omit3",
+	Omit5 = "omit5",
 }
 
 export interface ChordDetailed {
@@ -100,45 +140,321 @@ export interface Chord {
 	detailed: ChordDetailed;
 }
 
-export enum Key {
-	Cb_M = "Cb",
-	Cb_m = "Cbm",
-	C_M = "C",
-	C_m = "Cm",
-	Cs_M = "C#",
-	Cs_m = "C#m",
-	Db_M = "Db",
-	Db_m = "Dbm",
-	D_M = "D",
-	D_m = "Dm",
-	Ds_M = "D#",
-	Ds_m = "D#m",
-	Eb_M = "Eb",
-	Eb_m = "Ebm",
-	E_M = "E",
-	E_m = "Em",
-	Fb_M = "E#",
-	Fb_m = "E#m",
-	F_M = "F",
-	F_m = "Fm",
-	Fs_M = "F#",
-	Fs_m = "F#m",
-	Gb_M = "Gb",
-	Gb_m = "Gbm",
-	G_M = "G",
-	G_m = "Gm",
-	Gs_M = "G#",
-	Gs_m = "G#m",
-	Ab_M = "Ab",
-	Ab_m = "Abm",
-	A_M = "A",
-	A_m = "Am",
-	As_M = "A#",
-	As_m = "A#m",
-	Bb_M = "Bb",
-	Bb_m = "Bbm",
-	B_M = "B",
-	B_m = "Bm",
-	UnIdentified = "?",
+export type Key = KeyName;
+
+/** Two chords played at the same time, written like `{D|C7}` (D triad over C7) */
+export interface PolyChord {
+	upper: Chord;
+	lower: Chord;
+}
+
+/** Marks which change the order the bars are played in, placed between bars */
+export type NavigationMark = 
+	| { type: "repeatStart", value?: undefined }
+	| { type: "repeatEnd", value?: undefined }
+	/** numbered ending, like `<1.>` or `<1.2.>`. the bars until the next mark are played in the passes. */
+	| { type: "volta", value: number[] }
+	| { type: "segno", value?: undefined }
+	| { type: "coda", value?: undefined }
+	| { type: "toCoda", value?: undefined }
+	| { type: "fine", value?: undefined }
+	| { type: "daCapo", value?: undefined }
+	| { type: "daCapoAlFine", value?: undefined }
+	| { type: "daCapoAlCoda", value?: undefined }
+	| { type: "dalSegno", value?: undefined }
+	| { type: "dalSegnoAlFine", value?: undefined }
+	| { type: "dalSegnoAlCoda", value?: undefined };
+
+/** Reference to another section, written like `@use=Verse` or `@use=Verse:+2` */
+export interface SectionReference {
+	/** name of the referenced section (the value of `@section`) */
+	name: string;
+	/** semitones to transpose the chords of the referenced section */
+	transpose: number;
+}
+
+/** Time signature, written like `[time=3/4]` */
+export interface TimeSignature {
+	/** number of beats in a bar (numerator) */
+	beats: number;
+	/** note value of a beat (denominator), like 4 for a quarter note */
+	beatType: number;
+}
+
+/** Dynamics, written like `[dynamics=mf]` */
+export enum Dynamics {
+	Pp = "pp",
+	P = "p",
+	Mp = "mp",
+	Mf = "mf",
+	F = "f",
+	Ff = "ff",
+}
+
+/** Articulation of the chord (a band hit), written like `[articulation=accent]` */
+export enum Articulation {
+	Accent = "accent",
+	Staccato = "staccato",
+	Tenuto = "tenuto",
+	Marcato = "marcato",
+	/** short and accented hit */
+	Stab = "stab",
+}
+
+/** Validator of the value of a custom meta info */
+export type MetaValueValidator = 
+	/** any value */
+	| { type: "string", value?: undefined }
+	/** integer, optionally limited to the range from `min` to `max` */
+	| { type: "integer", value: {
+	min?: number;
+	max?: number;
+}}
+	/** one of the values */
+	| { type: "enum", value: string[] }
+	/** regular expression which the whole value should match */
+	| { type: "regex", value: string };
+
+/**
+ * Custom meta keys declared by the host application, in addition to the built-in ones
+ *
+ * The values of the registered keys are checked by their validators,
+ * and appear in the AST as `SectionMeta::Custom` and `ChordInfoMeta::Custom`.
+ * The built-in keys (like `section` and `key`) can't be overridden.
+ */
+export interface MetaRegistry {
+	/** keys of `@key=value` */
+	sectionMeta?: Record<string, MetaValueValidator>;
+	/** keys of `[key=value]` */
+	chordMeta?: Record<string, MetaValueValidator>;
+}
+
+/** What to do with the meta infos of the same key in a section (like two `@repeat`) or before a chord (like `[key=C][key=D]`) */
+export enum DuplicateMetaPolicy {
+	/** keep all of them */
+	Allow = "allow",
+	KeepFirst = "keepFirst",
+	KeepLast = "keepLast",
+	/** `SMIK-3` for the section, `CIMK-6` for the chord (the default) */
+	Error = "error",
+}
+
+/** Options of the parser. The default is strict, like the editor, and each option makes it more lenient. */
+export interface ParseOptions {
+	/** allow more than one blank line (or line of whitespaces) in a row, instead of `BL-1` */
+	allowConsecutiveBlankLines?: boolean;
+	/** allow `%` at the beginning of a section, repeating the last chord of the previous section, instead of `CHB-1` */
+	allowSameAtSectionStart?: boolean;
+	/** chord qualities read as other ones, like `"ma7": "M7"` for `Cma7`. the AST has the chord written with the replacement. */
+	chordAliases?: Record<string, string>;
+	/** read the section meta keys in lower case, like `@Section=A` */
+	caseInsensitiveSectionKeys?: boolean;
+	duplicateMeta?: DuplicateMetaPolicy;
+	/** maximum size of the input in bytes, `OTHER-2` if larger */
+	maxInputSize?: number;
+	/** custom meta keys */
+	registry?: MetaRegistry;
+}
+
+/** How serious a diagnostic is. Only the errors stop parsing. */
+export enum Severity {
+	Error = "error",
+	Warning = "warning",
+	Info = "info",
+	Hint = "hint",
+}
+
+/** Rules of the linter, identified by the kebab-case names (like `unknown-chord`) */
+export enum LintRule {
+	/** a line has a different number of bars from the other lines of the section */
+	BarsPerLine = "bars-per-line",
+	/** the root of the chord is not in the scale of the key (`[key=...]`) */
+	ChordOutOfKey = "chord-out-of-key",
+	/** `?` is left in the chord progression */
+	UnknownChord = "unknown-chord",
+	/** `%` repeats the chord in the same bar, so the chord can be written once */
+	RedundantSame = "redundant-same",
+	/** the extensions in parenthesis are not in the order of the degrees, like `C7(#11,9)` */
+	ExtensionOrder = "extension-order",
+	/** a section has no `@section`, while the chord progression has more than one section */
+	UnnamedSection = "unnamed-section",
+	/** the bars after the volta (like `<3.>`) are never played */
+	UnreachableVolta = "unreachable-volta",
+}
+
+/** Which rules are checked, and with which severities */
+export interface LintConfig {
+	/** rules not checked */
+	disabled?: LintRule[];
+	/** severities overriding the defaults of the rules */
+	severities?: Record<LintRule, Severity>;
+}
+
+/** Position in the input. The line and column numbers start from 1, and the column is counted in characters. */
+export interface Position {
+	lineNumber: number;
+	columnNumber: number;
+	length: number;
+}
+
+/** Token of the chord progression string, serialized with the names of the variants as the kinds, like `{ "type": "Chord", "value": "C" }` */
+export type Token = 
+	| { type: "Equal", value?: undefined }
+	| { type: "Comma", value?: undefined }
+	| { type: "LineBreak", value?: undefined }
+	| { type: "Slash", value?: undefined }
+	| { type: "SectionMetaInfoStart", value?: undefined }
+	| { type: "SectionMetaInfoKey", value: string }
+	| { type: "SectionMetaInfoName", value: string }
+	| { type: "SectionMetaInfoValue", value: string }
+	| { type: "ChordBlockSeparator", value?: undefined }
+	| { type: "Chord", value: string }
+	| { type: "Denominator", value: string }
+	| { type: "MetaInfoStart", value?: undefined }
+	| { type: "MetaInfoEnd", value?: undefined }
+	| { type: "MetaInfoKey", value: string }
+	| { type: "MetaInfoValue", value: string }
+	| { type: "ExtensionStart", value?: undefined }
+	| { type: "ExtensionEnd", value?: undefined }
+	| { type: "Extension", value: string }
+	| { type: "RepeatStart", value?: undefined }
+	| { type: "RepeatEnd", value?: undefined }
+	| { type: "NavigationMark", value: string };
+
+export interface TokenWithPosition {
+	token: Token;
+	position: Position;
+}
+
+/**
+ * Types of the semantic tokens, named as the standard ones of LSP.
+ * The index of a type in the legend is its order here.
+ */
+export enum SemanticTokenType {
+	/** `@` and the key of the section meta info, like `@section` */
+	Keyword = "keyword",
+	/** name of `@def`, like `X` of `@def X=C` */
+	Macro = "macro",
+	/** values of the meta infos */
+	String = "string",
+	/** chords and denominators */
+	Type = "type",
+	/** extensions, like `7` of `C(7)` */
+	Number = "number",
+	/** keys of the chord meta infos, like `key` of `[key=C]` */
+	Property = "property",
+	/** `=`, `,`, `/`, `-` and the brackets */
+	Operator = "operator",
+	/** repeats and navigation marks, like `|:` and `<segno>` */
+	Decorator = "decorator",
+	/** `// ...` to the end of the line */
+	Comment = "comment",
+}
+
+/**
+ * Token for highlighting, in the coordinates of LSP:
+ * the line starts from 0, and the start and length are in UTF-16 code units.
+ */
+export interface SemanticToken {
+	line: number;
+	start: number;
+	length: number;
+	tokenType: SemanticTokenType;
+}
+
+/** A warning of the valid input, which doesn't stop parsing */
+export interface Diagnostic {
+	severity: Severity;
+	code: ErrorCode;
+	additionalInfo?: string;
+	/** the file the warning is in (only with parseChordProgressionStringWithFiles or parseChordProgressionStringWithLoader) */
+	fileName?: string | null;
+	position: Position;
+}
+
+/** Range of the indexes, with the end excluded */
+export interface IndexRange {
+	start: number;
+	end: number;
+}
+
+/** What an edit changed */
+export interface EditChanges {
+	/** indexes of the sections of the new AST which differ from the previous one */
+	changedSections: IndexRange;
+	/** line numbers of the new input which are re-parsed, starting from 1 */
+	changedLines: IndexRange;
+}
+
+/** The error which stops parsing */
+export interface ErrorResult {
+	code: ErrorCode;
+	additionalInfo?: string;
+	/** inner error which caused this one, like `BS-1` of the invalid chord (`CHO-1`) */
+	cause?: ErrorCode;
+	/** corrections of the input, like the chord names close to the wrong one */
+	suggestions: string[];
+	/** the included file the error is in (only with parseChordProgressionStringWithFiles or parseChordProgressionStringWithLoader) */
+	fileName?: string | null;
+	position: Position;
+}
+
+export interface Highlighted {
+	/** in the order of the positions */
+	tokens: SemanticToken[];
+	/** `tokens` encoded as `data` of the semantic tokens of LSP (`new Uint32Array(data)` for Monaco) */
+	data: number[];
+}
+
+/** A warning of the lint rule, with the message in the language asked for */
+export interface LintWarning {
+	rule: LintRule;
+	severity: Severity;
+	message: string;
+	additionalInfo?: string;
+	position: Position;
+}
+
+/** The parsed AST with its warnings */
+export interface Parsed {
+	ast: Ast;
+	/** in the order of the positions (always empty with the included files) */
+	warnings: Diagnostic[];
+}
+
+/** Same as `SemanticTokensLegend` of LSP and Monaco */
+export interface SemanticTokensLegend {
+	tokenTypes: string[];
+	tokenModifiers: string[];
+}
+
+export interface Tokenized {
+	tokens: TokenWithPosition[];
+}
+
+export interface Transposed {
+	ast: Ast;
+	/** the transposed chord progression string */
+	output: string;
+}
+
+/** The warnings only, without the AST */
+export interface Warnings<T> {
+	/** in the order of the positions */
+	warnings: T[];
 }
 
+/**
+ * Key serialized as the string, like `C`, `F#m`, `Ddorian` or `?`.
+ * Appended to generatedTypes.ts by `make generate-ts-types`, as typeshare can't generate template literal types.
+ */
+export type KeyName =
+	| `${"C" | "D" | "E" | "F" | "G" | "A" | "B"}${"" | "#" | "b"}${
+			| ""
+			| "m"
+			| "dorian"
+			| "phrygian"
+			| "lydian"
+			| "mixolydian"
+			| "locrian"}`
+	| "?";
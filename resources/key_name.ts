@@ -0,0 +1,15 @@
+
+/**
+ * Key serialized as the string, like `C`, `F#m`, `Ddorian` or `?`.
+ * Appended to generatedTypes.ts by `make generate-ts-types`, as typeshare can't generate template literal types.
+ */
+export type KeyName =
+	| `${"C" | "D" | "E" | "F" | "G" | "A" | "B"}${"" | "#" | "b"}${
+			| ""
+			| "m"
+			| "dorian"
+			| "phrygian"
+			| "lydian"
+			| "mixolydian"
+			| "locrian"}`
+	| "?";
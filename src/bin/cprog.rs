@@ -0,0 +1,451 @@
+use std::io::{IsTerminal, Read, Write};
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use chord_progression_parser::diagnostic::{render, RenderOptions};
+use chord_progression_parser::error_code::ErrorInfoWithPosition;
+use chord_progression_parser::exporter::{export, ExportFormat};
+use chord_progression_parser::include::{
+    parse_with_includes, parse_with_includes_and_trivia, FileSystemLoader, SourceError,
+};
+use chord_progression_parser::parse_chord_progression_string;
+use chord_progression_parser::parser::types::key::Key;
+use chord_progression_parser::stringifier::trivia::{collect_trivia, Trivia};
+use chord_progression_parser::stringifier::{stringify_with_trivia, StringifyOptions};
+use chord_progression_parser::tokenizer::lexer::Lexer;
+use chord_progression_parser::tokenizer::types::lexeme::Lexeme;
+use chord_progression_parser::tokenizer::types::token::TokenKind;
+use chord_progression_parser::transposer::{interval_to_key, transpose, transpose_trivia};
+use chord_progression_parser::util::position::Position;
+
+const USAGE: &str = "Usage: cprog <COMMAND> [OPTIONS] [FILES...]
+
+Commands:
+  check                          Exit with non-zero status if any file has errors
+  json                           Print the AST as JSON
+  fmt [--check]                  Format files in place (or check they are formatted)
+  transpose --to <KEY>           Print the chord progression transposed to KEY
+  export --format <FORMAT>       Print the chord progression as midi, musicxml or chordpro
+         [--output <PATH>]       (or write it to PATH, for one input file)
+
+Options:
+  --lang <LANG>                  Language of error messages (en, ja) [default: en]
+  --no-color                     Do not use colors in error messages
+
+Reads from stdin when no FILES (or \"-\") are given.";
+
+/// exit status when some input has errors or is not formatted
+const EXIT_FAILURE: u8 = 1;
+/// exit status when the command line is invalid
+const EXIT_USAGE: u8 = 2;
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Check,
+    Json,
+    Fmt {
+        check: bool,
+    },
+    Transpose {
+        to: Key,
+    },
+    Export {
+        format: ExportFormat,
+        output: Option<String>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    command: Command,
+    files: Vec<String>,
+    lang: String,
+    color: bool,
+}
+
+struct Input {
+    name: String,
+    path: Option<String>,
+    content: String,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut args = args.iter();
+    let command_name = args.next().ok_or("no command is given")?;
+
+    let mut check = false;
+    let mut to: Option<String> = None;
+    let mut format: Option<String> = None;
+    let mut output: Option<String> = None;
+    let mut lang = "en".to_string();
+    let mut color = true;
+    let mut files: Vec<String> = vec![];
+    // the options of the commands, which are checked against the command
+    let mut command_options: Vec<&str> = vec![];
+
+    while let Some(arg) = args.next() {
+        if let "--check" | "--to" | "--format" | "--output" | "-o" = arg.as_str() {
+            command_options.push(arg);
+        }
+        match arg.as_str() {
+            "--check" => check = true,
+            "--lang" => lang = args.next().ok_or("--lang needs a language")?.clone(),
+            "--no-color" => color = false,
+            "--to" => to = Some(args.next().ok_or("--to needs a key")?.clone()),
+            "--format" => format = Some(args.next().ok_or("--format needs a format")?.clone()),
+            "--output" | "-o" => output = Some(args.next().ok_or("--output needs a path")?.clone()),
+            "-" => files.push(arg.clone()),
+            option if option.starts_with('-') => return Err(format!("unknown option: {}", option)),
+            file => files.push(file.to_string()),
+        }
+    }
+
+    let command = match command_name.as_str() {
+        "check" => Command::Check,
+        "json" => Command::Json,
+        "fmt" => Command::Fmt { check },
+        "transpose" => {
+            let to = to.ok_or("transpose needs --to <KEY>")?;
+            Command::Transpose {
+                to: Key::from_str(&to).map_err(|_| format!("invalid key: {}", to))?,
+            }
+        }
+        "export" => {
+            let format = format.ok_or("export needs --format <FORMAT>")?;
+            // each input would overwrite the output of the previous one
+            if output.is_some() && files.len() > 1 {
+                return Err("--output needs one input file".to_string());
+            }
+            Command::Export {
+                format: ExportFormat::from_str(&format)
+                    .map_err(|_| format!("invalid format: {}", format))?,
+                output,
+            }
+        }
+        command => return Err(format!("unknown command: {}", command)),
+    };
+    let allowed_options: &[&str] = match command {
+        Command::Check | Command::Json => &[],
+        Command::Fmt { .. } => &["--check"],
+        Command::Transpose { .. } => &["--to"],
+        Command::Export { .. } => &["--format", "--output", "-o"],
+    };
+    if let Some(option) = command_options
+        .iter()
+        .find(|option| !allowed_options.contains(option))
+    {
+        return Err(format!("{} is not an option of {}", option, command_name));
+    }
+
+    Ok(Args {
+        command,
+        files,
+        lang,
+        color,
+    })
+}
+
+fn read_inputs(files: &[String]) -> Result<Vec<Input>, String> {
+    if files.is_empty() {
+        return read_inputs(&["-".to_string()]);
+    }
+
+    files
+        .iter()
+        .map(|file| {
+            if file == "-" {
+                let mut content = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut content)
+                    .map_err(|e| format!("<stdin>: {}", e))?;
+                Ok(Input {
+                    name: "<stdin>".to_string(),
+                    path: None,
+                    content,
+                })
+            } else {
+                let content =
+                    std::fs::read_to_string(file).map_err(|e| format!("{}: {}", file, e))?;
+                Ok(Input {
+                    name: file.clone(),
+                    path: Some(file.clone()),
+                    content,
+                })
+            }
+        })
+        .collect()
+}
+
+fn write_stdout(bytes: &[u8]) -> Result<(), String> {
+    std::io::stdout()
+        .write_all(bytes)
+        .map_err(|e| format!("<stdout>: {}", e))
+}
+
+fn render_options(args: &Args, file_name: String) -> RenderOptions {
+    RenderOptions {
+        lang: args.lang.clone(),
+        color: args.color && std::io::stderr().is_terminal(),
+        file_name: Some(file_name),
+    }
+}
+
+/// position of the first key (like `D` of `[key=D]`) which the input is transposed from,
+/// or of the first chord if the input has no key (like the one with the key in an included file)
+fn first_key_position(source: &str) -> Position {
+    let lexemes: Vec<Lexeme> = Lexer::new(source).map_while(Result::ok).collect();
+
+    lexemes
+        .windows(3)
+        .find(|lexemes| {
+            lexemes[0].kind == TokenKind::MetaInfoKey
+                && lexemes[0].text == "key"
+                && lexemes[2].kind == TokenKind::MetaInfoValue
+        })
+        .map(|lexemes| lexemes[2].position())
+        .or_else(|| {
+            lexemes
+                .iter()
+                .find(|lexeme| lexeme.kind == TokenKind::Chord)
+                .map(Lexeme::position)
+        })
+        .unwrap_or(Position {
+            line_number: 1,
+            column_number: 1,
+            length: 0,
+        })
+}
+
+fn run(args: Args) -> Result<bool, String> {
+    let inputs = read_inputs(&args.files)?;
+    let mut is_success = true;
+
+    for input in inputs.iter() {
+        let root_error = |error_info| SourceError {
+            file_name: None,
+            source: input.content.clone(),
+            error_info,
+        };
+        // fmt keeps `@include` as written, and the others read the included files.
+        // fmt and transpose write the input back with its trivia (like the comments and the chord macros).
+        let result = match &args.command {
+            Command::Fmt { .. } => parse_chord_progression_string(&input.content)
+                .and_then(|ast| Ok((ast, collect_trivia(&input.content)?)))
+                .map_err(root_error),
+            Command::Transpose { .. } => parse_with_includes_and_trivia(
+                &input.content,
+                input.path.as_deref(),
+                &FileSystemLoader,
+            ),
+            _ => parse_with_includes(&input.content, input.path.as_deref(), &FileSystemLoader)
+                .map(|ast| (ast, Trivia::default())),
+        };
+        let (ast, trivia) = match result {
+            Ok(ast_and_trivia) => ast_and_trivia,
+            Err(source_error) => {
+                let options =
+                    render_options(&args, source_error.file_name.unwrap_or(input.name.clone()));
+                eprintln!(
+                    "{}\n",
+                    render(&source_error.source, &source_error.error_info, &options)
+                );
+                is_success = false;
+                continue;
+            }
+        };
+
+        match &args.command {
+            Command::Check => {}
+            Command::Json => {
+                let json = serde_json::to_string_pretty(&ast).map_err(|e| e.to_string())?;
+                println!("{}", json);
+            }
+            Command::Fmt { check } => {
+                let formatted = stringify_with_trivia(&ast, &trivia, &StringifyOptions::default());
+                match (&input.path, check) {
+                    (_, true) => {
+                        if formatted != input.content {
+                            eprintln!("{}: not formatted", input.name);
+                            is_success = false;
+                        }
+                    }
+                    (Some(path), false) => {
+                        if formatted != input.content {
+                            std::fs::write(path, formatted)
+                                .map_err(|e| format!("{}: {}", path, e))?;
+                        }
+                    }
+                    (None, false) => write_stdout(formatted.as_bytes())?,
+                }
+            }
+            Command::Transpose { to } => match interval_to_key(&ast, to) {
+                Ok((semitones, spelling)) => write_stdout(
+                    stringify_with_trivia(
+                        &transpose(&ast, semitones, spelling),
+                        &transpose_trivia(&trivia, semitones, spelling),
+                        &StringifyOptions::default(),
+                    )
+                    .as_bytes(),
+                )?,
+                Err(error) => {
+                    let error_info = ErrorInfoWithPosition {
+                        error,
+                        position: first_key_position(&input.content),
+                    };
+                    let options = render_options(&args, input.name.clone());
+                    eprintln!("{}\n", render(&input.content, &error_info, &options));
+                    is_success = false;
+                }
+            },
+            Command::Export { format, output } => {
+                let exported = export(&ast, format);
+                match output {
+                    Some(path) => {
+                        std::fs::write(path, exported).map_err(|e| format!("{}: {}", path, e))?
+                    }
+                    None => write_stdout(&exported)?,
+                }
+            }
+        }
+    }
+
+    Ok(is_success)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let args = match parse_args(&args) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    match run(args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(EXIT_FAILURE),
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::from(EXIT_FAILURE)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chord_progression_parser::parser::types::{accidental::Accidental, base::Base};
+
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn check_with_files() {
+        assert_eq!(
+            parse_args(&to_args(&["check", "a.txt", "b.txt"])),
+            Ok(Args {
+                command: Command::Check,
+                files: to_args(&["a.txt", "b.txt"]),
+                lang: "en".to_string(),
+                color: true,
+            })
+        );
+    }
+
+    #[test]
+    fn fmt_check() {
+        assert_eq!(
+            parse_args(&to_args(&["fmt", "--check", "-"])),
+            Ok(Args {
+                command: Command::Fmt { check: true },
+                files: to_args(&["-"]),
+                lang: "en".to_string(),
+                color: true,
+            })
+        );
+    }
+
+    #[test]
+    fn transpose_to_key() {
+        assert_eq!(
+            parse_args(&to_args(&["transpose", "--to", "Bb", "a.txt"])),
+            Ok(Args {
+                command: Command::Transpose {
+                    to: Key::major(Base::B, Some(Accidental::Flat))
+                },
+                files: to_args(&["a.txt"]),
+                lang: "en".to_string(),
+                color: true,
+            })
+        );
+        assert!(parse_args(&to_args(&["transpose", "--to", "H"])).is_err());
+        assert!(parse_args(&to_args(&["transpose"])).is_err());
+    }
+
+    #[test]
+    fn export_format() {
+        assert_eq!(
+            parse_args(&to_args(&["export", "--format", "midi", "-o", "a.mid"])),
+            Ok(Args {
+                command: Command::Export {
+                    format: ExportFormat::Midi,
+                    output: Some("a.mid".to_string()),
+                },
+                files: vec![],
+                lang: "en".to_string(),
+                color: true,
+            })
+        );
+        assert!(parse_args(&to_args(&["export", "--format", "pdf"])).is_err());
+        assert!(parse_args(&to_args(&[
+            "export", "--format", "midi", "-o", "a.mid", "a.txt", "b.txt"
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn lang_and_color() {
+        assert_eq!(
+            parse_args(&to_args(&["check", "--lang", "ja", "--no-color"])),
+            Ok(Args {
+                command: Command::Check,
+                files: vec![],
+                lang: "ja".to_string(),
+                color: false,
+            })
+        );
+    }
+
+    #[test]
+    fn position_of_the_key_to_transpose_from() {
+        assert_eq!(
+            first_key_position("@section=A\nC - [time=3/4][key=Am]Am"),
+            Position {
+                line_number: 2,
+                column_number: 20,
+                length: 2,
+            }
+        );
+        assert_eq!(first_key_position("@section=A\nC - G").column_number, 1);
+        assert_eq!(first_key_position("").length, 0);
+    }
+
+    #[test]
+    fn unknown_command_and_option() {
+        assert!(parse_args(&to_args(&["lint"])).is_err());
+        assert!(parse_args(&to_args(&["check", "--verbose"])).is_err());
+        assert!(parse_args(&to_args(&["check", "--to", "C"])).is_err());
+        assert!(parse_args(&to_args(&["check", "--format", "midi"])).is_err());
+        assert!(parse_args(&to_args(&["export", "--format", "midi", "--check"])).is_err());
+        assert!(parse_args(&to_args(&["fmt", "-o", "a.txt"])).is_err());
+        assert!(parse_args(&[]).is_err());
+    }
+}
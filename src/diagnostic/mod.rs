@@ -0,0 +1,388 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+use typeshare::typeshare;
+use unicode_width::UnicodeWidthChar;
+
+use crate::error_code::{ErrorCode, ErrorInfo, ErrorInfoWithPosition};
+use crate::locale;
+use crate::suggestion::suggestions;
+use crate::util::position::Position;
+
+const RESET: &str = "\x1b[0m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const BOLD_YELLOW: &str = "\x1b[1;33m";
+const BOLD_BLUE: &str = "\x1b[1;34m";
+const BOLD_CYAN: &str = "\x1b[1;36m";
+const BOLD: &str = "\x1b[1m";
+
+/// How serious a diagnostic is. Only the errors stop parsing.
+#[typeshare]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Display, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+/// An error or a warning of the input with its severity.
+/// The warnings are returned with the AST, like the trailing comma of `C/D,` which is ignored (`CHB-4`).
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub error: ErrorInfo,
+    pub position: Position,
+}
+
+/// with the severity of the code (see [`ErrorCode::severity`])
+impl From<ErrorInfoWithPosition> for Diagnostic {
+    fn from(error_info: ErrorInfoWithPosition) -> Self {
+        Diagnostic {
+            severity: error_info.error.code.severity(),
+            error: error_info.error,
+            position: error_info.position,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RenderOptions {
+    /// "en", "ja" or a locale registered with [`crate::locale::register_locale`].
+    /// Unknown languages fall back to "en".
+    pub lang: String,
+    /// use ANSI colors
+    pub color: bool,
+    /// shown before the line number, like "song.txt:1:5"
+    pub file_name: Option<String>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            lang: "en".to_string(),
+            color: false,
+            file_name: None,
+        }
+    }
+}
+
+/// localized message of the error code
+pub fn message(code: &ErrorCode, lang: &str) -> String {
+    code.message(lang)
+}
+
+/// localized hint to fix the error, in the catalogue of [`crate::locale`]
+pub fn hint(code: &ErrorCode, lang: &str) -> Option<String> {
+    locale::hint(code, lang)
+}
+
+/// localized "did you mean" message, like "did you mean `M7` or `M9`?"
+fn did_you_mean(suggestions: &[String], lang: &str) -> String {
+    let quoted: Vec<String> = suggestions.iter().map(|s| format!("`{}`", s)).collect();
+
+    match (lang, quoted.split_last()) {
+        ("ja", _) => format!("{} のことですか？", quoted.join("、")),
+        (_, Some((last, rest))) if !rest.is_empty() => {
+            format!("did you mean {} or {}?", rest.join(", "), last)
+        }
+        _ => format!("did you mean {}?", quoted.join("")),
+    }
+}
+
+/// Render the error with the offending line and a caret under it
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::diagnostic::{render, RenderOptions};
+/// use chord_progression_parser::parse_chord_progression_string;
+///
+/// let input = "C(9,111)";
+/// let error_info = parse_chord_progression_string(input).unwrap_err();
+/// println!("{}", render(input, &error_info, &RenderOptions::default()));
+/// // error[EXT-1]: Invalid extension: 111
+/// //  --> 1:5
+/// //   |
+/// // 1 | C(9,111)
+/// //   |     ^^^
+/// //   |
+/// //   = help: write extensions like `7`, `b9`, `M7`, `add9` or `sus4`
+/// ```
+pub fn render(source: &str, error_info: &ErrorInfoWithPosition, options: &RenderOptions) -> String {
+    render_with_severity(
+        source,
+        Severity::Error,
+        &error_info.error,
+        &error_info.position,
+        options,
+    )
+}
+
+/// Render the diagnostic like [`render`], labeled with its severity (like `warning[CHB-4]`)
+pub fn render_diagnostic(source: &str, diagnostic: &Diagnostic, options: &RenderOptions) -> String {
+    render_with_severity(
+        source,
+        diagnostic.severity,
+        &diagnostic.error,
+        &diagnostic.position,
+        options,
+    )
+}
+
+fn render_with_severity(
+    source: &str,
+    severity: Severity,
+    error: &ErrorInfo,
+    position: &Position,
+    options: &RenderOptions,
+) -> String {
+    let paint = |style: &str, text: &str| -> String {
+        if options.color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    };
+
+    let code = &error.code;
+    let title = match &error.additional_info {
+        Some(info) => format!("{}: {}", message(code, &options.lang), info),
+        None => message(code, &options.lang),
+    };
+
+    let line = source
+        .split('\n')
+        .nth(position.line_number.saturating_sub(1))
+        .unwrap_or("")
+        .trim_end_matches('\r');
+    let line_number = position.line_number.to_string();
+    let gutter = " ".repeat(line_number.len());
+    let bar = paint(BOLD_BLUE, "|");
+
+    // measured in the display width, so that full-width characters get 2 columns,
+    // and tabs are kept so that the caret is aligned with the line
+    let width = |c: char| c.width().unwrap_or(0);
+    let padding: String = line
+        .chars()
+        .take(position.column_number.saturating_sub(1))
+        .map(|c| {
+            if c == '\t' {
+                "\t".to_string()
+            } else {
+                " ".repeat(width(c))
+            }
+        })
+        .collect();
+    // the length of the position is in bytes
+    let mut length = 0;
+    let caret_width: usize = line
+        .chars()
+        .skip(position.column_number.saturating_sub(1))
+        .take_while(|c| {
+            length += c.len_utf8();
+            length <= position.length
+        })
+        .map(width)
+        .sum();
+    let caret = "^".repeat(caret_width.max(1));
+
+    let location = match &options.file_name {
+        Some(file_name) => format!(
+            "{}:{}:{}",
+            file_name, position.line_number, position.column_number
+        ),
+        None => format!("{}:{}", position.line_number, position.column_number),
+    };
+
+    let style = match severity {
+        Severity::Error => BOLD_RED,
+        Severity::Warning => BOLD_YELLOW,
+        Severity::Info | Severity::Hint => BOLD_CYAN,
+    };
+
+    let mut lines = vec![
+        format!(
+            "{}{}",
+            paint(style, &format!("{}[{}]", severity, code)),
+            paint(BOLD, &format!(": {}", title))
+        ),
+        format!("{}{} {}", gutter, paint(BOLD_BLUE, "-->"), location),
+        format!("{} {}", gutter, bar),
+        format!("{} {} {}", paint(BOLD_BLUE, &line_number), bar, line),
+        format!("{} {} {}{}", gutter, bar, padding, paint(style, &caret)),
+    ];
+
+    let mut helps: Vec<String> = vec![];
+    let suggestions = suggestions(error);
+    if !suggestions.is_empty() {
+        helps.push(did_you_mean(&suggestions, &options.lang));
+    }
+    if let Some(hint) = hint(code, &options.lang) {
+        helps.push(hint);
+    }
+
+    if !helps.is_empty() {
+        lines.push(format!("{} {}", gutter, bar));
+    }
+    for help in helps {
+        lines.push(format!(
+            "{} {} {}",
+            gutter,
+            paint(BOLD_BLUE, "="),
+            paint(BOLD_CYAN, &format!("help: {}", help))
+        ));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_code::ErrorInfo;
+    use crate::parser::options::ParseOptions;
+    use crate::util::position::Position;
+    use crate::{parse_chord_progression_string, parse_chord_progression_string_with_warnings};
+
+    #[test]
+    fn render_plain_text() {
+        let input = "@section=A\nC(9,111)";
+        let error_info = parse_chord_progression_string(input).unwrap_err();
+
+        assert_eq!(
+            render(input, &error_info, &RenderOptions::default()),
+            "error[EXT-1]: Invalid extension: 111
+ --> 2:5
+  |
+2 | C(9,111)
+  |     ^^^
+  |
+  = help: did you mean `b11`, `11` or `#11`?
+  = help: write extensions like `7`, `b9`, `M7`, `add9` or `sus4`"
+        );
+    }
+
+    #[test]
+    fn render_warning() {
+        let input = "C/D,";
+        let (_, warnings) =
+            parse_chord_progression_string_with_warnings(input, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            render_diagnostic(input, &warnings[0], &RenderOptions::default()),
+            "warning[CHB-4]: Comma at the end of the bar is ignored
+ --> 1:4
+  |
+1 | C/D,
+  |    ^
+  |
+  = help: remove the trailing `,`, or write the chord after it"
+        );
+    }
+
+    #[test]
+    fn render_full_width_characters() {
+        let caret_line = |input: &str| {
+            let error_info = parse_chord_progression_string(input).unwrap_err();
+            render(input, &error_info, &RenderOptions::default())
+                .lines()
+                .nth(4)
+                .unwrap()
+                .to_string()
+        };
+
+        // "ｘ" is 3 bytes and 2 columns wide
+        assert_eq!(caret_line("ｘ - C"), "  | ^^");
+        // "Ｘ" before the error shifts the caret by 2 columns
+        assert_eq!(caret_line("@def Ｘ=C\n$Ｘ - C(ｙ)"), "  |         ^^");
+    }
+
+    #[test]
+    fn render_japanese_with_file_name() {
+        let input = "C(9,111)";
+        let error_info = parse_chord_progression_string(input).unwrap_err();
+        let options = RenderOptions {
+            lang: "ja".to_string(),
+            color: false,
+            file_name: Some("song.txt".to_string()),
+        };
+
+        assert_eq!(
+            render(input, &error_info, &options),
+            "error[EXT-1]: テンションが不正です: 111
+ --> song.txt:1:5
+  |
+1 | C(9,111)
+  |     ^^^
+  |
+  = help: `b11`、`11`、`#11` のことですか？
+  = help: テンションは `7`、`b9`、`M7`、`add9`、`sus4` のように書いてください"
+        );
+    }
+
+    #[test]
+    fn render_with_suggestions() {
+        let input = "C(dom7)";
+        let error_info = parse_chord_progression_string(input).unwrap_err();
+
+        assert_eq!(
+            render(input, &error_info, &RenderOptions::default()),
+            "error[EXT-1]: Invalid extension: dom7
+ --> 1:3
+  |
+1 | C(dom7)
+  |   ^^^^
+  |
+  = help: did you mean `7`?
+  = help: write extensions like `7`, `b9`, `M7`, `add9` or `sus4`"
+        );
+
+        let options = RenderOptions {
+            lang: "ja".to_string(),
+            ..RenderOptions::default()
+        };
+        assert!(render(input, &error_info, &options).contains("= help: `7` のことですか？"));
+    }
+
+    #[test]
+    fn render_with_color() {
+        let input = "C(9,111)";
+        let error_info = parse_chord_progression_string(input).unwrap_err();
+        let options = RenderOptions {
+            color: true,
+            ..RenderOptions::default()
+        };
+        let rendered = render(input, &error_info, &options);
+
+        assert!(rendered.starts_with("\x1b[1;31merror[EXT-1]\x1b[0m"));
+        assert!(rendered.contains("\x1b[1;31m^^^\x1b[0m"));
+        assert!(rendered.contains("\x1b[1;36mhelp: "));
+    }
+
+    #[test]
+    fn caret_is_aligned_with_tab_and_clamped_to_line() {
+        let input = "\tC(9,111)";
+        let error_info = ErrorInfoWithPosition {
+            error: ErrorInfo {
+                code: ErrorCode::Tkn1,
+                additional_info: None,
+                cause: None,
+            },
+            position: Position {
+                line_number: 1,
+                column_number: 6,
+                length: 10,
+            },
+        };
+
+        assert_eq!(
+            render(input, &error_info, &RenderOptions::default()),
+            "error[TKN-1]: Invalid token type
+ --> 1:6
+  |
+1 | \tC(9,111)
+  | \t    ^^^^"
+        );
+    }
+}
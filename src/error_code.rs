@@ -1,207 +1,242 @@
-// NOTE: Do not edit this file manually, it is generated by error_code_message_map.util.ts
-use strum_macros::{Display, EnumString};
+use serde::{Serialize, Serializer};
+use strum_macros::{Display, EnumIter, EnumString};
 
+use crate::diagnostic::Severity;
+use crate::locale;
 use crate::util::position::Position;
 
-#[derive(Debug, Display, PartialEq, EnumString)]
+/// short name of each error genre (the prefix of the error code) and its summary
+pub const ERROR_GENRES: [(&str, &str); 17] = [
+    ("SMIK", "SectionMetaInfo key"),
+    ("SMIV", "SectionMetaInfo value"),
+    ("CIMK", "ChordInfoMeta key"),
+    ("CIMV", "ChordInfoMeta value"),
+    ("CHB", "ChordBlock"),
+    ("CHO", "Chord"),
+    ("DEN", "Denominator"),
+    ("EXT", "Extension"),
+    ("TKN", "Token"),
+    ("BS", "Base"),
+    ("BL", "BreakLine"),
+    ("TRP", "Transpose"),
+    ("NAV", "Navigation mark"),
+    ("INC", "Include"),
+    ("DEF", "Chord macro definition"),
+    ("SEM", "Semantic"),
+    ("OTHER", "Other"),
+];
+
+/// Messages of each code are in the catalogue of [`crate::locale`]
+#[derive(Debug, Display, PartialEq, Eq, Hash, Clone, Copy, EnumString, EnumIter)]
 pub enum ErrorCode {
     #[strum(serialize = "SMIK-1")]
-    /**
-     * en: SectionMetaInfoKey is invalid
-     * ja: セクションメタ情報のキーが不正です
-     */
     Smik1,
-
     #[strum(serialize = "SMIK-2")]
-    /**
-     * en: SectionMetaInfoKey should not contains line break
-     * ja: セクションメタ情報のキーに改行を含めることはできません
-     */
     Smik2,
+    #[strum(serialize = "SMIK-3")]
+    Smik3,
 
     #[strum(serialize = "SMIV-1")]
-    /**
-     * en: SectionMetaInfoValue should not be empty
-     * ja: セクションメタ情報の値は空にできません
-     */
     Smiv1,
-
     #[strum(serialize = "SMIV-2")]
-    /**
-     * en: SectionMetaInfoValue needs line break after
-     * ja: セクションメタ情報の値の後に改行が必要です
-     */
     Smiv2,
-
     #[strum(serialize = "SMIV-3")]
-    /**
-     * en: SectionMetaInfoValue of repeat needs to be number
-     * ja: セクションメタ情報の値のrepeatの値は数値である必要があります
-     */
     Smiv3,
+    #[strum(serialize = "SMIV-4")]
+    Smiv4,
+    #[strum(serialize = "SMIV-5")]
+    Smiv5,
+    #[strum(serialize = "SMIV-6")]
+    Smiv6,
+    #[strum(serialize = "SMIV-7")]
+    Smiv7,
+    #[strum(serialize = "SMIV-8")]
+    Smiv8,
+    #[strum(serialize = "SMIV-9")]
+    Smiv9,
 
     #[strum(serialize = "CIMK-1")]
-    /**
-     * en: ChordInfoMetaKey should not contains line break
-     * ja: コードメタ情報のキーに改行を含めることはできません
-     */
     Cimk1,
-
     #[strum(serialize = "CIMK-2")]
-    /**
-     * en: MetaInfoKey should not be empty
-     * ja: コードメタ情報のキーは空にできません
-     */
     Cimk2,
-
     #[strum(serialize = "CIMK-3")]
-    /**
-     * en: MetaInfoKey is invalid
-     * ja: コードメタ情報のキーが不正です
-     */
     Cimk3,
+    #[strum(serialize = "CIMK-4")]
+    Cimk4,
+    #[strum(serialize = "CIMK-5")]
+    Cimk5,
+    #[strum(serialize = "CIMK-6")]
+    Cimk6,
+    #[strum(serialize = "CIMK-7")]
+    Cimk7,
 
     #[strum(serialize = "CIMV-1")]
-    /**
-     * en: MetaInfoValue should not contains line break
-     * ja: コードメタ情報の値に改行を含めることはできません
-     */
     Cimv1,
-
     #[strum(serialize = "CIMV-2")]
-    /**
-     * en: MetaInfoValue should not be empty
-     * ja: コードメタ情報の値は空にできません
-     */
     Cimv2,
-
     #[strum(serialize = "CIMV-3")]
-    /**
-     * en: MetaInfoValue needs close parenthesis after
-     * ja: コードメタ情報の値の後に閉じ括弧が必要です
-     */
     Cimv3,
-
     #[strum(serialize = "CIMV-4")]
-    /**
-     * en: MetaInfoValue is invalid
-     * ja: コードメタ情報の値が不正です
-     */
     Cimv4,
+    #[strum(serialize = "CIMV-5")]
+    Cimv5,
+    #[strum(serialize = "CIMV-6")]
+    Cimv6,
+    #[strum(serialize = "CIMV-7")]
+    Cimv7,
+    #[strum(serialize = "CIMV-8")]
+    Cimv8,
+    #[strum(serialize = "CIMV-9")]
+    Cimv9,
 
     #[strum(serialize = "CHB-1")]
-    /**
-     * en: % should not be placed first of ChordBlock
-     * ja: コードブロックの先頭に%を置くことはできません
-     */
     Chb1,
-
     #[strum(serialize = "CHB-2")]
-    /**
-     * en: Bar should not contains line break
-     * ja: コードブロックに改行を含めることはできません
-     */
     Chb2,
+    #[strum(serialize = "CHB-3")]
+    Chb3,
+    #[strum(serialize = "CHB-4")]
+    Chb4,
 
     #[strum(serialize = "CHO-1")]
-    /**
-     * en: Invalid chord
-     * ja: コードが不正です
-     */
     Cho1,
-
     #[strum(serialize = "CHO-2")]
-    /**
-     * en: Chord should not contains line break
-     * ja: コードに改行を含めることはできません
-     */
     Cho2,
-
     #[strum(serialize = "CHO-3")]
-    /**
-     * en: Chord should not be empty
-     * ja: コードは空にできません
-     */
     Cho3,
+    #[strum(serialize = "CHO-4")]
+    Cho4,
+    #[strum(serialize = "CHO-5")]
+    Cho5,
+    #[strum(serialize = "CHO-6")]
+    Cho6,
 
     #[strum(serialize = "DEN-1")]
-    /**
-     * en: Invalid denominator
-     * ja: 分母が不正です
-     */
     Den1,
-
     #[strum(serialize = "DEN-2")]
-    /**
-     * en: Denominator is limited to one per chord
-     * ja: コードに対して分母は1つまでです
-     */
     Den2,
 
     #[strum(serialize = "EXT-1")]
-    /**
-     * en: Invalid extension
-     * ja: テンションが不正です
-     */
     Ext1,
-
     #[strum(serialize = "EXT-2")]
-    /**
-     * en: Extension must not be empty
-     * ja: テンションは空にできません
-     */
     Ext2,
-
     #[strum(serialize = "EXT-3")]
-    /**
-     * en: Extension must be surrounded by parenthesis
-     * ja: テンションは括弧で囲む必要があります
-     */
     Ext3,
-
     #[strum(serialize = "EXT-4")]
-    /**
-     * en: No multiple extension parenthesis
-     * ja: テンションの括弧は1つまでです
-     */
     Ext4,
 
     #[strum(serialize = "TKN-1")]
-    /**
-     * en: Invalid token type
-     * ja: 不正なトークンタイプです
-     */
     Tkn1,
 
     #[strum(serialize = "BS-1")]
-    /**
-     * en: Invalid base
-     * ja: 不正なベース音です
-     */
     Bs1,
 
     #[strum(serialize = "BL-1")]
-    /**
-     * en: Continuous blank lines are not allowed
-     * ja: 連続した空行は許可されていません
-     */
     Bl1,
 
+    #[strum(serialize = "TRP-1")]
+    Trp1,
+    #[strum(serialize = "TRP-2")]
+    Trp2,
+    #[strum(serialize = "TRP-3")]
+    Trp3,
+
+    #[strum(serialize = "NAV-1")]
+    Nav1,
+    #[strum(serialize = "NAV-2")]
+    Nav2,
+    #[strum(serialize = "NAV-3")]
+    Nav3,
+    #[strum(serialize = "NAV-4")]
+    Nav4,
+    #[strum(serialize = "NAV-5")]
+    Nav5,
+    #[strum(serialize = "NAV-6")]
+    Nav6,
+    #[strum(serialize = "NAV-7")]
+    Nav7,
+
+    #[strum(serialize = "INC-1")]
+    Inc1,
+    #[strum(serialize = "INC-2")]
+    Inc2,
+    #[strum(serialize = "INC-3")]
+    Inc3,
+
+    #[strum(serialize = "DEF-1")]
+    Def1,
+    #[strum(serialize = "DEF-2")]
+    Def2,
+    #[strum(serialize = "DEF-3")]
+    Def3,
+    #[strum(serialize = "DEF-4")]
+    Def4,
+
+    #[strum(serialize = "SEM-1")]
+    Sem1,
+    #[strum(serialize = "SEM-2")]
+    Sem2,
+
     #[strum(serialize = "OTHER-1")]
-    /**
-     * en: Unknown error
-     * ja: 不明なエラーです
-     */
     Other1,
+    #[strum(serialize = "OTHER-2")]
+    Other2,
+    #[strum(serialize = "OTHER-3")]
+    Other3,
+}
+
+/// serialized as the code, like `"BS-1"`
+impl Serialize for ErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl ErrorCode {
+    /// short name of the genre, like "SMIK" of "SMIK-1"
+    pub fn genre(&self) -> &'static str {
+        let code = self.to_string();
+        let short_genre = code.split('-').next().unwrap_or_default();
+
+        ERROR_GENRES
+            .iter()
+            .map(|(genre, _)| *genre)
+            .find(|genre| *genre == short_genre)
+            .unwrap_or("OTHER")
+    }
+
+    /// Localized message of the error code.
+    /// Unknown locales fall back to [`locale::DEFAULT_LOCALE`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use chord_progression_parser::error_code::ErrorCode;
+    ///
+    /// assert_eq!(ErrorCode::Ext1.message("en"), "Invalid extension");
+    /// assert_eq!(ErrorCode::Ext1.message("ja"), "テンションが不正です");
+    /// ```
+    pub fn message(&self, locale: &str) -> String {
+        locale::message(self, locale)
+    }
+
+    /// Severity of the code. The warnings don't stop parsing, and are returned with the AST.
+    pub fn severity(&self) -> Severity {
+        match self {
+            ErrorCode::Chb4 | ErrorCode::Sem1 | ErrorCode::Sem2 => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ErrorInfo {
     pub code: ErrorCode,
     pub additional_info: Option<String>,
+    /// inner error which caused this one, like `BS-1` of the invalid chord (`CHO-1`)
+    pub cause: Option<ErrorCode>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ErrorInfoWithPosition {
     pub error: ErrorInfo,
     pub position: Position,
@@ -0,0 +1,138 @@
+use crate::parser::types::ast::Ast;
+use crate::parser::types::chord_block::ChordBlock;
+use crate::parser::types::chord_expression::ChordExpression;
+use crate::parser::types::chord_info::ChordInfo;
+use crate::parser::types::section_meta::SectionMeta;
+use crate::transposer::first_key;
+
+/// Export the AST as ChordPro
+///
+/// Each line of the chord progression becomes a line of bars like `| [C] | [F] [G] |`.
+pub fn export(ast: &Ast) -> String {
+    let mut lines: Vec<String> = vec![];
+
+    if let Some(key) = first_key(ast) {
+        lines.push(format!("{{key: {}}}", key));
+    }
+
+    for (index, section) in ast.iter().enumerate() {
+        if index > 0 {
+            lines.push("".to_string());
+        }
+
+        for meta_info in section.meta_infos.iter() {
+            match meta_info {
+                SectionMeta::Section(name) => lines.push(format!("{{comment: {}}}", name)),
+                SectionMeta::Repeat(count) => lines.push(format!("{{comment: x{}}}", count)),
+                SectionMeta::Use(reference) => lines.push(format!("{{comment: = {}}}", reference)),
+                SectionMeta::Include(path) => lines.push(format!("{{comment: include {}}}", path)),
+                SectionMeta::Custom { key, value } => {
+                    lines.push(format!("{{meta: {} {}}}", key, value))
+                }
+            }
+        }
+
+        let chord_lines = section
+            .chord_blocks
+            .split(|chord_block| *chord_block == ChordBlock::Br)
+            .filter(|bars| !bars.is_empty());
+
+        for bars in chord_lines {
+            // bars are separated by " | ", and navigation marks are written as text
+            let mut line = String::new();
+            let mut is_previous_bar = false;
+            for chord_block in bars.iter() {
+                match chord_block {
+                    ChordBlock::Bar(bar) => {
+                        if is_previous_bar {
+                            line.push_str(" | ");
+                        } else if !line.is_empty() {
+                            line.push(' ');
+                        }
+                        line.push_str(
+                            &bar.iter()
+                                .map(export_chord_info)
+                                .collect::<Vec<String>>()
+                                .join(" "),
+                        );
+                        is_previous_bar = true;
+                    }
+                    ChordBlock::Navigation(mark) => {
+                        if !line.is_empty() {
+                            line.push(' ');
+                        }
+                        line.push_str(&mark.to_string());
+                        is_previous_bar = false;
+                    }
+                    ChordBlock::Br => {}
+                }
+            }
+            lines.push(format!("| {} |", line));
+        }
+    }
+
+    lines
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect::<String>()
+}
+
+fn export_chord_info(chord_info: &ChordInfo) -> String {
+    let chord = match &chord_info.chord_expression {
+        ChordExpression::Chord(chord) => chord.plain.clone(),
+        ChordExpression::PolyChord(poly_chord) => {
+            format!("{}|{}", poly_chord.upper.plain, poly_chord.lower.plain)
+        }
+        ChordExpression::UnIdentified => "?".to_string(),
+        ChordExpression::NoChord => "N.C.".to_string(),
+        ChordExpression::Same => return "%".to_string(),
+    };
+
+    match &chord_info.denominator {
+        Some(denominator) => format!("[{}/{}]", chord, denominator),
+        None => format!("[{}]", chord),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_chord_progression_string;
+
+    #[test]
+    fn export_sections() {
+        let input: &str = "
+@section=Intro
+[key=E]E-C#m(7)
+F#m(7),B/D#-%
+
+@section=Verse
+@repeat=2
+E-_-?
+";
+        let ast = parse_chord_progression_string(input).unwrap();
+
+        assert_eq!(
+            export(&ast),
+            "{key: E}
+{comment: Intro}
+| [E] | [C#m(7)] |
+| [F#m(7)] [B/D#] | % |
+
+{comment: Verse}
+{comment: x2}
+| [E] | [N.C.] | [?] |
+"
+        );
+    }
+
+    #[test]
+    fn navigation_marks_are_text() {
+        let ast = parse_chord_progression_string("|: C - F :| <fine> G <D.C. al Fine>").unwrap();
+
+        assert_eq!(
+            export(&ast),
+            "| |: [C] | [F] :| <fine> [G] <D.C. al Fine> |\n"
+        );
+    }
+}
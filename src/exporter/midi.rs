@@ -0,0 +1,336 @@
+use crate::parser::types::articulation::Articulation;
+use crate::parser::types::ast::Ast;
+use crate::parser::types::chord_expression::ChordExpression;
+use crate::parser::types::chord_info::ChordInfo;
+use crate::parser::types::chord_info_meta::ChordInfoMeta;
+use crate::parser::types::dynamics::Dynamics;
+use crate::parser::types::time_signature::TimeSignature;
+use crate::timeline::played_bars_with_time_signature;
+use crate::util::note::{pitch_class, split_note};
+
+const TICKS_PER_QUARTER: u32 = 480;
+/// BPM until `[tempo=...]`
+const TEMPO: u32 = 120;
+/// velocity until `[dynamics=...]` (mf)
+const VELOCITY: u8 = 80;
+/// velocity added to accented chords
+const ACCENT: u8 = 16;
+/// C4
+const CHORD_OCTAVE: u8 = 60;
+/// C2
+const BASS_OCTAVE: u8 = 36;
+
+/// Export the AST as a standard MIDI file (format 0)
+///
+/// Every bar is played in its time signature (4/4 until `[time=...]`), and divided equally among its chords.
+/// The bars are played in the order of [`played_bars_with_time_signature`].
+/// `[tempo=...]` (120 BPM until then) and `[dynamics=...]` are carried forward,
+/// accents make the chord louder, and staccatos play the first half of the chord only.
+pub fn export(ast: &Ast) -> Vec<u8> {
+    let mut track: Vec<u8> = vec![];
+
+    track.push(0x00);
+    track.extend(tempo_event(TEMPO));
+
+    let bars = played_bars_with_time_signature(ast);
+    let mut time_signature = bars
+        .first()
+        .map_or(TimeSignature::common(), |(_, time_signature)| {
+            time_signature.clone()
+        });
+    track.push(0x00);
+    track.extend(time_signature_event(&time_signature));
+
+    let mut previous_notes: Vec<u8> = vec![];
+    let mut rest_ticks: u32 = 0;
+    let mut velocity = VELOCITY;
+
+    for (bar, bar_time_signature) in bars {
+        if bar_time_signature != time_signature {
+            write_variable_length(&mut track, rest_ticks);
+            rest_ticks = 0;
+            track.extend(time_signature_event(&bar_time_signature));
+            time_signature = bar_time_signature;
+        }
+
+        let bar_ticks = time_signature.quarters(TICKS_PER_QUARTER);
+        if bar.is_empty() {
+            rest_ticks += bar_ticks;
+            continue;
+        }
+
+        let count = bar.len() as u32;
+        for (index, chord_info) in bar.iter().enumerate() {
+            let ticks = if index as u32 == count - 1 {
+                bar_ticks - (bar_ticks / count) * (count - 1)
+            } else {
+                bar_ticks / count
+            };
+
+            let mut accent = false;
+            let mut staccato = false;
+            for meta_info in chord_info.meta_infos.iter() {
+                match meta_info {
+                    ChordInfoMeta::Tempo(bpm) => {
+                        write_variable_length(&mut track, rest_ticks);
+                        rest_ticks = 0;
+                        track.extend(tempo_event(*bpm));
+                    }
+                    ChordInfoMeta::Dynamics(dynamics) => velocity = dynamics_velocity(dynamics),
+                    ChordInfoMeta::Articulation(articulation) => match articulation {
+                        Articulation::Accent | Articulation::Marcato => accent = true,
+                        Articulation::Staccato => staccato = true,
+                        Articulation::Stab => {
+                            accent = true;
+                            staccato = true;
+                        }
+                        Articulation::Tenuto => {}
+                    },
+                    _ => {}
+                }
+            }
+
+            let notes = match &chord_info.chord_expression {
+                ChordExpression::Same => previous_notes.clone(),
+                _ => chord_notes(chord_info),
+            };
+
+            if notes.is_empty() {
+                rest_ticks += ticks;
+                continue;
+            }
+
+            let note_velocity = if accent {
+                velocity.saturating_add(ACCENT).min(127)
+            } else {
+                velocity
+            };
+            let note_ticks = if staccato { ticks / 2 } else { ticks };
+            for (i, note) in notes.iter().enumerate() {
+                let delta = if i == 0 { rest_ticks } else { 0 };
+                write_variable_length(&mut track, delta);
+                track.extend([0x90, *note, note_velocity]);
+            }
+            for (i, note) in notes.iter().enumerate() {
+                let delta = if i == 0 { note_ticks } else { 0 };
+                write_variable_length(&mut track, delta);
+                track.extend([0x80, *note, 0x00]);
+            }
+
+            rest_ticks = ticks - note_ticks;
+            previous_notes = notes;
+        }
+    }
+
+    // end of track
+    write_variable_length(&mut track, rest_ticks);
+    track.extend([0xFF, 0x2F, 0x00]);
+
+    let mut midi: Vec<u8> = vec![];
+    midi.extend(b"MThd");
+    midi.extend(6u32.to_be_bytes());
+    midi.extend(0u16.to_be_bytes());
+    midi.extend(1u16.to_be_bytes());
+    midi.extend((TICKS_PER_QUARTER as u16).to_be_bytes());
+    midi.extend(b"MTrk");
+    midi.extend((track.len() as u32).to_be_bytes());
+    midi.extend(track);
+    midi
+}
+
+/// MIDI note numbers of the chord. the denominator (or the root) is played as bass.
+/// the upper chord of a polychord is played an octave above the lower one.
+fn chord_notes(chord_info: &ChordInfo) -> Vec<u8> {
+    let (lower, upper) = match &chord_info.chord_expression {
+        ChordExpression::Chord(chord) => (chord, None),
+        ChordExpression::PolyChord(poly_chord) => (&poly_chord.lower, Some(&poly_chord.upper)),
+        _ => return vec![],
+    };
+
+    let root = lower.detailed.root_pitch_class();
+    let bass = match chord_info
+        .denominator
+        .as_ref()
+        .and_then(|denominator| split_note(denominator))
+    {
+        Some((base, accidental, _)) => pitch_class(&base, &accidental),
+        None => root,
+    };
+
+    let mut notes = vec![BASS_OCTAVE + bass];
+    notes.extend(
+        lower
+            .detailed
+            .intervals()
+            .iter()
+            .map(|interval| CHORD_OCTAVE + root + interval),
+    );
+    if let Some(upper) = upper {
+        let upper_root = upper.detailed.root_pitch_class();
+        notes.extend(
+            upper
+                .detailed
+                .intervals()
+                .iter()
+                .map(|interval| CHORD_OCTAVE + 12 + upper_root + interval),
+        );
+    }
+    notes
+}
+
+/// velocity of the dynamics
+fn dynamics_velocity(dynamics: &Dynamics) -> u8 {
+    match dynamics {
+        Dynamics::Pp => 33,
+        Dynamics::P => 49,
+        Dynamics::Mp => 64,
+        Dynamics::Mf => 80,
+        Dynamics::F => 96,
+        Dynamics::Ff => 112,
+    }
+}
+
+/// tempo meta event (microseconds per quarter note), without the delta time.
+/// the tempo is 3 bytes, so the BPM below 4 is written as the slowest tempo (about 3.58 BPM)
+fn tempo_event(bpm: u32) -> [u8; 6] {
+    let tempo = (60_000_000 / bpm).min(0xFF_FFFF).to_be_bytes();
+    [0xFF, 0x51, 0x03, tempo[1], tempo[2], tempo[3]]
+}
+
+/// time signature meta event, without the delta time
+fn time_signature_event(time_signature: &TimeSignature) -> [u8; 7] {
+    [
+        0xFF,
+        0x58,
+        0x04,
+        time_signature.beats as u8,
+        time_signature.beat_type.trailing_zeros() as u8,
+        0x18,
+        0x08,
+    ]
+}
+
+fn write_variable_length(buffer: &mut Vec<u8>, value: u32) {
+    let mut bytes = vec![(value & 0x7F) as u8];
+    let mut value = value >> 7;
+    while value > 0 {
+        bytes.push(((value & 0x7F) as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.reverse();
+    buffer.extend(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_chord_progression_string;
+    use crate::parser::types::chord_block::ChordBlock;
+    use crate::timeline::played_bars;
+
+    #[test]
+    fn header_and_track() {
+        let ast = parse_chord_progression_string("C").unwrap();
+        let midi = export(&ast);
+
+        assert_eq!(&midi[0..4], b"MThd");
+        assert_eq!(&midi[14..18], b"MTrk");
+        let track_length = u32::from_be_bytes([midi[18], midi[19], midi[20], midi[21]]);
+        assert_eq!(track_length as usize, midi.len() - 22);
+        assert_eq!(&midi[midi.len() - 3..], [0xFF, 0x2F, 0x00]);
+    }
+
+    #[test]
+    fn notes_of_slash_chord() {
+        let ast = parse_chord_progression_string("Am/G").unwrap();
+        let chord_info = match &ast[0].chord_blocks[0] {
+            ChordBlock::Bar(bar) => &bar[0],
+            _ => panic!("bar expected"),
+        };
+
+        assert_eq!(chord_notes(chord_info), vec![43, 69, 72, 76]);
+    }
+
+    #[test]
+    fn section_is_repeated() {
+        let ast = parse_chord_progression_string("@section=A\n@repeat=3\nC-G\n\nF").unwrap();
+
+        assert_eq!(played_bars(&ast).len(), 7);
+    }
+
+    #[test]
+    fn time_signature_changes() {
+        let ast = parse_chord_progression_string("[time=3/4]C - [time=6/8]G - C").unwrap();
+        let midi = export(&ast);
+
+        // 3/4 at the beginning
+        assert_eq!(
+            &midi[29..37],
+            [0x00, 0xFF, 0x58, 0x04, 0x03, 0x02, 0x18, 0x08]
+        );
+        // 6/8 after the first bar (3 quarters = 1440 ticks)
+        let change = [0x00, 0xFF, 0x58, 0x04, 0x06, 0x03, 0x18, 0x08];
+        assert_eq!(
+            midi.windows(8).filter(|window| *window == change).count(),
+            1
+        );
+        let mut note_off = vec![];
+        write_variable_length(&mut note_off, 1440);
+        note_off.extend([0x80, 36, 0x00]);
+        assert!(midi
+            .windows(note_off.len())
+            .any(|window| window == note_off));
+    }
+
+    #[test]
+    fn tempo_and_dynamics() {
+        let ast =
+            parse_chord_progression_string("[tempo=60][dynamics=p]C - [articulation=stab]G - C")
+                .unwrap();
+        let midi = export(&ast);
+
+        // 120 BPM at the beginning, then 60 BPM (1,000,000 microseconds)
+        assert_eq!(&midi[22..29], [0x00, 0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20]);
+        assert!(midi
+            .windows(7)
+            .any(|window| window == [0x00, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40]));
+        // p, and the accented stab
+        assert_eq!(
+            midi.windows(3)
+                .filter(|window| *window == [0x90, 36, 49])
+                .count(),
+            2
+        );
+        assert!(midi.windows(3).any(|window| window == [0x90, 43, 65]));
+        // the stab is released after the half of the bar (960 ticks)
+        let mut note_off = vec![];
+        write_variable_length(&mut note_off, 960);
+        note_off.extend([0x80, 43, 0x00]);
+        assert!(midi
+            .windows(note_off.len())
+            .any(|window| window == note_off));
+    }
+
+    #[test]
+    fn slowest_tempo() {
+        // 15,000,000 microseconds fits in 3 bytes, and 20,000,000 doesn't
+        let midi = export(&parse_chord_progression_string("[tempo=4]C - [tempo=3]C").unwrap());
+
+        assert!(midi
+            .windows(6)
+            .any(|window| window == [0xFF, 0x51, 0x03, 0xE4, 0xE1, 0xC0]));
+        assert!(midi
+            .windows(6)
+            .any(|window| window == [0xFF, 0x51, 0x03, 0xFF, 0xFF, 0xFF]));
+        assert_eq!(tempo_event(1), [0xFF, 0x51, 0x03, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn variable_length_quantity() {
+        let mut buffer = vec![];
+        write_variable_length(&mut buffer, 0);
+        write_variable_length(&mut buffer, 0x7F);
+        write_variable_length(&mut buffer, 1920);
+        assert_eq!(buffer, vec![0x00, 0x7F, 0x8F, 0x00]);
+    }
+}
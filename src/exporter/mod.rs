@@ -0,0 +1,36 @@
+pub mod chordpro;
+pub mod midi;
+pub mod musicxml;
+
+use strum_macros::{Display, EnumString, VariantNames};
+
+use crate::parser::types::ast::Ast;
+
+#[derive(Debug, PartialEq, Clone, Display, EnumString, VariantNames)]
+pub enum ExportFormat {
+    #[strum(serialize = "midi")]
+    Midi,
+    #[strum(serialize = "musicxml")]
+    MusicXml,
+    #[strum(serialize = "chordpro")]
+    ChordPro,
+}
+
+/// Export the AST to the given format
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::exporter::{export, ExportFormat};
+/// use chord_progression_parser::parse_chord_progression_string;
+///
+/// let ast = parse_chord_progression_string("C-G").unwrap();
+/// let chordpro = export(&ast, &ExportFormat::ChordPro);
+/// assert_eq!(String::from_utf8(chordpro).unwrap(), "| [C] | [G] |\n");
+/// ```
+pub fn export(ast: &Ast, format: &ExportFormat) -> Vec<u8> {
+    match format {
+        ExportFormat::Midi => midi::export(ast),
+        ExportFormat::MusicXml => musicxml::export(ast).into_bytes(),
+        ExportFormat::ChordPro => chordpro::export(ast).into_bytes(),
+    }
+}
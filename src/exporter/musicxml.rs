@@ -0,0 +1,455 @@
+use crate::parser::types::accidental::Accidental;
+use crate::parser::types::articulation::Articulation;
+use crate::parser::types::ast::Ast;
+use crate::parser::types::base::Base;
+use crate::parser::types::chord::Chord;
+use crate::parser::types::chord_block::ChordBlock;
+use crate::parser::types::chord_detailed::ChordDetailed;
+use crate::parser::types::chord_expression::ChordExpression;
+use crate::parser::types::chord_info::ChordInfo;
+use crate::parser::types::chord_info_meta::ChordInfoMeta;
+use crate::parser::types::chord_type::ChordType;
+use crate::parser::types::extension::Extension;
+use crate::parser::types::navigation_mark::NavigationMark;
+use crate::parser::types::section_meta::SectionMeta;
+use crate::parser::types::time_signature::TimeSignature;
+use crate::util::note::split_note;
+
+/// divisions per quarter note. 12 can divide a 4/4 bar into 1, 2, 3, 4 or 6 chords evenly.
+const DIVISIONS: usize = 12;
+
+/// Export the AST as MusicXML (score-partwise) with one `<harmony>` per chord
+///
+/// A polychord is exported as two `<harmony>`s, the lower chord first.
+/// Repeats and voltas become `<barline>`s, and the other navigation marks become `<direction>`s.
+/// Bars are 4/4 until `[time=...]`, and each time signature change starts with `<attributes>`.
+/// Tempo, rit./accel. and dynamics become `<direction>`s before the chord,
+/// and fermatas and articulations become `<notations>` of the rest note of the chord.
+pub fn export(ast: &Ast) -> String {
+    let mut measures: Vec<String> = vec![];
+    // left barlines of the next measure
+    let mut barlines: Vec<String> = vec![];
+    // number of the volta not stopped yet, like "1, 2"
+    let mut open_volta: Option<String> = None;
+    // time signature of the previous measure, carried forward
+    let mut time_signature = TimeSignature::common();
+
+    for section in ast.iter() {
+        let mut directions: Vec<String> = section
+            .meta_infos
+            .iter()
+            .map(|meta_info| match meta_info {
+                SectionMeta::Section(name) => format!(
+                    "      <direction placement=\"above\">\n        <direction-type>\n          <rehearsal>{}</rehearsal>\n        </direction-type>\n      </direction>\n",
+                    escape(name)
+                ),
+                SectionMeta::Repeat(count) => format!(
+                    "      <direction placement=\"above\">\n        <direction-type>\n          <words>x{}</words>\n        </direction-type>\n      </direction>\n",
+                    count
+                ),
+                SectionMeta::Use(reference) => {
+                    direction(&format!("<words>= {}</words>", escape(&reference.to_string())))
+                }
+                // not resolved, and has no bars
+                SectionMeta::Include(_) => "".to_string(),
+                // only the application registering the key knows what it means
+                SectionMeta::Custom { .. } => "".to_string(),
+            })
+            .collect();
+
+        for chord_block in section.chord_blocks.iter() {
+            let bar = match chord_block {
+                ChordBlock::Bar(bar) => bar,
+                ChordBlock::Br => continue,
+                ChordBlock::Navigation(mark) => {
+                    match mark {
+                        NavigationMark::RepeatStart => barlines.push(
+                            "      <barline location=\"left\">\n        <bar-style>heavy-light</bar-style>\n        <repeat direction=\"forward\"/>\n      </barline>\n".to_string(),
+                        ),
+                        NavigationMark::RepeatEnd => {
+                            let ending = match open_volta.take() {
+                                Some(number) => format!(
+                                    "        <ending number=\"{}\" type=\"stop\"/>\n",
+                                    number
+                                ),
+                                None => "".to_string(),
+                            };
+                            if let Some(measure) = measures.last_mut() {
+                                measure.push_str(&format!(
+                                    "      <barline location=\"right\">\n        <bar-style>light-heavy</bar-style>\n{}        <repeat direction=\"backward\"/>\n      </barline>\n",
+                                    ending
+                                ));
+                            }
+                        }
+                        NavigationMark::Volta(numbers) => {
+                            discontinue_volta(&mut measures, &mut open_volta);
+                            let number = numbers
+                                .iter()
+                                .map(|number| number.to_string())
+                                .collect::<Vec<String>>()
+                                .join(", ");
+                            barlines.push(format!(
+                                "      <barline location=\"left\">\n        <ending number=\"{}\" type=\"start\">{}.</ending>\n      </barline>\n",
+                                number,
+                                number.replace(", ", ".")
+                            ));
+                            open_volta = Some(number);
+                        }
+                        // segno and coda are at the beginning of the next measure
+                        NavigationMark::Segno => directions.push(direction("<segno/>")),
+                        NavigationMark::Coda => directions.push(direction("<coda/>")),
+                        // the others are at the end of the previous measure
+                        _ => {
+                            let words = direction(&format!(
+                                "<words>{}</words>",
+                                escape(mark.to_string().trim_start_matches('<').trim_end_matches('>'))
+                            ));
+                            match measures.last_mut() {
+                                Some(measure) => measure.push_str(&words),
+                                None => directions.push(words),
+                            }
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            let mut measure = String::new();
+            let is_time_changed = match TimeSignature::of_bar(bar) {
+                Some(written) if *written != time_signature => {
+                    time_signature = written.clone();
+                    true
+                }
+                _ => false,
+            };
+            if measures.is_empty() || is_time_changed {
+                let divisions = if measures.is_empty() {
+                    format!("        <divisions>{}</divisions>\n", DIVISIONS)
+                } else {
+                    "".to_string()
+                };
+                measure.push_str(&format!(
+                    "      <attributes>\n{}        <time>\n          <beats>{}</beats>\n          <beat-type>{}</beat-type>\n        </time>\n      </attributes>\n",
+                    divisions, time_signature.beats, time_signature.beat_type
+                ));
+            }
+            measure.push_str(&barlines.concat());
+            barlines.clear();
+            measure.push_str(&directions.concat());
+            directions.clear();
+
+            let durations = divide(
+                time_signature.quarters(DIVISIONS as u32) as usize,
+                bar.len(),
+            );
+            for (chord_info, duration) in bar.iter().zip(durations) {
+                measure.push_str(&performance_directions(chord_info));
+                measure.push_str(&export_harmony(chord_info));
+                measure.push_str(&format!(
+                    "      <note>\n        <rest/>\n        <duration>{}</duration>\n{}      </note>\n",
+                    duration,
+                    notations(chord_info)
+                ));
+            }
+
+            measures.push(measure);
+        }
+    }
+    discontinue_volta(&mut measures, &mut open_volta);
+
+    let measures = measures
+        .iter()
+        .enumerate()
+        .map(|(index, measure)| {
+            format!(
+                "    <measure number=\"{}\">\n{}    </measure>\n",
+                index + 1,
+                measure
+            )
+        })
+        .collect::<String>();
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>
+<!DOCTYPE score-partwise PUBLIC \"-//Recordare//DTD MusicXML 4.0 Partwise//EN\" \"http://www.musicxml.org/dtds/partwise.dtd\">
+<score-partwise version=\"4.0\">
+  <part-list>
+    <score-part id=\"P1\">
+      <part-name>Chords</part-name>
+    </score-part>
+  </part-list>
+  <part id=\"P1\">
+{}  </part>
+</score-partwise>
+",
+        measures
+    )
+}
+
+fn direction(direction_type: &str) -> String {
+    format!(
+        "      <direction placement=\"above\">\n        <direction-type>\n          {}\n        </direction-type>\n      </direction>\n",
+        direction_type
+    )
+}
+
+/// `<direction>`s of the tempo, rit./accel. and dynamics of the chord
+fn performance_directions(chord_info: &ChordInfo) -> String {
+    chord_info
+        .meta_infos
+        .iter()
+        .map(|meta_info| match meta_info {
+            ChordInfoMeta::Tempo(bpm) => format!(
+                "      <direction placement=\"above\">\n        <direction-type>\n          <metronome>\n            <beat-unit>quarter</beat-unit>\n            <per-minute>{}</per-minute>\n          </metronome>\n        </direction-type>\n        <sound tempo=\"{}\"/>\n      </direction>\n",
+                bpm, bpm
+            ),
+            ChordInfoMeta::Ritardando => direction("<words>rit.</words>"),
+            ChordInfoMeta::Accelerando => direction("<words>accel.</words>"),
+            ChordInfoMeta::Dynamics(dynamics) => {
+                direction(&format!("<dynamics><{}/></dynamics>", dynamics))
+            }
+            _ => "".to_string(),
+        })
+        .collect()
+}
+
+/// `<notations>` of the fermata and the articulations of the chord. a stab is a staccato with an accent.
+fn notations(chord_info: &ChordInfo) -> String {
+    let mut fermata = false;
+    let mut articulations: Vec<&str> = vec![];
+    for meta_info in chord_info.meta_infos.iter() {
+        match meta_info {
+            ChordInfoMeta::Fermata => fermata = true,
+            ChordInfoMeta::Articulation(articulation) => articulations.extend(match articulation {
+                Articulation::Accent => ["accent"].as_slice(),
+                Articulation::Staccato => &["staccato"],
+                Articulation::Tenuto => &["tenuto"],
+                Articulation::Marcato => &["strong-accent"],
+                Articulation::Stab => &["staccato", "accent"],
+            }),
+            _ => {}
+        }
+    }
+    if !fermata && articulations.is_empty() {
+        return "".to_string();
+    }
+
+    let mut notations = "        <notations>\n".to_string();
+    if fermata {
+        notations.push_str("          <fermata/>\n");
+    }
+    if !articulations.is_empty() {
+        notations.push_str("          <articulations>\n");
+        for articulation in articulations {
+            notations.push_str(&format!("            <{}/>\n", articulation));
+        }
+        notations.push_str("          </articulations>\n");
+    }
+    notations.push_str("        </notations>\n");
+    notations
+}
+
+/// end the volta without a repeat (like the last ending) at the last measure
+fn discontinue_volta(measures: &mut [String], open_volta: &mut Option<String>) {
+    if let (Some(number), Some(measure)) = (open_volta.take(), measures.last_mut()) {
+        measure.push_str(&format!(
+            "      <barline location=\"right\">\n        <ending number=\"{}\" type=\"discontinue\"/>\n      </barline>\n",
+            number
+        ));
+    }
+}
+
+/// divide total into count parts. the remainder is added to the last part.
+fn divide(total: usize, count: usize) -> Vec<usize> {
+    if count == 0 {
+        return vec![];
+    }
+    let mut durations = vec![total / count; count];
+    durations[count - 1] += total % count;
+    durations
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn step_and_alter(base: &Base, accidental: &Option<Accidental>, tag: &str) -> String {
+    let alter = match accidental {
+        Some(accidental) => format!(
+            "          <{}-alter>{}</{}-alter>\n",
+            tag,
+            accidental.semitones(),
+            tag
+        ),
+        None => "".to_string(),
+    };
+    format!(
+        "          <{}-step>{:?}</{}-step>\n{}",
+        tag, base, tag, alter
+    )
+}
+
+fn export_harmony(chord_info: &ChordInfo) -> String {
+    let bass = match chord_info
+        .denominator
+        .as_ref()
+        .and_then(|denominator| split_note(denominator))
+    {
+        Some((base, accidental, _)) => format!(
+            "        <bass>\n{}        </bass>\n",
+            step_and_alter(&base, &accidental, "bass")
+        ),
+        None => "".to_string(),
+    };
+
+    match &chord_info.chord_expression {
+        ChordExpression::Chord(chord) => harmony(chord, &bass),
+        // the lower chord (with the bass) first, then the upper chord
+        ChordExpression::PolyChord(poly_chord) => [
+            harmony(&poly_chord.lower, &bass),
+            harmony(&poly_chord.upper, ""),
+        ]
+        .concat(),
+        ChordExpression::NoChord | ChordExpression::UnIdentified | ChordExpression::Same => {
+            "".to_string()
+        }
+    }
+}
+
+fn harmony(chord: &Chord, bass: &str) -> String {
+    let text = match split_note(&chord.plain) {
+        Some((_, _, rest)) => rest.to_string(),
+        None => "".to_string(),
+    };
+
+    format!(
+        "      <harmony>\n        <root>\n{}        </root>\n        <kind text=\"{}\">{}</kind>\n{}      </harmony>\n",
+        step_and_alter(&chord.detailed.base, &chord.detailed.accidental, "root"),
+        escape(&text),
+        kind(&chord.detailed),
+        bass
+    )
+}
+
+/// MusicXML `<kind>` value of the chord
+fn kind(detailed: &ChordDetailed) -> &'static str {
+    let has = |extension: Extension| detailed.extensions.contains(&extension);
+    let has_major_seven = has(Extension::MajorSeven)
+        || has(Extension::MajorNine)
+        || has(Extension::MajorEleven)
+        || has(Extension::MajorThirteen);
+
+    if has(Extension::HalfDiminish) {
+        return "half-diminished";
+    }
+
+    match detailed.chord_type {
+        ChordType::Diminished if has(Extension::Seven) => "diminished-seventh",
+        ChordType::Diminished => "diminished",
+        ChordType::Augmented if has(Extension::Seven) => "augmented-seventh",
+        ChordType::Augmented => "augmented",
+        ChordType::Minor if has(Extension::FlatFive) && has(Extension::Seven) => "half-diminished",
+        ChordType::Minor if has_major_seven => "major-minor",
+        ChordType::Minor if has(Extension::Seven) => "minor-seventh",
+        ChordType::Minor if has(Extension::Six) => "minor-sixth",
+        ChordType::Minor => "minor",
+        ChordType::Major if has_major_seven => "major-seventh",
+        ChordType::Major if has(Extension::Seven) => "dominant",
+        ChordType::Major if has(Extension::Six) => "major-sixth",
+        ChordType::Major if has(Extension::Sus4) => "suspended-fourth",
+        ChordType::Major if has(Extension::Sus2) => "suspended-second",
+        ChordType::Major => "major",
+        ChordType::Power => "power",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_chord_progression_string;
+
+    #[test]
+    fn export_harmony_with_bass() {
+        let ast = parse_chord_progression_string("@section=A\nC#m(7)/B,F(M7)").unwrap();
+        let xml = export(&ast);
+
+        assert!(xml.contains("<rehearsal>A</rehearsal>"));
+        assert!(xml.contains(
+            "<root>\n          <root-step>C</root-step>\n          <root-alter>1</root-alter>\n        </root>"
+        ));
+        assert!(xml.contains("<kind text=\"m(7)\">minor-seventh</kind>"));
+        assert!(xml.contains("<bass>\n          <bass-step>B</bass-step>\n        </bass>"));
+        assert!(xml.contains("<kind text=\"(M7)\">major-seventh</kind>"));
+        assert_eq!(xml.matches("<duration>24</duration>").count(), 2);
+    }
+
+    #[test]
+    fn one_measure_per_bar() {
+        let ast = parse_chord_progression_string("C-G\nAm-_").unwrap();
+        let xml = export(&ast);
+
+        assert_eq!(xml.matches("<measure number=").count(), 4);
+        assert_eq!(xml.matches("<harmony>").count(), 3);
+        assert_eq!(xml.matches("<attributes>").count(), 1);
+    }
+
+    #[test]
+    fn time_signature_changes() {
+        let ast = parse_chord_progression_string("[time=3/4]C - G - [time=6/8]Am,F - [time=6/8]C")
+            .unwrap();
+        let xml = export(&ast);
+
+        assert_eq!(xml.matches("<attributes>").count(), 2);
+        assert_eq!(xml.matches("<divisions>").count(), 1);
+        assert!(xml.contains("<beats>3</beats>\n          <beat-type>4</beat-type>"));
+        assert!(xml.contains("<beats>6</beats>\n          <beat-type>8</beat-type>"));
+        assert_eq!(xml.matches("<duration>36</duration>").count(), 3);
+        assert_eq!(xml.matches("<duration>18</duration>").count(), 2);
+    }
+
+    #[test]
+    fn navigation_marks() {
+        let ast =
+            parse_chord_progression_string("<segno>|: C <1.> F :| <2.> G <D.S. al Fine> <fine>")
+                .unwrap();
+        let xml = export(&ast);
+
+        assert_eq!(xml.matches("<measure number=").count(), 3);
+        assert!(xml.contains("<segno/>"));
+        assert!(xml.contains("<repeat direction=\"forward\"/>"));
+        assert!(xml.contains("<ending number=\"1\" type=\"start\">1.</ending>"));
+        assert!(xml.contains(
+            "<ending number=\"1\" type=\"stop\"/>\n        <repeat direction=\"backward\"/>"
+        ));
+        assert!(xml.contains("<ending number=\"2\" type=\"discontinue\"/>"));
+        assert!(xml.contains("<words>D.S. al Fine</words>"));
+        assert!(xml.contains("<words>fine</words>"));
+    }
+
+    #[test]
+    fn performance_meta_infos() {
+        let ast = parse_chord_progression_string(
+            "[tempo=96][dynamics=mf]C - [rit][fermata]G - [articulation=stab]C",
+        )
+        .unwrap();
+        let xml = export(&ast);
+
+        assert!(xml.contains("<per-minute>96</per-minute>"));
+        assert!(xml.contains("<sound tempo=\"96\"/>"));
+        assert!(xml.contains("<dynamics><mf/></dynamics>"));
+        assert!(xml.contains("<words>rit.</words>"));
+        assert!(xml.contains("<notations>\n          <fermata/>\n        </notations>"));
+        assert!(xml.contains(
+            "<articulations>\n            <staccato/>\n            <accent/>\n          </articulations>"
+        ));
+    }
+
+    #[test]
+    fn divide_remainder_goes_to_last() {
+        assert_eq!(divide(48, 5), vec![9, 9, 9, 9, 12]);
+        assert_eq!(divide(48, 0), Vec::<usize>::new());
+    }
+}
@@ -0,0 +1,284 @@
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+use typeshare::typeshare;
+
+use crate::error_code::ErrorInfoWithPosition;
+use crate::tokenizer::tokenize;
+use crate::tokenizer::types::token::Token;
+
+/// Types of the semantic tokens, named as the standard ones of LSP.
+/// The index of a type in the legend is its order here.
+#[typeshare]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Display, EnumIter, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum SemanticTokenType {
+    /// `@` and the key of the section meta info, like `@section`
+    Keyword,
+    /// name of `@def`, like `X` of `@def X=C`
+    Macro,
+    /// values of the meta infos
+    String,
+    /// chords and denominators
+    Type,
+    /// extensions, like `7` of `C(7)`
+    Number,
+    /// keys of the chord meta infos, like `key` of `[key=C]`
+    Property,
+    /// `=`, `,`, `/`, `-` and the brackets
+    Operator,
+    /// repeats and navigation marks, like `|:` and `<segno>`
+    Decorator,
+    /// `// ...` to the end of the line
+    Comment,
+}
+
+/// Names of the types in the order of the indexes, for the legend of LSP or Monaco
+pub fn legend() -> Vec<String> {
+    SemanticTokenType::iter()
+        .map(|token_type| token_type.to_string())
+        .collect()
+}
+
+/// Token for highlighting, in the coordinates of LSP:
+/// the line starts from 0, and the start and length are in UTF-16 code units.
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticToken {
+    pub line: u32,
+    pub start: u32,
+    pub length: u32,
+    pub token_type: SemanticTokenType,
+}
+
+fn token_type(token: &Token) -> Option<SemanticTokenType> {
+    match token {
+        Token::LineBreak => None,
+        Token::SectionMetaInfoStart | Token::SectionMetaInfoKey(_) => {
+            Some(SemanticTokenType::Keyword)
+        }
+        Token::SectionMetaInfoName(_) => Some(SemanticTokenType::Macro),
+        Token::SectionMetaInfoValue(_) | Token::MetaInfoValue(_) => Some(SemanticTokenType::String),
+        Token::Chord(_) | Token::Denominator(_) => Some(SemanticTokenType::Type),
+        Token::Extension(_) => Some(SemanticTokenType::Number),
+        Token::MetaInfoKey(_) => Some(SemanticTokenType::Property),
+        Token::Equal
+        | Token::Comma
+        | Token::Slash
+        | Token::ChordBlockSeparator
+        | Token::MetaInfoStart
+        | Token::MetaInfoEnd
+        | Token::ExtensionStart
+        | Token::ExtensionEnd => Some(SemanticTokenType::Operator),
+        Token::RepeatStart | Token::RepeatEnd | Token::NavigationMark(_) => {
+            Some(SemanticTokenType::Decorator)
+        }
+    }
+}
+
+/// number of the characters of the token as written, since the length of the position is in bytes
+fn char_count(token: &Token, length: usize) -> usize {
+    match token {
+        Token::SectionMetaInfoKey(value)
+        | Token::SectionMetaInfoName(value)
+        | Token::SectionMetaInfoValue(value)
+        | Token::Chord(value)
+        | Token::Denominator(value)
+        | Token::MetaInfoKey(value)
+        | Token::MetaInfoValue(value)
+        | Token::Extension(value)
+        | Token::NavigationMark(value) => value.chars().count(),
+        _ => length,
+    }
+}
+
+/// UTF-16 offset and length of the characters from `column` (starting from 0) in the line
+fn utf16_range(line: &str, column: usize, char_count: usize) -> (u32, u32) {
+    let start: usize = line.chars().take(column).map(char::len_utf16).sum();
+    let length: usize = line
+        .chars()
+        .skip(column)
+        .take(char_count)
+        .map(char::len_utf16)
+        .sum();
+
+    (start as u32, length as u32)
+}
+
+/// Tokens of the input for highlighting, in the order of the positions.
+/// Only the tokenizer is run, so the input with the errors of the parser (like unknown chords) is also highlighted.
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::highlight::{semantic_tokens, SemanticToken, SemanticTokenType};
+///
+/// let tokens = semantic_tokens("[key=C]C").unwrap();
+/// assert_eq!(
+///     tokens[1],
+///     SemanticToken {
+///         line: 0,
+///         start: 1,
+///         length: 3,
+///         token_type: SemanticTokenType::Property,
+///     }
+/// );
+/// ```
+pub fn semantic_tokens(input: &str) -> Result<Vec<SemanticToken>, ErrorInfoWithPosition> {
+    let lines: Vec<&str> = input.split('\n').collect();
+    let mut semantic_tokens: Vec<SemanticToken> = vec![];
+
+    for token_with_position in tokenize(input)? {
+        let Some(token_type) = token_type(&token_with_position.token) else {
+            continue;
+        };
+        let position = &token_with_position.position;
+        let line = position.line_number - 1;
+        let (start, length) = utf16_range(
+            lines.get(line).unwrap_or(&""),
+            position.column_number - 1,
+            char_count(&token_with_position.token, position.length),
+        );
+
+        semantic_tokens.push(SemanticToken {
+            line: line as u32,
+            start,
+            length,
+            token_type,
+        });
+    }
+
+    // the comments are skipped by the tokenizer, so "//" not in a token starts a comment
+    for (line, text) in lines.iter().enumerate() {
+        let text = text.trim_end_matches('\r');
+        let comment = text.match_indices("//").find_map(|(index, _)| {
+            let start = text[..index].encode_utf16().count() as u32;
+            let is_in_token = semantic_tokens.iter().any(|token| {
+                token.line == line as u32
+                    && token.start <= start
+                    && start < token.start + token.length
+            });
+            (!is_in_token).then_some((start, text[index..].encode_utf16().count() as u32))
+        });
+
+        if let Some((start, length)) = comment {
+            semantic_tokens.push(SemanticToken {
+                line: line as u32,
+                start,
+                length,
+                token_type: SemanticTokenType::Comment,
+            });
+        }
+    }
+
+    semantic_tokens.sort_by_key(|token| (token.line, token.start));
+    Ok(semantic_tokens)
+}
+
+/// Encode the tokens as `data` of the semantic tokens of LSP, which Monaco also takes.
+/// Each token is 5 numbers: the line and the start relative to the previous token,
+/// the length, the index of the type in the legend, and the modifiers (always 0).
+pub fn encode_semantic_tokens(tokens: &[SemanticToken]) -> Vec<u32> {
+    let mut data: Vec<u32> = Vec::with_capacity(tokens.len() * 5);
+    let (mut previous_line, mut previous_start) = (0, 0);
+
+    for token in tokens {
+        let delta_line = token.line - previous_line;
+        let delta_start = if delta_line == 0 {
+            token.start - previous_start
+        } else {
+            token.start
+        };
+        data.extend([
+            delta_line,
+            delta_start,
+            token.length,
+            token.token_type as u32,
+            0,
+        ]);
+        (previous_line, previous_start) = (token.line, token.start);
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types(input: &str) -> Vec<(u32, u32, u32, SemanticTokenType)> {
+        semantic_tokens(input)
+            .unwrap()
+            .into_iter()
+            .map(|token| (token.line, token.start, token.length, token.token_type))
+            .collect()
+    }
+
+    #[test]
+    fn token_types() {
+        use SemanticTokenType::*;
+
+        assert_eq!(
+            types("@section=A\n|: [key=C]C(9)-G/B :| // end"),
+            vec![
+                (0, 0, 1, Keyword),
+                (0, 1, 7, Keyword),
+                (0, 8, 1, Operator),
+                (0, 9, 1, String),
+                (1, 0, 2, Decorator),
+                (1, 3, 1, Operator),
+                (1, 4, 3, Property),
+                (1, 7, 1, Operator),
+                (1, 8, 1, String),
+                (1, 9, 1, Operator),
+                (1, 10, 1, Type),
+                (1, 11, 1, Operator),
+                (1, 12, 1, Number),
+                (1, 13, 1, Operator),
+                (1, 14, 1, Operator),
+                (1, 15, 1, Type),
+                (1, 16, 1, Operator),
+                (1, 17, 1, Type),
+                (1, 19, 2, Decorator),
+                (1, 22, 6, Comment),
+            ]
+        );
+        // the chord which the parser rejects is highlighted
+        assert_eq!(types("Cxyz"), vec![(0, 0, 4, Type)]);
+    }
+
+    #[test]
+    fn utf16_columns() {
+        use SemanticTokenType::*;
+
+        // "𝄪" is 2 code units in UTF-16, and "♭" is 1
+        assert_eq!(
+            types("@section=𝄪\nB♭m"),
+            vec![
+                (0, 0, 1, Keyword),
+                (0, 1, 7, Keyword),
+                (0, 8, 1, Operator),
+                (0, 9, 2, String),
+                (1, 0, 3, Type),
+            ]
+        );
+    }
+
+    #[test]
+    fn encoded_relatively() {
+        let tokens = semantic_tokens("C - G\n// x\nAm").unwrap();
+
+        assert_eq!(legend()[SemanticTokenType::Type as usize], "type");
+        assert_eq!(
+            encode_semantic_tokens(&tokens),
+            vec![
+                0, 0, 1, 3, 0, // C
+                0, 2, 1, 6, 0, // -
+                0, 2, 1, 3, 0, // G
+                1, 0, 4, 8, 0, // comment
+                1, 0, 2, 3, 0, // Am
+            ]
+        );
+    }
+}
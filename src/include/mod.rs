@@ -0,0 +1,422 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::diagnostic::Diagnostic;
+use crate::error_code::{ErrorCode, ErrorInfo, ErrorInfoWithPosition};
+use crate::parser::options::ParseOptions;
+use crate::parser::types::ast::Ast;
+use crate::parser::types::section_meta::SectionMeta;
+use crate::parser::{parse_input_with_tokens, warnings};
+use crate::stringifier::trivia::{collect_trivia, section_items, Trivia};
+use crate::tokenizer::types::token::Token;
+use crate::util::position::Position;
+
+/// Loads the files written in `@include`
+pub trait SourceLoader {
+    /// name of the file which `path` in the file `from` refers to (`from` is `None` for the root input).
+    /// the name is used for loading, detecting cycles and reporting errors.
+    fn resolve(&self, path: &str, _from: Option<&str>) -> String {
+        path.to_string()
+    }
+
+    /// content of the file named by [`SourceLoader::resolve`]
+    fn load(&self, name: &str) -> Result<String, String>;
+}
+
+/// Loader from the files given in memory, keyed by the path written in `@include`
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MemoryLoader {
+    pub files: HashMap<String, String>,
+}
+
+impl MemoryLoader {
+    pub fn new(files: HashMap<String, String>) -> MemoryLoader {
+        MemoryLoader { files }
+    }
+}
+
+impl SourceLoader for MemoryLoader {
+    fn load(&self, name: &str) -> Result<String, String> {
+        self.files
+            .get(name)
+            .cloned()
+            .ok_or_else(|| "not found".to_string())
+    }
+}
+
+/// Loader calling a JS function `(path: string) => string | undefined` with the path written in `@include`
+#[derive(Debug, Clone)]
+pub struct JsCallbackLoader {
+    pub load: js_sys::Function,
+}
+
+impl SourceLoader for JsCallbackLoader {
+    fn load(&self, name: &str) -> Result<String, String> {
+        let content = self
+            .load
+            .call1(&JsValue::NULL, &JsValue::from_str(name))
+            .map_err(|error| match error.dyn_ref::<js_sys::Error>() {
+                Some(error) => String::from(error.message()),
+                None => format!("{:?}", error),
+            })?;
+
+        content.as_string().ok_or_else(|| "not found".to_string())
+    }
+}
+
+/// Loader from the file system. paths are relative to the directory of the file including them.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct FileSystemLoader;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SourceLoader for FileSystemLoader {
+    fn resolve(&self, path: &str, from: Option<&str>) -> String {
+        let path = match from.and_then(|from| std::path::Path::new(from).parent()) {
+            Some(directory) => directory.join(path),
+            None => std::path::PathBuf::from(path),
+        };
+        // canonical, so that the same file is detected as a cycle however it is referred to
+        std::fs::canonicalize(&path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn load(&self, name: &str) -> Result<String, String> {
+        std::fs::read_to_string(name).map_err(|error| error.to_string())
+    }
+}
+
+/// Error with the file it occurred in, to render the diagnostic
+#[derive(Debug, PartialEq)]
+pub struct SourceError {
+    /// name of the file (`None` for the root input without a name)
+    pub file_name: Option<String>,
+    /// content of the file
+    pub source: String,
+    pub error_info: ErrorInfoWithPosition,
+}
+
+/// Warning with the file it occurred in
+#[derive(Debug, PartialEq, Clone)]
+pub struct SourceDiagnostic {
+    /// name of the file (`None` for the root input without a name)
+    pub file_name: Option<String>,
+    pub diagnostic: Diagnostic,
+}
+
+/// the AST of a file with the included files
+struct ParsedFile {
+    ast: Ast,
+    /// empty unless it is kept
+    trivia: Trivia,
+    /// the warnings of the file, then the ones of the included files in the order of `@include`
+    warnings: Vec<SourceDiagnostic>,
+}
+
+/// Parse a chord progression string, replacing each section with `@include=path` by the sections of the file
+///
+/// # Example
+/// ```rust
+/// use std::collections::HashMap;
+/// use chord_progression_parser::include::{parse_with_includes, MemoryLoader};
+/// use chord_progression_parser::stringifier::stringify;
+///
+/// let loader = MemoryLoader::new(HashMap::from([(
+///     "intro.txt".to_string(),
+///     "@section=Intro\nC - G".to_string(),
+/// )]));
+/// let ast = parse_with_includes("@include=intro.txt\n\n@section=Verse\nF", None, &loader).unwrap();
+/// assert_eq!(stringify(&ast), "@section=Intro\nC - G\n\n@section=Verse\nF\n");
+/// ```
+pub fn parse_with_includes(
+    input: &str,
+    file_name: Option<&str>,
+    loader: &dyn SourceLoader,
+) -> Result<Ast, SourceError> {
+    Ok(parse_files(input, file_name, loader, false)?.ast)
+}
+
+/// Same as [`parse_with_includes`], also returning the warnings of the files
+/// (like [`parse_chord_progression_string_with_warnings`](crate::parse_chord_progression_string_with_warnings)):
+/// the ones of the input, then the ones of the included files in the order of `@include`
+///
+/// # Example
+/// ```rust
+/// use std::collections::HashMap;
+/// use chord_progression_parser::error_code::ErrorCode;
+/// use chord_progression_parser::include::{parse_with_includes_and_warnings, MemoryLoader};
+///
+/// let loader = MemoryLoader::new(HashMap::from([(
+///     "intro.txt".to_string(),
+///     "@section=Intro\nC - G,".to_string(),
+/// )]));
+/// let (_, warnings) =
+///     parse_with_includes_and_warnings("@include=intro.txt\n\n@section=Verse\nF", None, &loader)
+///         .unwrap();
+/// assert_eq!(warnings[0].file_name, Some("intro.txt".to_string()));
+/// assert_eq!(warnings[0].diagnostic.error.code, ErrorCode::Chb4);
+/// ```
+pub fn parse_with_includes_and_warnings(
+    input: &str,
+    file_name: Option<&str>,
+    loader: &dyn SourceLoader,
+) -> Result<(Ast, Vec<SourceDiagnostic>), SourceError> {
+    let parsed = parse_files(input, file_name, loader, false)?;
+
+    Ok((parsed.ast, parsed.warnings))
+}
+
+/// Same as [`parse_with_includes`], also collecting the trivia (like the comments) of the files,
+/// to write them back with [`stringify_with_trivia`](crate::stringifier::stringify_with_trivia)
+///
+/// # Example
+/// ```rust
+/// use std::collections::HashMap;
+/// use chord_progression_parser::include::{parse_with_includes_and_trivia, MemoryLoader};
+/// use chord_progression_parser::stringifier::stringify_with_trivia;
+///
+/// let loader = MemoryLoader::new(HashMap::from([(
+///     "intro.txt".to_string(),
+///     "// shared intro\n@section=Intro\nC - G".to_string(),
+/// )]));
+/// let input = "@include=intro.txt\n\n@section=Verse\nF // to the chorus";
+/// let (ast, trivia) = parse_with_includes_and_trivia(input, None, &loader).unwrap();
+/// assert_eq!(
+///     stringify_with_trivia(&ast, &trivia, &Default::default()),
+///     "// shared intro\n@section=Intro\nC - G\n\n@section=Verse\nF // to the chorus\n"
+/// );
+/// ```
+pub fn parse_with_includes_and_trivia(
+    input: &str,
+    file_name: Option<&str>,
+    loader: &dyn SourceLoader,
+) -> Result<(Ast, Trivia), SourceError> {
+    let parsed = parse_files(input, file_name, loader, true)?;
+
+    Ok((parsed.ast, parsed.trivia))
+}
+
+/// the AST with the included files, with the trivia if `keep_trivia`
+fn parse_files(
+    input: &str,
+    file_name: Option<&str>,
+    loader: &dyn SourceLoader,
+    keep_trivia: bool,
+) -> Result<ParsedFile, SourceError> {
+    let mut including: Vec<String> = file_name
+        .iter()
+        .map(|name| loader.resolve(name, None))
+        .collect();
+
+    parse_file(input, file_name, loader, &mut including, keep_trivia)
+}
+
+/// `including` is the files being included, for detecting cycles
+fn parse_file(
+    input: &str,
+    file_name: Option<&str>,
+    loader: &dyn SourceLoader,
+    including: &mut Vec<String>,
+    keep_trivia: bool,
+) -> Result<ParsedFile, SourceError> {
+    let source_error = |error_info: ErrorInfoWithPosition| SourceError {
+        file_name: file_name.map(|name| name.to_string()),
+        source: input.to_string(),
+        error_info,
+    };
+    let error_at = |code: ErrorCode, additional_info: String, position: &Position| {
+        source_error(ErrorInfoWithPosition {
+            error: ErrorInfo {
+                code,
+                additional_info: Some(additional_info),
+                cause: None,
+            },
+            position: position.clone(),
+        })
+    };
+
+    let (ast, tokens) =
+        parse_input_with_tokens(input, &ParseOptions::default()).map_err(source_error)?;
+    let mut file_warnings: Vec<SourceDiagnostic> = warnings(&tokens)
+        .into_iter()
+        .map(|diagnostic| SourceDiagnostic {
+            file_name: file_name.map(|name| name.to_string()),
+            diagnostic,
+        })
+        .collect();
+    let mut trivia = match keep_trivia {
+        true => collect_trivia(input).map_err(source_error)?,
+        false => Trivia::default(),
+    };
+
+    // positions of the values of `@include`, in the same order as in the AST
+    let positions: Vec<&Position> = tokens
+        .windows(3)
+        .filter_map(|window| match (&window[0].token, &window[2].token) {
+            (Token::SectionMetaInfoKey(key), Token::SectionMetaInfoValue(_))
+                if key == "include" =>
+            {
+                Some(&window[2].position)
+            }
+            _ => None,
+        })
+        .collect();
+    let mut positions = positions.into_iter();
+
+    let mut sections: Ast = vec![];
+    // index of the first item of the section in the trivia
+    let mut item = 0;
+    for section in ast.into_iter() {
+        let path = match section
+            .meta_infos
+            .iter()
+            .find_map(|meta_info| match meta_info {
+                SectionMeta::Include(path) => Some(path.clone()),
+                _ => None,
+            }) {
+            Some(path) => path,
+            None => {
+                item += section_items(&section);
+                sections.push(section);
+                continue;
+            }
+        };
+        let position = positions.next().unwrap();
+
+        if section.meta_infos.len() > 1 || !section.chord_blocks.is_empty() {
+            return Err(error_at(ErrorCode::Inc3, path, position));
+        }
+
+        let name = loader.resolve(&path, file_name);
+        if including.contains(&name) {
+            return Err(error_at(ErrorCode::Inc2, name, position));
+        }
+        let content = loader.load(&name).map_err(|message| {
+            error_at(ErrorCode::Inc1, format!("{}: {}", name, message), position)
+        })?;
+
+        including.push(name.clone());
+        let included = parse_file(&content, Some(&name), loader, including, keep_trivia)?;
+        including.pop();
+
+        if keep_trivia {
+            let items = included.trivia.items;
+            trivia.replace_item(item, included.trivia);
+            item += items;
+        }
+        sections.extend(included.ast);
+        file_warnings.extend(included.warnings);
+    }
+
+    Ok(ParsedFile {
+        ast: sections,
+        trivia,
+        warnings: file_warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stringifier::{stringify, stringify_with_trivia, StringifyOptions};
+
+    fn loader(files: &[(&str, &str)]) -> MemoryLoader {
+        MemoryLoader::new(
+            files
+                .iter()
+                .map(|(name, content)| (name.to_string(), content.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn nested_includes() {
+        let loader = loader(&[
+            ("medley.txt", "@include=intro.txt\n\n@section=A\nF"),
+            ("intro.txt", "@section=Intro\nC - G"),
+        ]);
+        let ast = parse_with_includes(
+            "@include=medley.txt\n\n@section=Outro\nC",
+            Some("song.txt"),
+            &loader,
+        )
+        .unwrap();
+
+        assert_eq!(
+            stringify(&ast),
+            "@section=Intro\nC - G\n\n@section=A\nF\n\n@section=Outro\nC\n"
+        );
+    }
+
+    #[test]
+    fn comments_of_included_files() {
+        let loader = loader(&[
+            (
+                "medley.txt",
+                "@include=intro.txt\n\n@section=A // medley\nF",
+            ),
+            (
+                "intro.txt",
+                "// shared intro\n@def X=G // dominant\n@section=Intro\nC - $X // to the verse",
+            ),
+        ]);
+        let (ast, trivia) = parse_with_includes_and_trivia(
+            "// song\n@include=medley.txt // the medley\n\n@section=Outro\nC // end",
+            Some("song.txt"),
+            &loader,
+        )
+        .unwrap();
+
+        assert_eq!(
+            stringify_with_trivia(&ast, &trivia, &StringifyOptions::default()),
+            "// song\n// shared intro\n// dominant\n@section=Intro\nC - G // to the verse\n\n@section=A // medley\nF\n// the medley\n\n@section=Outro\nC // end\n"
+        );
+    }
+
+    #[test]
+    fn error_in_included_file_names_the_file() {
+        let loader = loader(&[("intro.txt", "C - H")]);
+        let error =
+            parse_with_includes("@include=intro.txt", Some("song.txt"), &loader).unwrap_err();
+
+        assert_eq!(error.file_name, Some("intro.txt".to_string()));
+        assert_eq!(error.source, "C - H");
+        assert_eq!(error.error_info.error.code, ErrorCode::Cho1);
+        assert_eq!(error.error_info.position.column_number, 5);
+    }
+
+    #[test]
+    fn invalid_includes() {
+        let loader = loader(&[("a.txt", "@include=b.txt"), ("b.txt", "@include=a.txt")]);
+        let error =
+            |input: &str| parse_with_includes(input, Some("song.txt"), &loader).unwrap_err();
+
+        let not_found = error("C\n\n@include=c.txt");
+        assert_eq!(not_found.file_name, Some("song.txt".to_string()));
+        assert_eq!(not_found.error_info.error.code, ErrorCode::Inc1);
+        assert_eq!(
+            not_found.error_info.position,
+            Position {
+                line_number: 3,
+                column_number: 10,
+                length: 5,
+            }
+        );
+
+        let cyclic = error("@include=a.txt");
+        assert_eq!(cyclic.file_name, Some("b.txt".to_string()));
+        assert_eq!(cyclic.error_info.error.code, ErrorCode::Inc2);
+
+        assert_eq!(
+            error("@include=song.txt").error_info.error.code,
+            ErrorCode::Inc2
+        );
+        assert_eq!(
+            error("@section=A\n@include=a.txt").error_info.error.code,
+            ErrorCode::Inc3
+        );
+    }
+}
@@ -1,47 +1,25 @@
-mod error_code;
-mod parser;
-mod tokenizer;
-mod util;
-use error_code::ErrorInfoWithPosition;
-use parser::{parse, Ast};
-use serde_json::json;
-use tokenizer::tokenize;
-use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
-
-// FIXME:
-//  "serde_wasm_bindgen::to_value(&json_result).unwrap()" makes Map.
-//  But I want to generate JSON.
-//  So currently use deprecated from_serde() instead.
+pub mod diagnostic;
+pub mod error_code;
+pub mod exporter;
+pub mod highlight;
+pub mod include;
+pub mod linter;
+pub mod locale;
+pub mod parser;
+pub mod stringifier;
+pub mod suggestion;
+pub mod timeline;
+pub mod tokenizer;
+pub mod transposer;
+pub mod util;
 #[doc(hidden)]
-/// @param {string} input - The chord progression string to parse.
-/// @returns {ParsedResult} - The parsed result.
-/// @throws {string} - The error information.
-#[wasm_bindgen(js_name = "parseChordProgressionString", skip_jsdoc)]
-pub fn parse_chord_progression_string_js(input: &str) -> JsValue {
-    let result = parse_chord_progression_string(input);
-
-    let json_result = if let Err(error_info) = result {
-        json!({
-            "success": false,
-            "error": {
-                "code": error_info.error.code.to_string(),
-                "additionalInfo": error_info.error.additional_info,
-                "position": {
-                    "lineNumber": error_info.position.line_number,
-                    "columnNumber": error_info.position.column_number,
-                    "length": error_info.position.length,
-                },
-            }
-        })
-    } else {
-        json!({
-            "success": true,
-            "ast": result.unwrap(),
-        })
-    };
-
-    JsValue::from_serde(&json_result).unwrap()
-}
+pub mod wasm;
+use diagnostic::Diagnostic;
+use error_code::ErrorInfoWithPosition;
+use linter::{lint, LintConfig, LintWarning};
+use parser::meta_registry::MetaRegistry;
+use parser::options::{check_input_size, ParseOptions};
+use parser::{parse_input, parse_input_with_tokens, warnings, Ast};
 
 /// Parse a chord progression string and return the AST
 ///
@@ -67,24 +45,94 @@ pub fn parse_chord_progression_string_js(input: &str) -> JsValue {
 ///
 /// Panics if unhandled error occurs.
 pub fn parse_chord_progression_string(input: &str) -> Result<Ast, ErrorInfoWithPosition> {
-    let tokenized_result = tokenize(input);
-    if tokenized_result.is_err() {
-        return Err(tokenized_result.err().unwrap());
-    }
+    parse_input(input, &ParseOptions::default())
+}
 
-    let parsed_result = parse(&tokenized_result.unwrap());
-    if parsed_result.is_err() {
-        return Err(parsed_result.err().unwrap());
-    }
+/// Parse a chord progression string with the custom meta keys of the registry
+///
+/// See [`MetaRegistry`] for the example.
+pub fn parse_chord_progression_string_with_registry(
+    input: &str,
+    registry: &MetaRegistry,
+) -> Result<Ast, ErrorInfoWithPosition> {
+    parse_chord_progression_string_with_options(
+        input,
+        &ParseOptions {
+            registry: registry.clone(),
+            ..Default::default()
+        },
+    )
+}
 
-    Ok(parsed_result.unwrap())
+/// Parse a chord progression string with the options
+///
+/// See [`ParseOptions`] for the example.
+pub fn parse_chord_progression_string_with_options(
+    input: &str,
+    options: &ParseOptions,
+) -> Result<Ast, ErrorInfoWithPosition> {
+    check_input_size(input, options)?;
+
+    parse_input(input, options)
+}
+
+/// Parse a chord progression string with the options, and also return the warnings of the valid input
+///
+/// The warnings are the trailing commas which are ignored (`CHB-4`),
+/// and the key changes which have no effect (`SEM-1`) or are reverted at the next chord (`SEM-2`).
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::diagnostic::Severity;
+/// use chord_progression_parser::error_code::ErrorCode;
+/// use chord_progression_parser::parse_chord_progression_string_with_warnings;
+/// use chord_progression_parser::parser::options::ParseOptions;
+///
+/// let (ast, warnings) =
+///     parse_chord_progression_string_with_warnings("C/D,", &ParseOptions::default()).unwrap();
+/// assert_eq!(ast.len(), 1);
+/// assert_eq!(warnings[0].severity, Severity::Warning);
+/// assert_eq!(warnings[0].error.code, ErrorCode::Chb4);
+/// ```
+pub fn parse_chord_progression_string_with_warnings(
+    input: &str,
+    options: &ParseOptions,
+) -> Result<(Ast, Vec<Diagnostic>), ErrorInfoWithPosition> {
+    check_input_size(input, options)?;
+    let (ast, tokens) = parse_input_with_tokens(input, options)?;
+
+    Ok((ast, warnings(&tokens)))
+}
+
+/// Lint a chord progression string with the rules of the config
+///
+/// The input is parsed with the options, and the errors are returned as the other functions.
+/// See [`LintConfig`] for the example. The rules can be suppressed by the comments, like `// lint-disable-line unknown-chord`.
+pub fn lint_chord_progression_string(
+    input: &str,
+    options: &ParseOptions,
+    config: &LintConfig,
+) -> Result<Vec<LintWarning>, ErrorInfoWithPosition> {
+    check_input_size(input, options)?;
+    let (ast, tokens) = parse_input_with_tokens(input, options)?;
+
+    Ok(lint(input, &tokens, &ast, config))
 }
 
 #[cfg(test)]
 mod tests {
     #[cfg(test)]
     mod success {
-        use crate::parse_chord_progression_string;
+        use std::collections::HashMap;
+
+        use crate::parser::meta_registry::{MetaRegistry, MetaValueValidator};
+        use crate::parser::options::{DuplicateMetaPolicy, ParseOptions};
+        use crate::parser::types::chord_block::ChordBlock;
+        use crate::stringifier::stringify;
+        use crate::{
+            parse_chord_progression_string, parse_chord_progression_string_with_options,
+            parse_chord_progression_string_with_registry,
+        };
         use serde_json::json;
 
         // if C/D, is input, comma is ignored
@@ -362,10 +410,536 @@ C-A,B
 
             assert_eq!(result_json, expected);
         }
+
+        #[test]
+        fn quality_aliases_keep_plain_as_written() {
+            let input: &str = "Cmaj7(+9)-F#-7";
+
+            let result_json = json!(parse_chord_progression_string(input).unwrap());
+            let expected = json!([
+                {
+                    "chordBlocks": [
+                        {
+                            "type": "bar",
+                            "value": [
+                                {
+                                    "chordExpression": {
+                                        "type": "chord",
+                                        "value": {
+                                            "detailed": {
+                                                "accidental": null,
+                                                "base": "C",
+                                                "chordType": "M",
+                                                "extensions": ["M7", "#9"]
+                                            },
+                                            "plain": "Cmaj7(+9)"
+                                        }
+                                    },
+                                    "denominator": null,
+                                    "metaInfos": []
+                                }
+                            ]
+                        },
+                        {
+                            "type": "bar",
+                            "value": [
+                                {
+                                    "chordExpression": {
+                                        "type": "chord",
+                                        "value": {
+                                            "detailed": {
+                                                "accidental": "#",
+                                                "base": "F",
+                                                "chordType": "m",
+                                                "extensions": ["7"]
+                                            },
+                                            "plain": "F#-7"
+                                        }
+                                    },
+                                    "denominator": null,
+                                    "metaInfos": []
+                                }
+                            ]
+                        }
+                    ],
+                    "metaInfos": []
+                }
+            ]);
+
+            assert_eq!(result_json, expected);
+        }
+
+        #[test]
+        fn unicode_accidentals_in_key_and_chord() {
+            let input: &str = "[key=B♭]B♭m(7)/D♭";
+
+            let result_json = json!(parse_chord_progression_string(input).unwrap());
+            let expected = json!([
+                {
+                    "chordBlocks": [
+                        {
+                            "type": "bar",
+                            "value": [
+                                {
+                                    "chordExpression": {
+                                        "type": "chord",
+                                        "value": {
+                                            "detailed": {
+                                                "accidental": "b",
+                                                "base": "B",
+                                                "chordType": "m",
+                                                "extensions": ["7"]
+                                            },
+                                            "plain": "B♭m(7)"
+                                        }
+                                    },
+                                    "denominator": "D♭",
+                                    "metaInfos": [
+                                        {
+                                            "type": "key",
+                                            "value": "Bb",
+                                        }
+                                    ]
+                                }
+                            ]
+                        }
+                    ],
+                    "metaInfos": []
+                }
+            ]);
+
+            assert_eq!(result_json, expected);
+        }
+
+        #[test]
+        fn poly_chord() {
+            let input: &str = "{D|C7}/E";
+
+            let result_json = json!(parse_chord_progression_string(input).unwrap());
+            let expected = json!([
+                {
+                    "chordBlocks": [
+                        {
+                            "type": "bar",
+                            "value": [
+                                {
+                                    "chordExpression": {
+                                        "type": "polyChord",
+                                        "value": {
+                                            "upper": {
+                                                "detailed": {
+                                                    "accidental": null,
+                                                    "base": "D",
+                                                    "chordType": "M",
+                                                    "extensions": []
+                                                },
+                                                "plain": "D"
+                                            },
+                                            "lower": {
+                                                "detailed": {
+                                                    "accidental": null,
+                                                    "base": "C",
+                                                    "chordType": "M",
+                                                    "extensions": ["7"]
+                                                },
+                                                "plain": "C7"
+                                            }
+                                        }
+                                    },
+                                    "denominator": "E",
+                                    "metaInfos": []
+                                }
+                            ]
+                        }
+                    ],
+                    "metaInfos": []
+                }
+            ]);
+
+            assert_eq!(result_json, expected);
+        }
+
+        #[test]
+        fn navigation_marks() {
+            let input: &str = "|: C :| <D.C.>";
+
+            let result_json = json!(parse_chord_progression_string(input).unwrap());
+            let expected = json!([
+                {
+                    "chordBlocks": [
+                        { "type": "navigation", "value": { "type": "repeatStart" } },
+                        {
+                            "type": "bar",
+                            "value": [
+                                {
+                                    "chordExpression": {
+                                        "type": "chord",
+                                        "value": {
+                                            "detailed": {
+                                                "accidental": null,
+                                                "base": "C",
+                                                "chordType": "M",
+                                                "extensions": []
+                                            },
+                                            "plain": "C"
+                                        }
+                                    },
+                                    "denominator": null,
+                                    "metaInfos": []
+                                }
+                            ]
+                        },
+                        { "type": "navigation", "value": { "type": "repeatEnd" } },
+                        { "type": "navigation", "value": { "type": "daCapo" } }
+                    ],
+                    "metaInfos": []
+                }
+            ]);
+
+            assert_eq!(result_json, expected);
+        }
+
+        #[test]
+        fn performance_meta_infos() {
+            let input: &str =
+                "[tempo=96][dynamics=ff][articulation=stab]C - [rit][fermata]G - [accel]%";
+
+            let ast = parse_chord_progression_string(input).unwrap();
+            let meta_infos = json!(ast[0]
+                .chord_blocks
+                .iter()
+                .map(|chord_block| match chord_block {
+                    ChordBlock::Bar(bar) => bar[0].meta_infos.clone(),
+                    _ => vec![],
+                })
+                .collect::<Vec<_>>());
+            let expected = json!([
+                [
+                    { "type": "tempo", "value": 96 },
+                    { "type": "dynamics", "value": "ff" },
+                    { "type": "articulation", "value": "stab" }
+                ],
+                [{ "type": "ritardando" }, { "type": "fermata" }],
+                [{ "type": "accelerando" }]
+            ]);
+
+            assert_eq!(meta_infos, expected);
+            assert_eq!(stringify(&ast), format!("{}\n", input));
+        }
+
+        #[test]
+        fn custom_meta_infos() {
+            let registry = MetaRegistry::new()
+                .with_section_meta("mood", MetaValueValidator::String)
+                .with_chord_meta(
+                    "capo",
+                    MetaValueValidator::Integer {
+                        min: Some(0),
+                        max: Some(12),
+                    },
+                );
+            let input: &str = "@section=A\n@mood=calm\n[capo=2][key=C]C - G";
+
+            let ast = parse_chord_progression_string_with_registry(input, &registry).unwrap();
+            assert_eq!(
+                json!(ast[0].meta_infos),
+                json!([
+                    { "type": "section", "value": "A" },
+                    { "type": "custom", "value": { "key": "mood", "value": "calm" } }
+                ])
+            );
+            match &ast[0].chord_blocks[0] {
+                ChordBlock::Bar(bar) => assert_eq!(
+                    json!(bar[0].meta_infos),
+                    json!([
+                        { "type": "custom", "value": { "key": "capo", "value": "2" } },
+                        { "type": "key", "value": "C" }
+                    ])
+                ),
+                _ => panic!("bar expected"),
+            }
+            assert_eq!(stringify(&ast), format!("{}\n", input));
+        }
+
+        #[test]
+        fn lenient_options() {
+            let options = ParseOptions {
+                allow_consecutive_blank_lines: true,
+                allow_same_at_section_start: true,
+                chord_aliases: HashMap::from([("ma7".to_string(), "M7".to_string())]),
+                case_insensitive_section_keys: true,
+                duplicate_meta: DuplicateMetaPolicy::KeepLast,
+                ..Default::default()
+            };
+            let input: &str =
+                "@Section=Intro\n@SECTION=A\n[key=C][key=D]Cma7 - G\n  \n\n\n@section=B\n% - C";
+
+            let ast = parse_chord_progression_string_with_options(input, &options).unwrap();
+            assert_eq!(
+                stringify(&ast),
+                "@section=A\n[key=D]CM7 - G\n\n@section=B\n% - C\n"
+            );
+        }
     }
 
     mod failure {
-        use crate::{parse_chord_progression_string, util::position::Position};
+        use crate::error_code::ErrorCode;
+        use crate::parser::meta_registry::{MetaRegistry, MetaValueValidator};
+        use crate::parser::options::{DuplicateMetaPolicy, ParseOptions};
+        use crate::{
+            parse_chord_progression_string, parse_chord_progression_string_with_options,
+            parse_chord_progression_string_with_registry, util::position::Position,
+        };
+
+        #[test]
+        fn missing_coda() {
+            let error_info =
+                parse_chord_progression_string("C <to coda> F\nG <D.C. al Coda>").unwrap_err();
+
+            assert_eq!(error_info.error.code, ErrorCode::Nav6);
+            assert_eq!(
+                error_info.position,
+                Position {
+                    line_number: 1,
+                    column_number: 3,
+                    length: 9,
+                },
+            )
+        }
+
+        #[test]
+        fn comma_at_section_start() {
+            let error_info = parse_chord_progression_string("@section=A\n, C").unwrap_err();
+
+            assert_eq!(error_info.error.code, ErrorCode::Tkn1);
+            assert_eq!(
+                error_info.position,
+                Position {
+                    line_number: 2,
+                    column_number: 1,
+                    length: 1,
+                },
+            )
+        }
+
+        #[test]
+        fn extension_after_poly_chord() {
+            let error_info = parse_chord_progression_string("C - {D|C}(9)").unwrap_err();
+
+            assert_eq!(error_info.error.code, ErrorCode::Cho6);
+            assert_eq!(error_info.error.additional_info, Some("9".to_string()));
+            assert_eq!(
+                error_info.position,
+                Position {
+                    line_number: 1,
+                    column_number: 11,
+                    length: 1,
+                },
+            )
+        }
+
+        #[test]
+        fn incomplete_input() {
+            for (input, code, column_number) in [
+                ("@", ErrorCode::Smik2, 1),
+                ("@section", ErrorCode::Smik2, 1),
+                ("[", ErrorCode::Cimk2, 1),
+                ("C(", ErrorCode::Ext2, 2),
+                ("(9)C", ErrorCode::Cho3, 2),
+            ] {
+                let error_info = parse_chord_progression_string(input).unwrap_err();
+
+                assert_eq!(error_info.error.code, code, "{}", input);
+                assert_eq!(
+                    error_info.position.column_number, column_number,
+                    "{}",
+                    input
+                );
+            }
+        }
+
+        #[test]
+        fn unclosed_repeat() {
+            let error_info = parse_chord_progression_string("C\n|: F - G").unwrap_err();
+
+            assert_eq!(error_info.error.code, ErrorCode::Nav2);
+            assert_eq!(
+                error_info.position,
+                Position {
+                    line_number: 2,
+                    column_number: 1,
+                    length: 2,
+                },
+            )
+        }
+
+        #[test]
+        fn unclosed_poly_chord() {
+            let error_info = parse_chord_progression_string("C-{D|C7").unwrap_err();
+
+            assert_eq!(error_info.error.code, ErrorCode::Cho1);
+            assert_eq!(error_info.error.additional_info, Some("{D|C7".to_string()));
+            assert_eq!(error_info.error.cause, Some(ErrorCode::Cho4));
+            assert_eq!(
+                error_info.position,
+                Position {
+                    line_number: 1,
+                    column_number: 3,
+                    length: 5,
+                },
+            )
+        }
+
+        #[test]
+        fn invalid_performance_meta_infos() {
+            let error_info = |input: &str| parse_chord_progression_string(input).unwrap_err();
+
+            let no_value = error_info("C - [tempo]G");
+            assert_eq!(no_value.error.code, ErrorCode::Cimk4);
+            assert_eq!(
+                no_value.position,
+                Position {
+                    line_number: 1,
+                    column_number: 6,
+                    length: 5,
+                },
+            );
+            assert_eq!(error_info("[fermata=1]C").error.code, ErrorCode::Cimk5);
+            assert_eq!(error_info("[foo]C").error.code, ErrorCode::Cimk3);
+
+            let invalid_value = error_info("[tempo=0]C");
+            assert_eq!(invalid_value.error.code, ErrorCode::Cimv6);
+            assert_eq!(
+                invalid_value.position,
+                Position {
+                    line_number: 1,
+                    column_number: 8,
+                    length: 1,
+                },
+            );
+            assert_eq!(error_info("[dynamics=fff]C").error.code, ErrorCode::Cimv7);
+            assert_eq!(
+                error_info("[articulation=loud]C").error.code,
+                ErrorCode::Cimv8
+            );
+        }
+
+        #[test]
+        fn invalid_custom_meta_infos() {
+            let registry = MetaRegistry::new()
+                .with_section_meta(
+                    "mood",
+                    MetaValueValidator::Enum(vec!["calm".to_string(), "loud".to_string()]),
+                )
+                .with_chord_meta("cue", MetaValueValidator::Regex("[A-Z]\\d".to_string()));
+            let error_info = |input: &str| {
+                parse_chord_progression_string_with_registry(input, &registry).unwrap_err()
+            };
+
+            let section_meta = error_info("@mood=quiet\nC");
+            assert_eq!(section_meta.error.code, ErrorCode::Smiv8);
+            assert_eq!(
+                section_meta.error.additional_info,
+                Some("quiet (one of calm, loud)".to_string())
+            );
+            assert_eq!(
+                section_meta.position,
+                Position {
+                    line_number: 1,
+                    column_number: 7,
+                    length: 5,
+                },
+            );
+            let chord_meta = error_info("C - [cue=a1]G");
+            assert_eq!(chord_meta.error.code, ErrorCode::Cimv9);
+            assert_eq!(
+                chord_meta.position,
+                Position {
+                    line_number: 1,
+                    column_number: 10,
+                    length: 2,
+                },
+            );
+            assert_eq!(error_info("[cue]C").error.code, ErrorCode::Cimk4);
+            // not registered
+            assert_eq!(
+                parse_chord_progression_string("@mood=calm\nC")
+                    .unwrap_err()
+                    .error
+                    .code,
+                ErrorCode::Smik1
+            );
+            assert_eq!(
+                parse_chord_progression_string("[cue=A1]C")
+                    .unwrap_err()
+                    .error
+                    .code,
+                ErrorCode::Cimk3
+            );
+        }
+
+        #[test]
+        fn invalid_meta_info_semantics() {
+            let error_info = |input: &str| parse_chord_progression_string(input).unwrap_err();
+
+            let zero_repeat = error_info("@section=A\n@repeat=0\nC");
+            assert_eq!(zero_repeat.error.code, ErrorCode::Smiv9);
+            assert_eq!(
+                zero_repeat.position,
+                Position {
+                    line_number: 2,
+                    column_number: 9,
+                    length: 1,
+                },
+            );
+            assert_eq!(
+                error_info("@section=A\n@section=B\nC").error.code,
+                ErrorCode::Smik3
+            );
+            assert_eq!(error_info("[key=C][key=D]C").error.code, ErrorCode::Cimk6);
+            assert_eq!(error_info("C - G[key=D]").error.code, ErrorCode::Cimk7);
+        }
+
+        #[test]
+        fn strict_options() {
+            let options = ParseOptions {
+                duplicate_meta: DuplicateMetaPolicy::Error,
+                max_input_size: Some(64),
+                ..Default::default()
+            };
+            let error_info = |input: &str| {
+                parse_chord_progression_string_with_options(input, &options).unwrap_err()
+            };
+
+            let section_meta = error_info("@section=A\n@repeat=2\n@section=B\nC");
+            assert_eq!(section_meta.error.code, ErrorCode::Smik3);
+            assert_eq!(
+                section_meta.position,
+                Position {
+                    line_number: 3,
+                    column_number: 2,
+                    length: 7,
+                },
+            );
+            let chord_meta = error_info("C - [fermata][tempo=90][fermata]G");
+            assert_eq!(chord_meta.error.code, ErrorCode::Cimk6);
+            assert_eq!(
+                chord_meta.position,
+                Position {
+                    line_number: 1,
+                    column_number: 25,
+                    length: 7,
+                },
+            );
+            assert_eq!(error_info(&"C - ".repeat(20)).error.code, ErrorCode::Other2);
+            // the default is strict
+            assert_eq!(error_info("C\n\n\nG").error.code, ErrorCode::Bl1);
+            assert_eq!(
+                error_info("@section=A\nC\n\n@section=B\n%").error.code,
+                ErrorCode::Chb1
+            );
+            assert_eq!(error_info("@Section=A\nC").error.code, ErrorCode::Smik1);
+        }
 
         #[test]
         fn tension_position_when_error() {
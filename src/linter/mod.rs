@@ -0,0 +1,303 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+use typeshare::typeshare;
+
+pub use crate::diagnostic::Severity;
+use crate::locale;
+use crate::parser::Ast;
+use crate::tokenizer::types::token_with_position::TokenWithPosition;
+use crate::util::position::Position;
+
+mod rules;
+
+use rules::LintContext;
+
+/// Rules of the linter, identified by the kebab-case names (like `unknown-chord`)
+#[typeshare]
+#[derive(
+    Debug, PartialEq, Eq, Hash, Clone, Copy, Display, EnumString, EnumIter, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum LintRule {
+    /// a line has a different number of bars from the other lines of the section
+    BarsPerLine,
+    /// the root of the chord is not in the scale of the key (`[key=...]`)
+    ChordOutOfKey,
+    /// `?` is left in the chord progression
+    UnknownChord,
+    /// `%` repeats the chord in the same bar, so the chord can be written once
+    RedundantSame,
+    /// the extensions in parenthesis are not in the order of the degrees, like `C7(#11,9)`
+    ExtensionOrder,
+    /// a section has no `@section`, while the chord progression has more than one section
+    UnnamedSection,
+    /// the bars after the volta (like `<3.>`) are never played
+    UnreachableVolta,
+}
+
+impl LintRule {
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            LintRule::UnknownChord | LintRule::UnreachableVolta => Severity::Warning,
+            LintRule::BarsPerLine | LintRule::ChordOutOfKey | LintRule::UnnamedSection => {
+                Severity::Info
+            }
+            LintRule::RedundantSame | LintRule::ExtensionOrder => Severity::Hint,
+        }
+    }
+
+    /// Localized message of the rule, from the catalogue of [`crate::locale`].
+    /// Unknown locales fall back to [`crate::locale::DEFAULT_LOCALE`].
+    pub fn message(&self, locale: &str) -> String {
+        locale::lint_message(self, locale)
+    }
+}
+
+/// Which rules are checked, and with which severities
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::linter::{LintConfig, LintRule, Severity};
+/// use chord_progression_parser::lint_chord_progression_string;
+/// use chord_progression_parser::parser::options::ParseOptions;
+///
+/// let config = LintConfig::new()
+///     .disable(LintRule::UnnamedSection)
+///     .with_severity(LintRule::ChordOutOfKey, Severity::Warning);
+/// let warnings =
+///     lint_chord_progression_string("[key=C]C - Bb - ?", &ParseOptions::default(), &config).unwrap();
+///
+/// assert_eq!(warnings[0].rule, LintRule::ChordOutOfKey);
+/// assert_eq!(warnings[0].severity, Severity::Warning);
+/// assert_eq!(warnings[1].rule, LintRule::UnknownChord);
+/// ```
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LintConfig {
+    /// rules not checked
+    pub disabled: Vec<LintRule>,
+    /// severities overriding the defaults of the rules
+    pub severities: HashMap<LintRule, Severity>,
+}
+
+impl LintConfig {
+    pub fn new() -> LintConfig {
+        LintConfig::default()
+    }
+
+    pub fn disable(mut self, rule: LintRule) -> LintConfig {
+        self.disabled.push(rule);
+        self
+    }
+
+    pub fn with_severity(mut self, rule: LintRule, severity: Severity) -> LintConfig {
+        self.severities.insert(rule, severity);
+        self
+    }
+
+    /// severity of the rule, or None if it is disabled
+    pub fn severity(&self, rule: LintRule) -> Option<Severity> {
+        if self.disabled.contains(&rule) {
+            return None;
+        }
+        Some(
+            self.severities
+                .get(&rule)
+                .copied()
+                .unwrap_or(rule.default_severity()),
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LintWarning {
+    pub rule: LintRule,
+    pub severity: Severity,
+    pub additional_info: Option<String>,
+    pub position: Position,
+}
+
+/// rules suppressed on a line. `all` is for the comment without rule names.
+#[derive(Debug, PartialEq, Clone, Default)]
+struct Suppressed {
+    all: bool,
+    rules: HashSet<LintRule>,
+}
+
+impl Suppressed {
+    fn add(&mut self, rules: &Option<Vec<LintRule>>) {
+        match rules {
+            Some(rules) => self.rules.extend(rules.iter().copied()),
+            None => self.all = true,
+        }
+    }
+
+    fn contains(&self, rule: LintRule) -> bool {
+        self.all || self.rules.contains(&rule)
+    }
+}
+
+/// Rules suppressed on each line by the comments:
+/// - `// lint-disable-line rule-a, rule-b` at the end of the line
+/// - `// lint-disable-next-line rule-a` on the line before
+/// - `// lint-disable rule-a` to `// lint-enable rule-a` (or the end of the input)
+///
+/// Without rule names, all the rules are suppressed. Unknown rule names are ignored.
+fn suppressions(input: &str) -> Vec<Suppressed> {
+    let mut lines: Vec<Suppressed> = vec![];
+    let mut disabled = Suppressed::default();
+    let mut next_line = Suppressed::default();
+
+    for line in input.lines() {
+        let mut suppressed = disabled.clone();
+        suppressed.all |= next_line.all;
+        suppressed.rules.extend(next_line.rules.drain());
+        next_line.all = false;
+
+        let comment = line.find("//").map(|index| line[index + 2..].trim());
+        if let Some((directive, rules)) = comment.map(|comment| {
+            let (directive, rules) = comment
+                .split_once(char::is_whitespace)
+                .unwrap_or((comment, ""));
+            let rules: Vec<LintRule> = rules
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter_map(|rule| LintRule::from_str(rule).ok())
+                .collect();
+            (directive, (!rules.is_empty()).then_some(rules))
+        }) {
+            match directive {
+                "lint-disable-line" => suppressed.add(&rules),
+                "lint-disable-next-line" => next_line.add(&rules),
+                "lint-disable" => disabled.add(&rules),
+                "lint-enable" => match rules {
+                    Some(rules) => disabled.rules.retain(|rule| !rules.contains(rule)),
+                    None => disabled = Suppressed::default(),
+                },
+                _ => {}
+            }
+        }
+
+        lines.push(suppressed);
+    }
+
+    lines
+}
+
+/// Check the AST with the rules of the config, except the ones suppressed by the comments of the input.
+/// `tokens` are the ones the AST is parsed from, for the positions of the warnings.
+/// The warnings are in the order of the positions.
+pub fn lint(
+    input: &str,
+    tokens: &[TokenWithPosition],
+    ast: &Ast,
+    config: &LintConfig,
+) -> Vec<LintWarning> {
+    let context = LintContext::new(ast, tokens);
+    let suppressions = suppressions(input);
+
+    let mut warnings: Vec<LintWarning> = vec![];
+    for rule in LintRule::iter() {
+        let Some(severity) = config.severity(rule) else {
+            continue;
+        };
+
+        for (position, additional_info) in rules::check(rule, &context) {
+            let is_suppressed = suppressions
+                .get(position.line_number - 1)
+                .is_some_and(|suppressed| suppressed.contains(rule));
+            if !is_suppressed {
+                warnings.push(LintWarning {
+                    rule,
+                    severity,
+                    additional_info,
+                    position,
+                });
+            }
+        }
+    }
+
+    warnings.sort_by_key(|warning| (warning.position.line_number, warning.position.column_number));
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint_chord_progression_string;
+    use crate::parser::options::ParseOptions;
+
+    fn rules(input: &str, config: &LintConfig) -> Vec<(LintRule, usize)> {
+        lint_chord_progression_string(input, &ParseOptions::default(), config)
+            .unwrap()
+            .into_iter()
+            .map(|warning| (warning.rule, warning.position.line_number))
+            .collect()
+    }
+
+    #[test]
+    fn rule_names() {
+        assert_eq!(LintRule::UnreachableVolta.to_string(), "unreachable-volta");
+        assert_eq!(
+            LintRule::from_str("bars-per-line"),
+            Ok(LintRule::BarsPerLine)
+        );
+        assert_eq!(
+            serde_json::to_string(&LintRule::ChordOutOfKey).unwrap(),
+            "\"chord-out-of-key\""
+        );
+    }
+
+    #[test]
+    fn config() {
+        let config: LintConfig = serde_json::from_str(
+            r#"{ "disabled": ["unknown-chord"], "severities": { "chord-out-of-key": "error" } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.severity(LintRule::UnknownChord), None);
+        assert_eq!(
+            config.severity(LintRule::ChordOutOfKey),
+            Some(Severity::Error)
+        );
+        assert_eq!(
+            config.severity(LintRule::UnreachableVolta),
+            Some(Severity::Warning)
+        );
+        assert_eq!(
+            rules("[key=C]? - Bb", &config),
+            vec![(LintRule::ChordOutOfKey, 1)]
+        );
+    }
+
+    #[test]
+    fn suppression_comments() {
+        let config = LintConfig::new();
+
+        assert_eq!(
+            rules("? // lint-disable-line unknown-chord\n?", &config),
+            vec![(LintRule::UnknownChord, 2)]
+        );
+        assert_eq!(
+            rules("// lint-disable-next-line\n?\n?", &config),
+            vec![(LintRule::UnknownChord, 3)]
+        );
+        assert_eq!(
+            rules(
+                "// lint-disable unknown-chord, chord-out-of-key\n[key=C]? - Bb\n// lint-enable unknown-chord\n? - Bb",
+                &config
+            ),
+            vec![(LintRule::UnknownChord, 4)]
+        );
+        // another rule is not suppressed
+        assert_eq!(
+            rules("? // lint-disable-line bars-per-line", &config),
+            vec![(LintRule::UnknownChord, 1)]
+        );
+    }
+}
@@ -0,0 +1,423 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use strum::IntoEnumIterator;
+
+use crate::parser::types::bar::Bar;
+use crate::parser::types::chord_block::ChordBlock;
+use crate::parser::types::chord_expression::ChordExpression;
+use crate::parser::types::chord_info_meta::ChordInfoMeta;
+use crate::parser::types::extension::Extension;
+use crate::parser::types::key::Key;
+use crate::parser::types::navigation_mark::NavigationMark;
+use crate::parser::types::section_meta::SectionMeta;
+use crate::parser::Ast;
+use crate::timeline::played_bars;
+use crate::tokenizer::types::token::Token;
+use crate::tokenizer::types::token_with_position::TokenWithPosition;
+use crate::util::note::{note_to_string, pitch_class};
+use crate::util::position::Position;
+
+use super::LintRule;
+
+/// chord block of a section as written, with the positions of its chords (or its mark)
+struct WrittenBlock<'a> {
+    section_index: usize,
+    chord_block: &'a ChordBlock,
+    positions: Vec<&'a Position>,
+}
+
+/// The AST with the positions of the chord blocks.
+/// The sections with `@use` are skipped, as their chord blocks are copies of the referenced sections.
+pub(super) struct LintContext<'a> {
+    ast: &'a Ast,
+    blocks: Vec<WrittenBlock<'a>>,
+}
+
+impl<'a> LintContext<'a> {
+    /// the chords and the navigation marks of the AST are in the order of the tokens
+    pub(super) fn new(ast: &'a Ast, tokens: &'a [TokenWithPosition]) -> LintContext<'a> {
+        let mut chord_positions = tokens
+            .iter()
+            .filter(|token_with_position| matches!(token_with_position.token, Token::Chord(_)))
+            .map(|token_with_position| &token_with_position.position);
+        let mut mark_positions = tokens
+            .iter()
+            .filter(|token_with_position| {
+                matches!(
+                    token_with_position.token,
+                    Token::RepeatStart | Token::RepeatEnd | Token::NavigationMark(_)
+                )
+            })
+            .map(|token_with_position| &token_with_position.position);
+
+        let mut blocks: Vec<WrittenBlock> = vec![];
+        for (section_index, section) in ast.iter().enumerate() {
+            let is_reference = section
+                .meta_infos
+                .iter()
+                .any(|meta_info| matches!(meta_info, SectionMeta::Use(_)));
+            if is_reference {
+                continue;
+            }
+
+            for chord_block in section.chord_blocks.iter() {
+                let positions = match chord_block {
+                    ChordBlock::Bar(bar) => chord_positions.by_ref().take(bar.len()).collect(),
+                    ChordBlock::Navigation(_) => mark_positions.next().into_iter().collect(),
+                    ChordBlock::Br => vec![],
+                };
+                blocks.push(WrittenBlock {
+                    section_index,
+                    chord_block,
+                    positions,
+                });
+            }
+        }
+
+        LintContext { ast, blocks }
+    }
+
+    /// bars as written, with the positions of their chords
+    fn bars(&self) -> impl Iterator<Item = (&'a Bar, &[&'a Position])> {
+        self.blocks
+            .iter()
+            .filter_map(|block| match block.chord_block {
+                ChordBlock::Bar(bar) => Some((bar, block.positions.as_slice())),
+                _ => None,
+            })
+    }
+}
+
+/// positions and additional infos of the warnings of the rule
+pub(super) fn check(rule: LintRule, context: &LintContext) -> Vec<(Position, Option<String>)> {
+    match rule {
+        LintRule::BarsPerLine => bars_per_line(context),
+        LintRule::ChordOutOfKey => chord_out_of_key(context),
+        LintRule::UnknownChord => unknown_chord(context),
+        LintRule::RedundantSame => redundant_same(context),
+        LintRule::ExtensionOrder => extension_order(context),
+        LintRule::UnnamedSection => unnamed_section(context),
+        LintRule::UnreachableVolta => unreachable_volta(context),
+    }
+}
+
+/// the lines with a different number of bars from the most common one in the section
+fn bars_per_line(context: &LintContext) -> Vec<(Position, Option<String>)> {
+    // section, number of bars and the position of the first chord of each line
+    let mut lines: Vec<(usize, usize, &Position)> = vec![];
+    let mut is_line_start = true;
+    for block in context.blocks.iter() {
+        let is_section_start = lines
+            .last()
+            .is_some_and(|line| line.0 != block.section_index);
+        match block.chord_block {
+            ChordBlock::Bar(_) if is_line_start || is_section_start => {
+                lines.push((block.section_index, 1, block.positions[0]));
+                is_line_start = false;
+            }
+            ChordBlock::Bar(_) => lines.last_mut().unwrap().1 += 1,
+            ChordBlock::Br => is_line_start = true,
+            ChordBlock::Navigation(_) => {}
+        }
+    }
+
+    let mut warnings = vec![];
+    for (section_index, bars, position) in lines.iter() {
+        let counts: Vec<usize> = lines
+            .iter()
+            .filter(|line| line.0 == *section_index)
+            .map(|line| line.1)
+            .collect();
+        // the most common number, and the first one of them for a tie
+        let expected = counts
+            .iter()
+            .rev()
+            .max_by_key(|count| counts.iter().filter(|other| other == count).count())
+            .copied()
+            .unwrap_or(*bars);
+
+        if *bars != expected {
+            warnings.push((
+                (*position).clone(),
+                Some(format!("{} (expected {})", bars, expected)),
+            ));
+        }
+    }
+    warnings
+}
+
+/// the chords whose root is not in the scale of the key, which is carried forward from `[key=...]`
+fn chord_out_of_key(context: &LintContext) -> Vec<(Position, Option<String>)> {
+    let mut warnings = vec![];
+    let mut key: Option<&Key> = None;
+
+    for (bar, positions) in context.bars() {
+        for (chord_info, position) in bar.iter().zip(positions) {
+            if let Some(written) =
+                chord_info
+                    .meta_infos
+                    .iter()
+                    .find_map(|meta_info| match meta_info {
+                        ChordInfoMeta::Key(key) => Some(key),
+                        _ => None,
+                    })
+            {
+                key = Some(written);
+            }
+
+            let (Some(key), ChordExpression::Chord(chord)) = (key, &chord_info.chord_expression)
+            else {
+                continue;
+            };
+            let (Some(tonic), Some(mode)) = (key.tonic_pitch_class(), key.mode()) else {
+                continue;
+            };
+
+            let root = pitch_class(&chord.detailed.base, &chord.detailed.accidental);
+            if !mode.scale().contains(&((root + 12 - tonic) % 12)) {
+                warnings.push((
+                    (*position).clone(),
+                    Some(format!(
+                        "{} in {}",
+                        note_to_string(&chord.detailed.base, &chord.detailed.accidental),
+                        key
+                    )),
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+fn unknown_chord(context: &LintContext) -> Vec<(Position, Option<String>)> {
+    context
+        .bars()
+        .flat_map(|(bar, positions)| bar.iter().zip(positions))
+        .filter(|(chord_info, _)| chord_info.chord_expression == ChordExpression::UnIdentified)
+        .map(|(_, position)| ((*position).clone(), None))
+        .collect()
+}
+
+/// `%` after another chord of the bar, like `G,%`. `%` with meta infos (like `[dynamics=f]%`) is not redundant.
+fn redundant_same(context: &LintContext) -> Vec<(Position, Option<String>)> {
+    context
+        .bars()
+        .flat_map(|(bar, positions)| bar.iter().zip(positions).skip(1))
+        .filter(|(chord_info, _)| {
+            chord_info.chord_expression == ChordExpression::Same && chord_info.meta_infos.is_empty()
+        })
+        .map(|(_, position)| ((*position).clone(), None))
+        .collect()
+}
+
+/// the extensions in parenthesis in the order of `Extension`, with the chord rewritten in the order
+fn extension_order(context: &LintContext) -> Vec<(Position, Option<String>)> {
+    let order = |extension: &Extension| Extension::iter().position(|other| other == *extension);
+
+    let mut warnings = vec![];
+    for (bar, positions) in context.bars() {
+        for (chord_info, position) in bar.iter().zip(positions) {
+            let ChordExpression::Chord(chord) = &chord_info.chord_expression else {
+                continue;
+            };
+            let Some((chord_without_parenthesis, extensions)) = chord
+                .plain
+                .strip_suffix(')')
+                .and_then(|plain| plain.split_once('('))
+            else {
+                continue;
+            };
+
+            let mut written: Vec<(Option<usize>, &str)> = extensions
+                .split(',')
+                .map(|extension| {
+                    let extension = extension.trim();
+                    (
+                        Extension::from_str(extension).ok().as_ref().and_then(order),
+                        extension,
+                    )
+                })
+                .collect();
+            if written.windows(2).all(|pair| pair[0].0 <= pair[1].0) {
+                continue;
+            }
+
+            written.sort_by_key(|(order, _)| *order);
+            let canonical: Vec<&str> = written.iter().map(|(_, extension)| *extension).collect();
+            warnings.push((
+                (*position).clone(),
+                Some(format!(
+                    "{}({})",
+                    chord_without_parenthesis,
+                    canonical.join(",")
+                )),
+            ));
+        }
+    }
+    warnings
+}
+
+/// the sections without `@section` (except the ones with `@use` or `@include`), at the first chord
+fn unnamed_section(context: &LintContext) -> Vec<(Position, Option<String>)> {
+    if context.ast.len() < 2 {
+        return vec![];
+    }
+
+    let mut warnings = vec![];
+    for (section_index, section) in context.ast.iter().enumerate() {
+        let is_unnamed = !section.meta_infos.iter().any(|meta_info| {
+            matches!(
+                meta_info,
+                SectionMeta::Section(_) | SectionMeta::Use(_) | SectionMeta::Include(_)
+            )
+        });
+        let first_chord = context
+            .blocks
+            .iter()
+            .filter(|block| block.section_index == section_index)
+            .find_map(|block| match block.chord_block {
+                ChordBlock::Bar(_) => block.positions.first(),
+                _ => None,
+            });
+
+        if let (true, Some(position)) = (is_unnamed, first_chord) {
+            warnings.push(((*position).clone(), None));
+        }
+    }
+    warnings
+}
+
+/// the voltas none of whose bars (until the next mark) are played in the timeline
+fn unreachable_volta(context: &LintContext) -> Vec<(Position, Option<String>)> {
+    let played: HashSet<*const Bar> = played_bars(context.ast)
+        .into_iter()
+        .map(|bar| bar as *const Bar)
+        .collect();
+
+    let mut warnings = vec![];
+    for (index, block) in context.blocks.iter().enumerate() {
+        let ChordBlock::Navigation(mark @ NavigationMark::Volta(_)) = block.chord_block else {
+            continue;
+        };
+
+        let bars: Vec<&Bar> = context.blocks[index + 1..]
+            .iter()
+            .take_while(|next| {
+                next.section_index == block.section_index
+                    && !matches!(next.chord_block, ChordBlock::Navigation(_))
+            })
+            .filter_map(|next| match next.chord_block {
+                ChordBlock::Bar(bar) => Some(bar),
+                _ => None,
+            })
+            .collect();
+
+        let is_unreachable = !bars.is_empty()
+            && bars
+                .iter()
+                .all(|bar| !played.contains(&(*bar as *const Bar)));
+        if let (true, Some(position)) = (is_unreachable, block.positions.first()) {
+            warnings.push(((*position).clone(), Some(mark.to_string())));
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lint_chord_progression_string;
+    use crate::linter::{LintConfig, LintRule};
+    use crate::parser::options::ParseOptions;
+
+    /// warnings of the rule, with the line and column numbers
+    fn warnings(rule: LintRule, input: &str) -> Vec<(usize, usize, Option<String>)> {
+        lint_chord_progression_string(input, &ParseOptions::default(), &LintConfig::new())
+            .unwrap()
+            .into_iter()
+            .filter(|warning| warning.rule == rule)
+            .map(|warning| {
+                (
+                    warning.position.line_number,
+                    warning.position.column_number,
+                    warning.additional_info,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bars_per_line() {
+        assert_eq!(
+            warnings(
+                LintRule::BarsPerLine,
+                "C - G - Am - F\nC - G - F\nC - G - Am - F\n\nC - G"
+            ),
+            vec![(2, 1, Some("3 (expected 4)".to_string()))]
+        );
+    }
+
+    #[test]
+    fn chord_out_of_key() {
+        assert_eq!(
+            warnings(
+                LintRule::ChordOutOfKey,
+                "C - Bb - [key=C]F - Bb - [key=F]Bb - [key=Am]E7"
+            ),
+            vec![(1, 21, Some("Bb in C".to_string()))]
+        );
+    }
+
+    #[test]
+    fn unknown_chord() {
+        assert_eq!(
+            warnings(LintRule::UnknownChord, "C - ?\n?/E"),
+            vec![(1, 5, None), (2, 1, None)]
+        );
+    }
+
+    #[test]
+    fn redundant_same() {
+        assert_eq!(
+            warnings(LintRule::RedundantSame, "C - % - G,% - G,[dynamics=f]%"),
+            vec![(1, 11, None)]
+        );
+    }
+
+    #[test]
+    fn extension_order() {
+        assert_eq!(
+            warnings(LintRule::ExtensionOrder, "C7(9,#11) - C7(#11, 9) - Dm7b5"),
+            vec![(1, 13, Some("C7(9,#11)".to_string()))]
+        );
+    }
+
+    #[test]
+    fn unnamed_section() {
+        assert_eq!(warnings(LintRule::UnnamedSection, "C - G"), vec![]);
+        assert_eq!(
+            warnings(
+                LintRule::UnnamedSection,
+                "@section=A\nC - G\n\nF - G\n\n@use=A"
+            ),
+            vec![(4, 1, None)]
+        );
+    }
+
+    #[test]
+    fn unreachable_volta() {
+        assert_eq!(
+            warnings(LintRule::UnreachableVolta, "|: C <1.> F :| <2.> G"),
+            vec![]
+        );
+        assert_eq!(
+            warnings(LintRule::UnreachableVolta, "|: C <1.> F :| <3.> G"),
+            vec![(1, 16, Some("<3.>".to_string()))]
+        );
+        // without `:|`, only the first pass is played
+        assert_eq!(
+            warnings(LintRule::UnreachableVolta, "C <2.> D"),
+            vec![(1, 3, Some("<2.>".to_string()))]
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use crate::error_code::ErrorCode;
+use crate::linter::LintRule;
+
+/// English messages
+pub fn message(code: &ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::Smik1 => "SectionMetaInfoKey is invalid",
+        ErrorCode::Smik2 => "SectionMetaInfoKey should not contains line break",
+        ErrorCode::Smik3 => "SectionMetaInfoKey is duplicated in the section",
+        ErrorCode::Smiv1 => "SectionMetaInfoValue should not be empty",
+        ErrorCode::Smiv2 => "SectionMetaInfoValue needs line break after",
+        ErrorCode::Smiv3 => "SectionMetaInfoValue of repeat needs to be number",
+        ErrorCode::Smiv4 => "Section referenced by use is not found",
+        ErrorCode::Smiv5 => "Section references are cyclic",
+        ErrorCode::Smiv6 => {
+            "SectionMetaInfoValue of use needs to be a section name with an optional transposition"
+        }
+        ErrorCode::Smiv7 => "Section with use should not have its own chords",
+        ErrorCode::Smiv8 => "SectionMetaInfoValue does not match the registered validator",
+        ErrorCode::Smiv9 => "SectionMetaInfoValue of repeat should be 1 or more",
+        ErrorCode::Cimk1 => "ChordInfoMetaKey should not contains line break",
+        ErrorCode::Cimk2 => "MetaInfoKey should not be empty",
+        ErrorCode::Cimk3 => "MetaInfoKey is invalid",
+        ErrorCode::Cimk4 => "MetaInfoKey needs a value",
+        ErrorCode::Cimk5 => "MetaInfoKey should not have a value",
+        ErrorCode::Cimk6 => "MetaInfoKey is duplicated in the chord",
+        ErrorCode::Cimk7 => "MetaInfo should be followed by a chord in the same section",
+        ErrorCode::Cimv1 => "MetaInfoValue should not contains line break",
+        ErrorCode::Cimv2 => "MetaInfoValue should not be empty",
+        ErrorCode::Cimv3 => "MetaInfoValue needs close parenthesis after",
+        ErrorCode::Cimv4 => "MetaInfoValue is invalid",
+        ErrorCode::Cimv5 => "MetaInfoValue of time should be a time signature",
+        ErrorCode::Cimv6 => "MetaInfoValue of tempo should be BPM from 1 to 999",
+        ErrorCode::Cimv7 => "MetaInfoValue of dynamics should be one of pp, p, mp, mf, f and ff",
+        ErrorCode::Cimv8 => "MetaInfoValue of articulation should be one of accent, staccato, tenuto, marcato and stab",
+        ErrorCode::Cimv9 => "MetaInfoValue does not match the registered validator",
+        ErrorCode::Chb1 => "% should not be placed first of ChordBlock",
+        ErrorCode::Chb2 => "Bar should not contains line break",
+        ErrorCode::Chb3 => "Bar has more chords than the beats of the time signature",
+        ErrorCode::Chb4 => "Comma at the end of the bar is ignored",
+        ErrorCode::Cho1 => "Invalid chord",
+        ErrorCode::Cho2 => "Chord should not contains line break",
+        ErrorCode::Cho3 => "Chord should not be empty",
+        ErrorCode::Cho4 => "Polychord must be closed with }",
+        ErrorCode::Cho5 => "Polychord must have upper and lower chords separated by |",
+        ErrorCode::Cho6 => "Extensions of polychord must be inside the braces",
+        ErrorCode::Den1 => "Invalid denominator",
+        ErrorCode::Den2 => "Denominator is limited to one per chord",
+        ErrorCode::Ext1 => "Invalid extension",
+        ErrorCode::Ext2 => "Extension must not be empty",
+        ErrorCode::Ext3 => "Extension must be surrounded by parenthesis",
+        ErrorCode::Ext4 => "No multiple extension parenthesis",
+        ErrorCode::Tkn1 => "Invalid token type",
+        ErrorCode::Bs1 => "Invalid base",
+        ErrorCode::Bl1 => "Continuous blank lines are not allowed",
+        ErrorCode::Trp1 => "Key to transpose from is not specified",
+        ErrorCode::Trp2 => "Key to transpose to is invalid",
+        ErrorCode::Trp3 => "Key to transpose to is in a different mode from the key to transpose from",
+        ErrorCode::Nav1 => "Invalid navigation mark",
+        ErrorCode::Nav2 => "Repeat start |: is not closed with :|",
+        ErrorCode::Nav3 => "Repeat end :| has no matching |:",
+        ErrorCode::Nav4 => "Repeats should not be nested",
+        ErrorCode::Nav5 => "<segno> is not found for D.S.",
+        ErrorCode::Nav6 => "<coda> is not found for the jump to coda",
+        ErrorCode::Nav7 => "<fine> is not found for al Fine",
+        ErrorCode::Inc1 => "Included file cannot be loaded",
+        ErrorCode::Inc2 => "Includes are cyclic",
+        ErrorCode::Inc3 => "Section with include should not have other meta infos or chords",
+        ErrorCode::Def1 => "Chord macro is not defined",
+        ErrorCode::Def2 => "Chord macro is already defined",
+        ErrorCode::Def3 => "Chord macro should be defined as one chord",
+        ErrorCode::Def4 => "Chord macro name is invalid",
+        ErrorCode::Sem1 => "Key change has no effect because it is the current key",
+        ErrorCode::Sem2 => "Key change is reverted at the next chord",
+        ErrorCode::Other1 => "Unknown error",
+        ErrorCode::Other2 => "Input is larger than the maximum size",
+        ErrorCode::Other3 => "Argument is invalid",
+    }
+}
+
+/// English messages of the lint rules
+pub fn lint_message(rule: &LintRule) -> &'static str {
+    match rule {
+        LintRule::BarsPerLine => "Number of bars differs from the other lines of the section",
+        LintRule::ChordOutOfKey => "Root of the chord is not in the scale of the key",
+        LintRule::UnknownChord => "Unidentified chord (?) is left",
+        LintRule::RedundantSame => "% repeating the chord in the same bar is redundant",
+        LintRule::ExtensionOrder => "Extensions are not in the order of the degrees",
+        LintRule::UnnamedSection => "Section has no name (@section)",
+        LintRule::UnreachableVolta => "Bars after the volta are never played",
+    }
+}
+
+/// English hints to fix the errors
+pub fn hint(code: &ErrorCode) -> Option<&'static str> {
+    let hint = match code {
+        ErrorCode::Smik1 => {
+            "available section meta keys are `section`, `repeat`, `use`, `include`, `def` and the keys registered by the application"
+        }
+        ErrorCode::Smiv9 => "write 1 or more, or remove `@repeat` to play the section once",
+        ErrorCode::Cimk7 => {
+            "write the chord after the meta info, like `[key=D]D`, or remove the meta info"
+        }
+        ErrorCode::Sem1 => "remove the key change, which is already the key",
+        ErrorCode::Sem2 => {
+            "remove both key changes if the chord is borrowed, like a secondary dominant"
+        }
+        ErrorCode::Smik3 => "remove one of the section meta infos with the same key",
+        ErrorCode::Smik2 | ErrorCode::Smiv1 => {
+            "write section meta info in one line, like `@section=Intro`"
+        }
+        ErrorCode::Smiv2 => "put a line break after the section meta info",
+        ErrorCode::Smiv3 => "write the number of repeats, like `@repeat=2`",
+        ErrorCode::Smiv4 | ErrorCode::Smiv5 => {
+            "refer to another section named with `@section`, like `@use=Verse`"
+        }
+        ErrorCode::Smiv6 => {
+            "write the section name and optionally the semitones to transpose, like `@use=Verse` or `@use=Verse:+2`"
+        }
+        ErrorCode::Smiv7 => "remove the chords, or write them in a new section after a blank line",
+        ErrorCode::Smiv8 | ErrorCode::Cimv9 => {
+            "write a value accepted by the validator registered for the key"
+        }
+        ErrorCode::Cimk1 | ErrorCode::Cimk2 | ErrorCode::Cimv1 | ErrorCode::Cimv2 => {
+            "write meta info in one line, like `[key=C]C`"
+        }
+        ErrorCode::Cimk3 => {
+            "available meta keys are `key`, `time`, `tempo`, `dynamics`, `articulation`, `rit`, `accel` and `fermata` without a value, and the keys registered by the application"
+        }
+        ErrorCode::Cimk4 => "write the value, like `[tempo=120]`",
+        ErrorCode::Cimk5 => "write the key without a value, like `[fermata]`",
+        ErrorCode::Cimk6 => "remove one of the meta infos with the same key, like `[key=C][key=D]`",
+        ErrorCode::Other2 => "split the input into smaller files",
+        ErrorCode::Other3 => "pass the argument as an object of its type in `generatedTypes`",
+        ErrorCode::Cimv3 => "close the meta info with `]`",
+        ErrorCode::Cimv4 => "write a key like `C`, `F#m` or `Bb`",
+        ErrorCode::Cimv5 => "write a time signature like `3/4` or `6/8`",
+        ErrorCode::Cimv6 => "write the beats per minute, like `[tempo=120]`",
+        ErrorCode::Cimv7 => "write dynamics like `[dynamics=mf]`",
+        ErrorCode::Cimv8 => "write an articulation like `[articulation=accent]`",
+        ErrorCode::Chb1 => "`%` repeats the previous chord, so put a chord before it",
+        ErrorCode::Chb2 => "remove the trailing `,` or join the chords into one line",
+        ErrorCode::Chb4 => "remove the trailing `,`, or write the chord after it",
+        ErrorCode::Chb3 => {
+            "a chord takes at least one beat, so split the bar or change the time signature with `[time=...]`"
+        }
+        ErrorCode::Cho1 | ErrorCode::Bs1 => {
+            "a chord starts with A-G, like `C`, `F#m(7)` or `Bbaug`"
+        }
+        ErrorCode::Cho2 | ErrorCode::Cho3 => "put a chord (or `?`, `%`, `_`) between separators",
+        ErrorCode::Cho4 | ErrorCode::Cho5 => "write a polychord as `{upper|lower}`, like `{D|C7}`",
+        ErrorCode::Cho6 => "write the extensions of each chord inside the braces, like `{D(9)|C7}`",
+        ErrorCode::Den1 | ErrorCode::Den2 => "use only one `/` per chord, like `C/E`",
+        ErrorCode::Ext1 => "write extensions like `7`, `b9`, `M7`, `add9` or `sus4`",
+        ErrorCode::Ext2 => "remove the empty `()` or `,,`",
+        ErrorCode::Ext3 | ErrorCode::Ext4 => "put all extensions in one parenthesis, like `C(7,9)`",
+        ErrorCode::Bl1 => "separate sections with only one blank line",
+        ErrorCode::Trp1 => "put `[key=...]` before the first chord",
+        ErrorCode::Trp3 => {
+            "write the key to transpose to in the same mode, like `Cm` for a minor key"
+        }
+        ErrorCode::Nav1 => {
+            "available marks are `|:`, `:|`, `<1.>`, `<segno>`, `<coda>`, `<to coda>`, `<fine>`, `<D.C.>` and `<D.S.>` (with `al Fine` or `al Coda`)"
+        }
+        ErrorCode::Nav2 | ErrorCode::Nav3 | ErrorCode::Nav4 => {
+            "surround the repeated bars with one pair of `|:` and `:|`"
+        }
+        ErrorCode::Nav5 | ErrorCode::Nav6 | ErrorCode::Nav7 => {
+            "put the mark where the jump goes to"
+        }
+        ErrorCode::Inc1 => "check the path, which is relative to the file including it",
+        ErrorCode::Inc2 => "a file should not include itself, directly or through other files",
+        ErrorCode::Inc3 => "write `@include` alone, and end the section with a blank line",
+        ErrorCode::Def1 => {
+            "define the chord macro like `@def X=F#m(b5,7,11)`, and refer to it like `$X`"
+        }
+        ErrorCode::Def2 => "rename one of the chord macros",
+        ErrorCode::Def3 => "write one chord, like `F#m(b5,7,11)` or `C/E`",
+        ErrorCode::Def4 => "write the name between `@def` and `=`, like `@def X=C`",
+        ErrorCode::Tkn1 | ErrorCode::Trp2 | ErrorCode::Other1 => return None,
+    };
+
+    Some(hint)
+}
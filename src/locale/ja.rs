@@ -0,0 +1,160 @@
+use crate::error_code::ErrorCode;
+use crate::linter::LintRule;
+
+/// Japanese messages
+pub fn message(code: &ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::Smik1 => "セクションメタ情報のキーが不正です",
+        ErrorCode::Smik2 => "セクションメタ情報のキーに改行を含めることはできません",
+        ErrorCode::Smik3 => "セクションメタ情報のキーがセクション内で重複しています",
+        ErrorCode::Smiv1 => "セクションメタ情報の値は空にできません",
+        ErrorCode::Smiv2 => "セクションメタ情報の値の後に改行が必要です",
+        ErrorCode::Smiv3 => "セクションメタ情報の値のrepeatの値は数値である必要があります",
+        ErrorCode::Smiv4 => "useで参照しているセクションが見つかりません",
+        ErrorCode::Smiv5 => "セクションの参照が循環しています",
+        ErrorCode::Smiv6 => "useの値はセクション名（移調は任意）である必要があります",
+        ErrorCode::Smiv7 => "useを指定したセクションには独自のコードを書けません",
+        ErrorCode::Smiv8 => "セクションメタ情報の値が登録された検証ルールに一致しません",
+        ErrorCode::Smiv9 => "repeatの値は1以上である必要があります",
+        ErrorCode::Cimk1 => "コードメタ情報のキーに改行を含めることはできません",
+        ErrorCode::Cimk2 => "コードメタ情報のキーは空にできません",
+        ErrorCode::Cimk3 => "コードメタ情報のキーが不正です",
+        ErrorCode::Cimk4 => "コードメタ情報のキーには値が必要です",
+        ErrorCode::Cimk5 => "コードメタ情報のキーに値は指定できません",
+        ErrorCode::Cimk6 => "コードメタ情報のキーがコード内で重複しています",
+        ErrorCode::Cimk7 => "メタ情報の後には同じセクション内にコードが必要です",
+        ErrorCode::Cimv1 => "コードメタ情報の値に改行を含めることはできません",
+        ErrorCode::Cimv2 => "コードメタ情報の値は空にできません",
+        ErrorCode::Cimv3 => "コードメタ情報の値の後に閉じ括弧が必要です",
+        ErrorCode::Cimv4 => "コードメタ情報の値が不正です",
+        ErrorCode::Cimv5 => "timeの値は拍子である必要があります",
+        ErrorCode::Cimv6 => "tempoの値は1から999までのBPMである必要があります",
+        ErrorCode::Cimv7 => "dynamicsの値はpp、p、mp、mf、f、ffのいずれかである必要があります",
+        ErrorCode::Cimv8 => "articulationの値はaccent、staccato、tenuto、marcato、stabのいずれかである必要があります",
+        ErrorCode::Cimv9 => "コードメタ情報の値が登録された検証ルールに一致しません",
+        ErrorCode::Chb1 => "コードブロックの先頭に%を置くことはできません",
+        ErrorCode::Chb2 => "コードブロックに改行を含めることはできません",
+        ErrorCode::Chb3 => "小節のコードの数が拍子の拍数より多いです",
+        ErrorCode::Chb4 => "小節末尾のカンマは無視されます",
+        ErrorCode::Cho1 => "コードが不正です",
+        ErrorCode::Cho2 => "コードに改行を含めることはできません",
+        ErrorCode::Cho3 => "コードは空にできません",
+        ErrorCode::Cho4 => "ポリコードは } で閉じる必要があります",
+        ErrorCode::Cho5 => "ポリコードは上下のコードを | で区切る必要があります",
+        ErrorCode::Cho6 => "ポリコードのテンションは波括弧の中に書く必要があります",
+        ErrorCode::Den1 => "分母が不正です",
+        ErrorCode::Den2 => "コードに対して分母は1つまでです",
+        ErrorCode::Ext1 => "テンションが不正です",
+        ErrorCode::Ext2 => "テンションは空にできません",
+        ErrorCode::Ext3 => "テンションは括弧で囲む必要があります",
+        ErrorCode::Ext4 => "テンションの括弧は1つまでです",
+        ErrorCode::Tkn1 => "不正なトークンタイプです",
+        ErrorCode::Bs1 => "不正なベース音です",
+        ErrorCode::Bl1 => "連続した空行は許可されていません",
+        ErrorCode::Trp1 => "移調元のキーが指定されていません",
+        ErrorCode::Trp2 => "移調先のキーが不正です",
+        ErrorCode::Trp3 => "移調先のキーの旋法が移調元のキーと異なります",
+        ErrorCode::Nav1 => "ナビゲーション記号が不正です",
+        ErrorCode::Nav2 => "リピート開始 |: が :| で閉じられていません",
+        ErrorCode::Nav3 => "リピート終了 :| に対応する |: がありません",
+        ErrorCode::Nav4 => "リピートを入れ子にすることはできません",
+        ErrorCode::Nav5 => "D.S. に対応する <segno> がありません",
+        ErrorCode::Nav6 => "コーダへのジャンプに対応する <coda> がありません",
+        ErrorCode::Nav7 => "al Fine に対応する <fine> がありません",
+        ErrorCode::Inc1 => "インクルードするファイルを読み込めません",
+        ErrorCode::Inc2 => "インクルードが循環しています",
+        ErrorCode::Inc3 => "includeを指定したセクションには他のメタ情報やコードを書けません",
+        ErrorCode::Def1 => "コードマクロが定義されていません",
+        ErrorCode::Def2 => "コードマクロが既に定義されています",
+        ErrorCode::Def3 => "コードマクロは1つのコードとして定義する必要があります",
+        ErrorCode::Def4 => "コードマクロ名が不正です",
+        ErrorCode::Sem1 => "現在のキーと同じため、キーの変更に効果がありません",
+        ErrorCode::Sem2 => "キーの変更が次のコードで元に戻っています",
+        ErrorCode::Other1 => "不明なエラーです",
+        ErrorCode::Other2 => "入力が最大サイズを超えています",
+        ErrorCode::Other3 => "引数が不正です",
+    }
+}
+
+/// Japanese messages of the lint rules
+pub fn lint_message(rule: &LintRule) -> &'static str {
+    match rule {
+        LintRule::BarsPerLine => "この行の小節数がセクション内の他の行と異なります",
+        LintRule::ChordOutOfKey => "コードのルートがキーのスケールにありません",
+        LintRule::UnknownChord => "未確定のコード(?)が残っています",
+        LintRule::RedundantSame => "同じ小節のコードを繰り返す%は不要です",
+        LintRule::ExtensionOrder => "テンションが度数の順に並んでいません",
+        LintRule::UnnamedSection => "セクションに名前(@section)がありません",
+        LintRule::UnreachableVolta => "このカッコ以降の小節は演奏されません",
+    }
+}
+
+/// Japanese hints to fix the errors
+pub fn hint(code: &ErrorCode) -> Option<&'static str> {
+    let hint = match code {
+        ErrorCode::Smik1 => {
+            "使用できるセクションメタ情報のキーは `section`、`repeat`、`use`、`include`、`def` と、アプリケーションが登録したキーです"
+        }
+        ErrorCode::Smiv9 => "1以上を書くか、1回だけ演奏する場合は `@repeat` を削除してください",
+        ErrorCode::Cimk7 => "`[key=D]D` のようにメタ情報の後にコードを書くか、メタ情報を削除してください",
+        ErrorCode::Sem1 => "既に同じキーなので、キーの変更を削除してください",
+        ErrorCode::Sem2 => "セカンダリードミナントのような借用コードであれば、両方のキーの変更を削除してください",
+        ErrorCode::Smik3 => "同じキーのセクションメタ情報のどちらかを削除してください",
+        ErrorCode::Smik2 | ErrorCode::Smiv1 => "セクションメタ情報は `@section=Intro` のように1行で書いてください",
+        ErrorCode::Smiv2 => "セクションメタ情報の後に改行を入れてください",
+        ErrorCode::Smiv3 => "`@repeat=2` のように繰り返し回数を数値で書いてください",
+        ErrorCode::Smiv4 | ErrorCode::Smiv5 => {
+            "`@use=Verse` のように `@section` で名前を付けた別のセクションを参照してください"
+        }
+        ErrorCode::Smiv6 => "`@use=Verse` や `@use=Verse:+2` のようにセクション名と、必要なら移調する半音数を書いてください",
+        ErrorCode::Smiv7 => "コードを削除するか、空行の後の新しいセクションに書いてください",
+        ErrorCode::Smiv8 | ErrorCode::Cimv9 => "キーに登録された検証ルールに合う値を書いてください",
+        ErrorCode::Cimk1 | ErrorCode::Cimk2 | ErrorCode::Cimv1 | ErrorCode::Cimv2 => {
+            "メタ情報は `[key=C]C` のように1行で書いてください"
+        }
+        ErrorCode::Cimk3 => {
+            "使用できるメタ情報のキーは `key`、`time`、`tempo`、`dynamics`、`articulation`、値なしの `rit`、`accel`、`fermata` と、アプリケーションが登録したキーです"
+        }
+        ErrorCode::Cimk4 => "`[tempo=120]` のように値を書いてください",
+        ErrorCode::Cimk5 => "`[fermata]` のように値なしでキーを書いてください",
+        ErrorCode::Cimk6 => "`[key=C][key=D]` のような同じキーのメタ情報のどちらかを削除してください",
+        ErrorCode::Other2 => "入力をより小さなファイルに分割してください",
+        ErrorCode::Other3 => "引数は `generatedTypes` の型のオブジェクトで渡してください",
+        ErrorCode::Cimv3 => "メタ情報は `]` で閉じてください",
+        ErrorCode::Cimv4 => "キーは `C`、`F#m`、`Bb` のように書いてください",
+        ErrorCode::Cimv5 => "拍子は `3/4` や `6/8` のように書いてください",
+        ErrorCode::Cimv6 => "`[tempo=120]` のように1分あたりの拍数を書いてください",
+        ErrorCode::Cimv7 => "強弱は `[dynamics=mf]` のように書いてください",
+        ErrorCode::Cimv8 => "アーティキュレーションは `[articulation=accent]` のように書いてください",
+        ErrorCode::Chb1 => "`%` は直前のコードの繰り返しなので、前にコードを置いてください",
+        ErrorCode::Chb2 => "末尾の `,` を削除するか、コードを1行にまとめてください",
+        ErrorCode::Chb4 => "末尾の `,` を削除するか、その後にコードを書いてください",
+        ErrorCode::Chb3 => "コードは1拍以上なので、小節を分けるか `[time=...]` で拍子を変えてください",
+        ErrorCode::Cho1 | ErrorCode::Bs1 => "コードは `C`、`F#m(7)`、`Bbaug` のように A-G から始めてください",
+        ErrorCode::Cho2 | ErrorCode::Cho3 => "区切りの間にコード（または `?`、`%`、`_`）を置いてください",
+        ErrorCode::Cho4 | ErrorCode::Cho5 => "ポリコードは `{D|C7}` のように `{上|下}` と書いてください",
+        ErrorCode::Cho6 => "`{D(9)|C7}` のように、各コードのテンションを波括弧の中に書いてください",
+        ErrorCode::Den1 | ErrorCode::Den2 => "`C/E` のように `/` はコードごとに1つだけ使ってください",
+        ErrorCode::Ext1 => "テンションは `7`、`b9`、`M7`、`add9`、`sus4` のように書いてください",
+        ErrorCode::Ext2 => "空の `()` や `,,` を削除してください",
+        ErrorCode::Ext3 | ErrorCode::Ext4 => "テンションは `C(7,9)` のように1つの括弧にまとめてください",
+        ErrorCode::Bl1 => "セクションの間の空行は1行にしてください",
+        ErrorCode::Trp1 => "最初のコードの前に `[key=...]` を置いてください",
+        ErrorCode::Trp3 => "`Cm`（短調の場合）のように、移調先のキーを同じ旋法で書いてください",
+        ErrorCode::Nav1 => {
+            "使用できる記号は `|:`、`:|`、`<1.>`、`<segno>`、`<coda>`、`<to coda>`、`<fine>`、`<D.C.>`、`<D.S.>`（`al Fine`、`al Coda` 付きも可）です"
+        }
+        ErrorCode::Nav2 | ErrorCode::Nav3 | ErrorCode::Nav4 => "繰り返す小節を `|:` と `:|` の1組で囲んでください",
+        ErrorCode::Nav5 | ErrorCode::Nav6 | ErrorCode::Nav7 => "ジャンプ先に記号を置いてください",
+        ErrorCode::Inc1 => "パスを確認してください（インクルード元のファイルからの相対パスです）",
+        ErrorCode::Inc2 => "ファイルが（他のファイル経由も含めて）自分自身をインクルードしないようにしてください",
+        ErrorCode::Inc3 => "`@include` だけを書き、空行でセクションを終えてください",
+        ErrorCode::Def1 => "`@def X=F#m(b5,7,11)` のようにコードマクロを定義し、`$X` のように参照してください",
+        ErrorCode::Def2 => "どちらかのコードマクロの名前を変えてください",
+        ErrorCode::Def3 => "`F#m(b5,7,11)` や `C/E` のように1つのコードを書いてください",
+        ErrorCode::Def4 => "`@def X=C` のように `@def` と `=` の間に名前を書いてください",
+        ErrorCode::Tkn1 | ErrorCode::Trp2 | ErrorCode::Other1 => return None,
+    };
+
+    Some(hint)
+}
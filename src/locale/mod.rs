@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use crate::error_code::ErrorCode;
+use crate::linter::LintRule;
+
+pub mod en;
+pub mod ja;
+pub mod typescript;
+
+/// locale used when the requested one is unknown, or has no message for the code
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// locales whose messages are built in the crate
+pub const BUILTIN_LOCALES: [&str; 2] = ["en", "ja"];
+
+/// Key of a message in the catalogue: the error code, the hint to fix the error, or the lint rule
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum MessageKey {
+    Error(ErrorCode),
+    Hint(ErrorCode),
+    Lint(LintRule),
+}
+
+impl From<ErrorCode> for MessageKey {
+    fn from(code: ErrorCode) -> MessageKey {
+        MessageKey::Error(code)
+    }
+}
+
+impl From<LintRule> for MessageKey {
+    fn from(rule: LintRule) -> MessageKey {
+        MessageKey::Lint(rule)
+    }
+}
+
+fn registry() -> &'static RwLock<HashMap<String, HashMap<MessageKey, String>>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, HashMap<MessageKey, String>>>> =
+        OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Register messages of another locale, or override messages of a built-in one.
+/// The messages are keyed by the error codes, the hints of them ([`MessageKey::Hint`]) or the lint rules,
+/// and the keys without a message fall back to the built-in messages.
+///
+/// # Example
+/// ```rust
+/// use std::collections::HashMap;
+/// use chord_progression_parser::error_code::ErrorCode;
+/// use chord_progression_parser::locale::register_locale;
+///
+/// register_locale(
+///     "de",
+///     HashMap::from([(ErrorCode::Ext1, "Ungültige Erweiterung".to_string())]),
+/// );
+/// assert_eq!(ErrorCode::Ext1.message("de"), "Ungültige Erweiterung");
+/// assert_eq!(ErrorCode::Ext2.message("de"), "Extension must not be empty");
+/// ```
+pub fn register_locale<K: Into<MessageKey>>(locale: &str, messages: HashMap<K, String>) {
+    let mut registry = registry().write().unwrap_or_else(|e| e.into_inner());
+    registry.entry(locale.to_string()).or_default().extend(
+        messages
+            .into_iter()
+            .map(|(key, message)| (key.into(), message)),
+    );
+}
+
+/// built-in and registered locales
+pub fn locales() -> Vec<String> {
+    let registry = registry().read().unwrap_or_else(|e| e.into_inner());
+    let mut locales: Vec<String> = BUILTIN_LOCALES
+        .iter()
+        .map(|locale| locale.to_string())
+        .chain(registry.keys().cloned())
+        .collect();
+    locales.sort();
+    locales.dedup();
+    locales
+}
+
+fn registered_message(key: MessageKey, locale: &str) -> Option<String> {
+    let registry = registry().read().unwrap_or_else(|e| e.into_inner());
+    registry
+        .get(locale)
+        .and_then(|messages| messages.get(&key))
+        .cloned()
+}
+
+/// localized message of the error code
+pub fn message(code: &ErrorCode, locale: &str) -> String {
+    registered_message(MessageKey::Error(*code), locale)
+        .unwrap_or_else(|| builtin_message(code, locale).to_string())
+}
+
+/// localized hint to fix the error, if any
+pub fn hint(code: &ErrorCode, locale: &str) -> Option<String> {
+    registered_message(MessageKey::Hint(*code), locale)
+        .or_else(|| builtin_hint(code, locale).map(str::to_string))
+}
+
+/// localized message of the lint rule
+pub fn lint_message(rule: &LintRule, locale: &str) -> String {
+    registered_message(MessageKey::Lint(*rule), locale)
+        .unwrap_or_else(|| builtin_lint_message(rule, locale).to_string())
+}
+
+fn builtin_message(code: &ErrorCode, locale: &str) -> &'static str {
+    match locale {
+        "ja" => ja::message(code),
+        _ => en::message(code),
+    }
+}
+
+fn builtin_hint(code: &ErrorCode, locale: &str) -> Option<&'static str> {
+    match locale {
+        "ja" => ja::hint(code),
+        _ => en::hint(code),
+    }
+}
+
+fn builtin_lint_message(rule: &LintRule, locale: &str) -> &'static str {
+    match locale {
+        "ja" => ja::lint_message(rule),
+        _ => en::lint_message(rule),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn builtin_messages_are_unique() {
+        for locale in BUILTIN_LOCALES {
+            let messages: Vec<&str> = ErrorCode::iter()
+                .map(|code| builtin_message(&code, locale))
+                .collect();
+            let unique_messages: HashSet<&str> = messages.iter().copied().collect();
+
+            assert_eq!(messages.len(), unique_messages.len(), "{}", locale);
+
+            let lint_messages: Vec<&str> = LintRule::iter()
+                .map(|rule| builtin_lint_message(&rule, locale))
+                .collect();
+            let unique_lint_messages: HashSet<&str> = lint_messages.iter().copied().collect();
+
+            assert_eq!(
+                lint_messages.len(),
+                unique_lint_messages.len(),
+                "{}",
+                locale
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_locale_falls_back_to_default() {
+        assert_eq!(
+            message(&ErrorCode::Ext1, "xx"),
+            message(&ErrorCode::Ext1, DEFAULT_LOCALE)
+        );
+    }
+
+    #[test]
+    fn registered_locale_overrides_builtin() {
+        register_locale(
+            "en-GB",
+            HashMap::from([(ErrorCode::Ext3, "Extension must be in brackets".to_string())]),
+        );
+
+        assert_eq!(
+            message(&ErrorCode::Ext3, "en-GB"),
+            "Extension must be in brackets"
+        );
+        assert_eq!(message(&ErrorCode::Ext1, "en-GB"), "Invalid extension");
+        assert!(locales().contains(&"en-GB".to_string()));
+    }
+
+    #[test]
+    fn builtin_hints_are_in_every_locale() {
+        for code in ErrorCode::iter() {
+            assert_eq!(
+                ja::hint(&code).is_some(),
+                en::hint(&code).is_some(),
+                "{}",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn registered_locale_overrides_hints() {
+        register_locale(
+            "de-CH",
+            HashMap::from([(
+                MessageKey::Hint(ErrorCode::Ext1),
+                "Schreibe Erweiterungen wie `7` oder `b9`".to_string(),
+            )]),
+        );
+
+        assert_eq!(
+            hint(&ErrorCode::Ext1, "de-CH").unwrap(),
+            "Schreibe Erweiterungen wie `7` oder `b9`"
+        );
+        assert_eq!(
+            hint(&ErrorCode::Ext2, "de-CH"),
+            hint(&ErrorCode::Ext2, "en")
+        );
+        assert_eq!(hint(&ErrorCode::Other1, "de-CH"), None);
+    }
+
+    #[test]
+    fn registered_locale_overrides_lint_messages() {
+        register_locale(
+            "fr",
+            HashMap::from([(LintRule::UnknownChord, "Accord inconnu (?)".to_string())]),
+        );
+
+        assert_eq!(
+            lint_message(&LintRule::UnknownChord, "fr"),
+            "Accord inconnu (?)"
+        );
+        assert_eq!(
+            lint_message(&LintRule::UnnamedSection, "fr"),
+            "Section has no name (@section)"
+        );
+        assert_eq!(LintRule::UnknownChord.message("fr"), "Accord inconnu (?)");
+    }
+}
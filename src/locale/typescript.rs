@@ -0,0 +1,112 @@
+use strum::IntoEnumIterator;
+
+use crate::error_code::{ErrorCode, ERROR_GENRES};
+use crate::linter::LintRule;
+use crate::locale::{en, ja};
+
+const HEADER: &str = "// NOTE: Do not edit this file manually, it is generated from src/locale by `make generate-error-code-message-map-ts`
+";
+
+const TYPES: &str = "export type ShortErrorGenre = keyof typeof ErrorGenreSummary;
+
+export type ErrorCodeFor<Genre extends ShortErrorGenre> = `${Genre}-${number}`;
+
+type UniqueErrorCodeAndMessageMap = {
+  [key in ShortErrorGenre]: {
+    [k in ErrorCodeFor<key>]: {
+      en: string;
+      ja: string;
+    };
+  };
+};
+";
+
+const FUNCTIONS: &str = "type ExtractKeys<T> = T extends T ? keyof T : never;
+
+export type ErrorCode = ExtractKeys<
+  (typeof ERROR_CODE_MESSAGE_MAP)[keyof typeof ERROR_CODE_MESSAGE_MAP]
+>;
+
+export function getErrorMessage({
+  errorCode,
+  lang,
+}: {
+  errorCode: ErrorCode;
+  lang: \"en\" | \"ja\";
+}): string | undefined {
+  const [genreName, _] = errorCode.split(\"-\") as [ShortErrorGenre, string];
+  return (ERROR_CODE_MESSAGE_MAP[genreName] as any)?.[errorCode]?.[lang];
+}
+
+export function getLintMessage({
+  rule,
+  lang,
+}: {
+  rule: keyof typeof LINT_RULE_MESSAGE_MAP;
+  lang: \"en\" | \"ja\";
+}): string | undefined {
+  return LINT_RULE_MESSAGE_MAP[rule]?.[lang];
+}
+";
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Source of `resources/error_code_message_map.ts`, generated from the built-in messages of the error codes and the lint rules
+pub fn error_code_message_map() -> String {
+    let mut lines: Vec<String> = vec![HEADER.to_string()];
+
+    lines.push("const ErrorGenreSummary = {".to_string());
+    for (genre, summary) in ERROR_GENRES.iter() {
+        lines.push(format!("  {}: {},", genre, quote(summary)));
+    }
+    lines.push("} as const;\n".to_string());
+
+    lines.push(TYPES.to_string());
+
+    lines.push("export const ERROR_CODE_MESSAGE_MAP = {".to_string());
+    for (genre, _) in ERROR_GENRES.iter() {
+        lines.push(format!("  {}: {{", genre));
+        for code in ErrorCode::iter().filter(|code| code.genre() == *genre) {
+            lines.push(format!("    {}: {{", quote(&code.to_string())));
+            lines.push(format!("      en: {},", quote(en::message(&code))));
+            lines.push(format!("      ja: {},", quote(ja::message(&code))));
+            lines.push("    },".to_string());
+        }
+        lines.push("  },".to_string());
+    }
+    lines.push("} as const satisfies UniqueErrorCodeAndMessageMap;\n".to_string());
+
+    lines.push("export const LINT_RULE_MESSAGE_MAP = {".to_string());
+    for rule in LintRule::iter() {
+        lines.push(format!("  {}: {{", quote(&rule.to_string())));
+        lines.push(format!("    en: {},", quote(en::lint_message(&rule))));
+        lines.push(format!("    ja: {},", quote(ja::lint_message(&rule))));
+        lines.push("  },".to_string());
+    }
+    lines.push("} as const;\n".to_string());
+
+    lines.push(FUNCTIONS.to_string());
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_file_is_up_to_date() {
+        // run `make generate-error-code-message-map-ts` if this fails
+        assert_eq!(
+            include_str!("../../resources/error_code_message_map.ts"),
+            error_code_message_map()
+        );
+    }
+
+    #[test]
+    fn quote_escapes() {
+        assert_eq!(quote(r#"a"b\c"#), r#""a\"b\\c""#);
+    }
+}
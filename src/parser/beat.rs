@@ -0,0 +1,140 @@
+use crate::error_code::{ErrorCode, ErrorInfo, ErrorInfoWithPosition};
+use crate::util::position::Position;
+
+use super::types::chord_block::ChordBlock;
+use super::types::section::Section;
+use super::types::time_signature::TimeSignature;
+
+/// Check every bar has no more chords than the beats of its time signature, as a chord takes at least one beat.
+/// The time signature written like `[time=3/4]` is carried forward to the following bars.
+/// The bars before the first time signature are not checked, as their beats are not written.
+/// `chord_positions` are the positions of all the chords in the order of appearance.
+pub fn validate_beats(
+    sections: &[Section],
+    chord_positions: &[Position],
+) -> Result<(), ErrorInfoWithPosition> {
+    let mut time_signature: Option<&TimeSignature> = None;
+    let mut chord_positions = chord_positions.iter();
+
+    let bars = sections
+        .iter()
+        .flat_map(|section| section.chord_blocks.iter())
+        .filter_map(|chord_block| match chord_block {
+            ChordBlock::Bar(bar) => Some(bar),
+            ChordBlock::Br | ChordBlock::Navigation(_) => None,
+        });
+    for bar in bars {
+        if let Some(written) = TimeSignature::of_bar(bar) {
+            time_signature = Some(written);
+        }
+
+        let positions: Vec<&Position> = chord_positions.by_ref().take(bar.len()).collect();
+        let Some(time_signature) = time_signature else {
+            continue;
+        };
+        if bar.len() > time_signature.beats as usize {
+            return Err(ErrorInfoWithPosition {
+                error: ErrorInfo {
+                    code: ErrorCode::Chb3,
+                    additional_info: Some(time_signature.to_string()),
+                    cause: None,
+                },
+                position: positions[time_signature.beats as usize].clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error_code::ErrorCode;
+    use crate::parse_chord_progression_string;
+    use crate::parser::types::chord_block::ChordBlock;
+    use crate::parser::types::chord_info::ChordInfo;
+    use crate::parser::types::chord_info_meta::ChordInfoMeta;
+    use crate::parser::types::time_signature::TimeSignature;
+    use crate::stringifier::stringify;
+    use crate::util::position::Position;
+
+    fn chord_info(chord: &str) -> ChordInfo {
+        match &parse_chord_progression_string(chord).unwrap()[0].chord_blocks[0] {
+            ChordBlock::Bar(bar) => bar[0].clone(),
+            chord_block => panic!("{:?}", chord_block),
+        }
+    }
+
+    #[test]
+    fn time_signature_is_meta_info() {
+        let ast = parse_chord_progression_string("[time=5/4]C,D,E,F,G - C").unwrap();
+
+        assert_eq!(
+            ast[0].chord_blocks[0],
+            ChordBlock::Bar(vec![
+                ChordInfo {
+                    meta_infos: vec![ChordInfoMeta::Time(TimeSignature {
+                        beats: 5,
+                        beat_type: 4,
+                    })],
+                    ..chord_info("C")
+                },
+                chord_info("D"),
+                chord_info("E"),
+                chord_info("F"),
+                chord_info("G"),
+            ])
+        );
+        assert_eq!(stringify(&ast), "[time=5/4]C,D,E,F,G - C\n");
+    }
+
+    #[test]
+    fn time_signature_is_carried_forward() {
+        let error_info =
+            parse_chord_progression_string("[time=3/4]C,D,E - F\n\nG,A,B,C").unwrap_err();
+
+        assert_eq!(error_info.error.code, ErrorCode::Chb3);
+        assert_eq!(error_info.error.additional_info, Some("3/4".to_string()));
+        assert_eq!(
+            error_info.position,
+            Position {
+                line_number: 3,
+                column_number: 7,
+                length: 1,
+            }
+        );
+
+        assert!(
+            parse_chord_progression_string("C,D,E,F - [time=3/4]C,D,E - [time=4/4]C,D,E,F").is_ok()
+        );
+        assert_eq!(
+            parse_chord_progression_string("[time=4/4]C - C,D,E,F,G")
+                .unwrap_err()
+                .error
+                .code,
+            ErrorCode::Chb3
+        );
+    }
+
+    #[test]
+    fn bars_without_time_signature_are_not_checked() {
+        assert!(parse_chord_progression_string("C,D,E,F,G,A,B,C").is_ok());
+        assert!(parse_chord_progression_string("{D|C},{E|F},G,A,B").is_ok());
+        assert!(parse_chord_progression_string("C,D,E,F,G - [time=3/4]C,D,E").is_ok());
+    }
+
+    #[test]
+    fn invalid_time_signature() {
+        let error_info = parse_chord_progression_string("C - [time=3/5]D").unwrap_err();
+
+        assert_eq!(error_info.error.code, ErrorCode::Cimv5);
+        assert_eq!(
+            error_info.position,
+            Position {
+                line_number: 1,
+                column_number: 11,
+                length: 3,
+            }
+        );
+    }
+}
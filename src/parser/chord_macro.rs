@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use crate::error_code::{ErrorCode, ErrorInfo, ErrorInfoWithPosition};
+use crate::tokenizer::tokenize;
+use crate::tokenizer::types::token_ref::{TokenRef, TokenRefWithPosition};
+use crate::tokenizer::types::token_with_position::TokenWithPosition;
+use crate::util::position::Position;
+
+use super::options::ParseOptions;
+use super::parse_with_options;
+use super::types::chord_block::ChordBlock;
+
+fn error_at(
+    code: ErrorCode,
+    additional_info: Option<String>,
+    position: &Position,
+) -> ErrorInfoWithPosition {
+    ErrorInfoWithPosition {
+        error: ErrorInfo {
+            code,
+            additional_info,
+            cause: None,
+        },
+        position: position.clone(),
+    }
+}
+
+/// Whether the token defines or refers to a chord macro, so the tokens need to be expanded
+pub(crate) fn is_chord_macro(token: &TokenRef) -> bool {
+    match token {
+        TokenRef::SectionMetaInfoKey(key) => key == "def",
+        TokenRef::Chord(chord) => chord.starts_with('$'),
+        _ => false,
+    }
+}
+
+/// Expand the chord macros defined like `@def X=F#m(b5,7,11)` and referred to like `$X`.
+/// The lines of the definitions are removed, and each reference is replaced by the tokens of the definition,
+/// placed at the position of the reference. Definitions are for the whole document, wherever they are written.
+pub fn expand_chord_macros<'a>(
+    tokens: &[TokenRefWithPosition<'a>],
+    options: &ParseOptions,
+) -> Result<Vec<TokenRefWithPosition<'a>>, ErrorInfoWithPosition> {
+    // name -> tokens of the definition, and the position of the name
+    let mut definitions: HashMap<&str, (Vec<TokenWithPosition>, &Position)> = HashMap::new();
+    let mut rest: Vec<&TokenRefWithPosition<'a>> = vec![];
+
+    let mut index = 0;
+    while index < tokens.len() {
+        let is_definition = tokens[index].token == TokenRef::SectionMetaInfoStart
+            && matches!(
+                tokens.get(index + 1).map(|next| &next.token),
+                Some(TokenRef::SectionMetaInfoKey(key)) if key == "def"
+            );
+        if !is_definition {
+            rest.push(&tokens[index]);
+            index += 1;
+            continue;
+        }
+
+        let key_position = &tokens[index + 1].position;
+        let (name, name_position) = match tokens.get(index + 2) {
+            Some(TokenRefWithPosition {
+                token: TokenRef::SectionMetaInfoName(name),
+                position,
+            }) => {
+                if !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    return Err(error_at(ErrorCode::Def4, Some(name.to_string()), position));
+                }
+                (name.as_ref(), position)
+            }
+            _ => return Err(error_at(ErrorCode::Def4, None, key_position)),
+        };
+
+        let (value, value_position) = match (tokens.get(index + 3), tokens.get(index + 4)) {
+            (
+                Some(TokenRefWithPosition {
+                    token: TokenRef::Equal,
+                    ..
+                }),
+                Some(TokenRefWithPosition {
+                    token: TokenRef::SectionMetaInfoValue(value),
+                    position,
+                }),
+            ) => (value, position),
+            _ => return Err(error_at(ErrorCode::Smiv1, None, &tokens[index].position)),
+        };
+
+        // the definition is one line
+        index += 5;
+        match tokens.get(index) {
+            None => {}
+            Some(TokenRefWithPosition {
+                token: TokenRef::LineBreak,
+                ..
+            }) => index += 1,
+            Some(next) => return Err(error_at(ErrorCode::Smiv2, None, &next.position)),
+        }
+
+        if let Some((_, defined_position)) = definitions.get(name) {
+            return Err(error_at(
+                ErrorCode::Def2,
+                Some(format!(
+                    "{} ({}:{})",
+                    name, defined_position.line_number, defined_position.column_number
+                )),
+                name_position,
+            ));
+        }
+        definitions.insert(
+            name,
+            (
+                definition_tokens(value, value_position, options)?,
+                name_position,
+            ),
+        );
+    }
+
+    let mut expanded: Vec<TokenRefWithPosition<'a>> = vec![];
+    for token_with_position in rest {
+        let name = match &token_with_position.token {
+            TokenRef::Chord(chord) if chord.starts_with('$') => &chord[1..],
+            _ => {
+                expanded.push(token_with_position.clone());
+                continue;
+            }
+        };
+
+        let (definition, _) = definitions.get(name).ok_or_else(|| {
+            error_at(
+                ErrorCode::Def1,
+                Some(name.to_string()),
+                &token_with_position.position,
+            )
+        })?;
+        expanded.extend(definition.iter().map(|token| TokenRefWithPosition {
+            token: TokenRef::from(&token.token).into_owned(),
+            position: token_with_position.position.clone(),
+        }));
+    }
+
+    Ok(expanded)
+}
+
+/// tokens of the value of the definition, which should be one chord.
+/// errors in the value point at the definition.
+fn definition_tokens(
+    value: &str,
+    position: &Position,
+    options: &ParseOptions,
+) -> Result<Vec<TokenWithPosition>, ErrorInfoWithPosition> {
+    let in_source = |in_value: Position| Position {
+        line_number: position.line_number,
+        column_number: position.column_number + in_value.column_number - 1,
+        length: in_value.length,
+    };
+
+    let tokens: Vec<TokenWithPosition> = tokenize(value)
+        .map_err(|error| ErrorInfoWithPosition {
+            error: error.error,
+            position: in_source(error.position),
+        })?
+        .into_iter()
+        .map(|token| TokenWithPosition {
+            token: token.token,
+            position: in_source(token.position),
+        })
+        .collect();
+
+    let ast = parse_with_options(&tokens, options)?;
+    let is_one_chord = match ast.as_slice() {
+        [section] => {
+            matches!(section.chord_blocks.as_slice(), [ChordBlock::Bar(bar)] if bar.len() == 1)
+        }
+        _ => false,
+    };
+    if !is_one_chord {
+        return Err(error_at(ErrorCode::Def3, Some(value.to_string()), position));
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error_code::ErrorCode;
+    use crate::parse_chord_progression_string;
+    use crate::util::position::Position;
+
+    #[test]
+    fn references_are_expanded() {
+        let ast = parse_chord_progression_string(
+            "@def X=F#m(b5,7,11)\n@def Y=C/E\n@section=A\n$X - [key=C]$Y - $X,G",
+        )
+        .unwrap();
+
+        assert_eq!(
+            ast,
+            parse_chord_progression_string(
+                "@section=A\nF#m(b5,7,11) - [key=C]C/E - F#m(b5,7,11),G"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn definitions_are_for_the_whole_document() {
+        let ast =
+            parse_chord_progression_string("@section=A\n$X\n\n@section=B\n@def X=Am7\nF").unwrap();
+
+        assert_eq!(
+            ast,
+            parse_chord_progression_string("@section=A\nAm7\n\n@section=B\nF").unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_macros() {
+        let error = |input: &str| parse_chord_progression_string(input).unwrap_err();
+
+        let undefined = error("@def X=C\nC - $Y");
+        assert_eq!(undefined.error.code, ErrorCode::Def1);
+        assert_eq!(undefined.error.additional_info, Some("Y".to_string()));
+        assert_eq!(
+            undefined.position,
+            Position {
+                line_number: 2,
+                column_number: 5,
+                length: 2,
+            }
+        );
+
+        let redefined = error("@def X=C\n@def X=D\n$X");
+        assert_eq!(redefined.error.code, ErrorCode::Def2);
+        assert_eq!(redefined.error.additional_info, Some("X (1:6)".to_string()));
+        assert_eq!(
+            redefined.position,
+            Position {
+                line_number: 2,
+                column_number: 6,
+                length: 1,
+            }
+        );
+
+        assert_eq!(error("@def X=C-D").error.code, ErrorCode::Def3);
+        assert_eq!(error("@def X=C,D").error.code, ErrorCode::Def3);
+        assert_eq!(error("@def=C").error.code, ErrorCode::Def4);
+        assert_eq!(error("@def X!=C").error.code, ErrorCode::Def4);
+    }
+
+    #[test]
+    fn error_in_definition_points_at_definition() {
+        let error_info = parse_chord_progression_string("C\n@def X=F#m(b5,7,111)\n$X").unwrap_err();
+
+        assert_eq!(error_info.error.code, ErrorCode::Ext1);
+        assert_eq!(
+            error_info.position,
+            Position {
+                line_number: 2,
+                column_number: 17,
+                length: 3,
+            }
+        );
+    }
+}
@@ -0,0 +1,588 @@
+use std::ops::Range;
+
+use crate::diagnostic::Diagnostic;
+use crate::error_code::ErrorInfoWithPosition;
+use crate::parse_chord_progression_string_with_warnings;
+use crate::tokenizer::types::token_with_position::TokenWithPosition;
+use crate::util::position::Position;
+
+use super::options::{check_input_size, ParseOptions};
+use super::types::section_meta::SectionMeta;
+use super::{finish_sections, parse_input_sections, warnings, Ast, ParsedSections};
+
+/// Replacement of `range` (in bytes of the input before the edit) with `text`
+#[derive(Debug, PartialEq, Clone)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+/// What an edit changed
+#[derive(Debug, PartialEq, Clone)]
+pub struct Changes {
+    /// sections of the new AST which differ from the previous one. the others are the same, in the same order.
+    /// all the sections if the previous input has an error, and empty if the new one has.
+    pub sections: Range<usize>,
+    /// lines of the new input which are re-parsed (starting from 1, the end is excluded)
+    pub lines: Range<usize>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct ParsedChunk {
+    /// prepared tokens
+    tokens: Vec<TokenWithPosition>,
+    parsed: ParsedSections,
+}
+
+/// Lines between the blank lines, which are parsed in the same way as in the whole input
+#[derive(Debug, PartialEq, Clone)]
+struct Chunk {
+    text: String,
+    /// number of the first line in the input
+    line_number: usize,
+    parsed: Result<ParsedChunk, ErrorInfoWithPosition>,
+}
+
+fn shift(position: &mut Position, lines: isize) {
+    position.line_number = position.line_number.saturating_add_signed(lines);
+}
+
+impl Chunk {
+    fn parse(text: &str, line_number: usize, options: &ParseOptions) -> Chunk {
+        // the chunk after a blank line is lexed after a line break (at the line 2), as the lexer looks back to it
+        let (source, first_line_number) = match line_number {
+            1 => (text.to_string(), 1),
+            _ => (format!("\n{}", text), 2),
+        };
+        let mut tokens: Vec<TokenWithPosition> = vec![];
+        let mut is_line_break_skipped = first_line_number == 1;
+        let parsed = parse_input_sections(&source, options, |token_with_position| {
+            if !is_line_break_skipped {
+                is_line_break_skipped = true;
+                return None;
+            }
+            tokens.push(token_with_position.to_token_with_position());
+            Some(token_with_position)
+        })
+        .map(|parsed| ParsedChunk { tokens, parsed });
+
+        let mut chunk = Chunk {
+            text: text.to_string(),
+            line_number: first_line_number,
+            parsed,
+        };
+        chunk.move_to(line_number);
+        chunk
+    }
+
+    /// move the chunk to the line
+    fn move_to(&mut self, line_number: usize) {
+        let lines = line_number as isize - self.line_number as isize;
+        if lines == 0 {
+            return;
+        }
+        self.line_number = line_number;
+
+        match &mut self.parsed {
+            Ok(ParsedChunk { tokens, parsed }) => {
+                let positions = tokens
+                    .iter_mut()
+                    .map(|token| &mut token.position)
+                    .chain(
+                        parsed
+                            .navigation_marks
+                            .iter_mut()
+                            .map(|(_, position)| position),
+                    )
+                    .chain(parsed.chord_positions.iter_mut())
+                    .chain(
+                        parsed
+                            .section_references
+                            .iter_mut()
+                            .map(|(_, position)| position),
+                    );
+                for position in positions {
+                    shift(position, lines);
+                }
+            }
+            Err(error) => shift(&mut error.position, lines),
+        }
+    }
+
+    /// whether the chunk is parsed and its last section has chord blocks,
+    /// so a full parse starts a new section at the blank line after it
+    fn ends_with_chord_blocks(&self) -> bool {
+        self.parsed.as_ref().is_ok_and(|chunk| {
+            chunk
+                .parsed
+                .sections
+                .last()
+                .is_some_and(|section| !section.chord_blocks.is_empty())
+        })
+    }
+
+    fn section_count(&self) -> usize {
+        self.parsed
+            .as_ref()
+            .map_or(0, |chunk| chunk.parsed.sections.len())
+    }
+}
+
+fn is_skipped_char(c: char) -> bool {
+    matches!(c, ' ' | '　' | '\t')
+}
+
+/// Byte ranges and the line numbers of the candidate chunks.
+///
+/// The input is split at the blank line between two lines which look like chords, where a full parse may start a new section.
+/// It is not split after a line with `@` (a blank line after the section meta infos doesn't start a section),
+/// nor around the lines of only a comment (which are skipped with their line breaks).
+/// The chunks whose last section has no chord blocks are joined with the next one after parsing (see [`IncrementalParse`]).
+fn split_chunks(input: &str, options: &ParseOptions) -> Vec<(Range<usize>, usize)> {
+    // a macro of `@def` is used in all the following sections, `%` may repeat the chord of the previous section,
+    // and "\r" is a line break of its own for the tokenizer
+    let is_splittable = !options.allow_same_at_section_start
+        && !input.contains('\r')
+        && !input
+            .lines()
+            .any(|line| line.trim_start().to_lowercase().starts_with("@def"));
+    if !is_splittable {
+        return vec![(0..input.len(), 1)];
+    }
+
+    let mut lines: Vec<(usize, &str)> = vec![];
+    let mut offset = 0;
+    for line in input.split('\n') {
+        lines.push((offset, line));
+        offset += line.len() + 1;
+    }
+
+    let is_chords = |line: &str| {
+        let line = line.trim_start_matches(is_skipped_char);
+        !line.is_empty() && !line.starts_with("//") && !line.contains('@')
+    };
+    let starts_section = |line: &str| {
+        let line = line.trim_start_matches(is_skipped_char);
+        !line.is_empty()
+            && !line.starts_with("//")
+            && !line.starts_with([']', '(', ')', '-', '=', '/', ','])
+    };
+
+    let mut chunks: Vec<(Range<usize>, usize)> = vec![];
+    let (mut start, mut line_number) = (0, 1);
+    for index in 1..lines.len().saturating_sub(1) {
+        let (offset, line) = lines[index];
+        if line.trim_matches(is_skipped_char).is_empty()
+            && is_chords(lines[index - 1].1)
+            && starts_section(lines[index + 1].1)
+        {
+            chunks.push((start..offset, line_number));
+            start = lines[index + 1].0;
+            line_number = index + 2;
+        }
+    }
+    chunks.push((start..input.len(), line_number));
+
+    chunks
+}
+
+/// Parse result kept for the edits of the input, which re-parse only the changed sections.
+///
+/// The result is the same as [`crate::parse_chord_progression_string_with_warnings`] of the whole input.
+/// The input is split into chunks at the blank lines between the sections, and the chunks not changed by an edit are reused.
+/// A blank line is a boundary only after a chunk whose last section has chord blocks, where a full parse starts a new section.
+/// The validations over the whole input (the navigation marks, the beats and `@use`) and the warnings
+/// are done again with the kept tokens and sections. The input with `@def` is parsed as a whole each time,
+/// and so is the input with an error in a chunk, as the error found first may be another one in the whole input.
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::parser::incremental::{IncrementalParse, TextEdit};
+/// use chord_progression_parser::parser::options::ParseOptions;
+///
+/// let mut parse = IncrementalParse::new("@section=A\nC - F\n\n@section=B\nG", ParseOptions::default());
+/// let changes = parse.edit(&TextEdit {
+///     range: 29..30,
+///     text: "Am".to_string(),
+/// });
+///
+/// assert_eq!(changes.sections, 1..2);
+/// assert_eq!(changes.lines, 4..6);
+/// assert_eq!(parse.input(), "@section=A\nC - F\n\n@section=B\nAm");
+/// assert!(parse.result().is_ok());
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct IncrementalParse {
+    input: String,
+    options: ParseOptions,
+    chunks: Vec<Chunk>,
+    result: Result<(Ast, Vec<Diagnostic>), ErrorInfoWithPosition>,
+}
+
+impl IncrementalParse {
+    pub fn new(input: &str, options: ParseOptions) -> IncrementalParse {
+        let mut parse = IncrementalParse {
+            input: input.to_string(),
+            options,
+            chunks: vec![],
+            result: Ok((vec![], vec![])),
+        };
+        parse.reparse();
+        parse
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub fn result(&self) -> &Result<(Ast, Vec<Diagnostic>), ErrorInfoWithPosition> {
+        &self.result
+    }
+
+    /// Apply the edit to the input, and parse the chunks changed by it
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of the input, or not on the boundaries of the characters (same as [`String::replace_range`]).
+    pub fn edit(&mut self, edit: &TextEdit) -> Changes {
+        self.input.replace_range(edit.range.clone(), &edit.text);
+        self.reparse()
+    }
+
+    fn reparse(&mut self) -> Changes {
+        let mut ranges = split_chunks(&self.input, &self.options);
+        let texts: Vec<&str> = ranges
+            .iter()
+            .map(|(range, _)| &self.input[range.clone()])
+            .collect();
+
+        // the chunks at the beginning and the end with the same texts are reused
+        let prefix = self
+            .chunks
+            .iter()
+            .zip(texts.iter())
+            .take_while(|(chunk, text)| chunk.text == **text)
+            .count();
+        let suffix = self
+            .chunks
+            .iter()
+            .skip(prefix)
+            .rev()
+            .zip(texts.iter().skip(prefix).rev())
+            .take_while(|(chunk, text)| chunk.text == **text)
+            .count();
+
+        let mut reused = self.chunks.split_off(prefix);
+        let reused_suffix = reused.split_off(reused.len() - suffix);
+        let mut changed = prefix..ranges.len() - suffix;
+
+        for (range, line_number) in &ranges[changed.clone()] {
+            self.chunks.push(Chunk::parse(
+                &self.input[range.clone()],
+                *line_number,
+                &self.options,
+            ));
+        }
+        for (mut chunk, (_, line_number)) in reused_suffix.into_iter().zip(&ranges[changed.end..]) {
+            chunk.move_to(*line_number);
+            self.chunks.push(chunk);
+        }
+
+        // the blank line after the chunk without chord blocks at the end (like the one after `@section=A`)
+        // doesn't start a section, so the chunk is joined with the next one
+        let mut index = 0;
+        while index + 1 < self.chunks.len() {
+            if self.chunks[index].ends_with_chord_blocks() {
+                index += 1;
+                continue;
+            }
+
+            let (next, _) = ranges.remove(index + 1);
+            ranges[index].0.end = next.end;
+            self.chunks.remove(index + 1);
+            let (range, line_number) = &ranges[index];
+            self.chunks[index] =
+                Chunk::parse(&self.input[range.clone()], *line_number, &self.options);
+            if changed.start > index {
+                changed.start -= 1;
+            }
+            if changed.end > index + 1 {
+                changed.end -= 1;
+            }
+            changed = changed.start.min(index)..changed.end.max(index + 1);
+        }
+
+        let result = self.assemble();
+        let previous = std::mem::replace(&mut self.result, result);
+
+        let section_count =
+            |chunks: &[Chunk]| chunks.iter().map(Chunk::section_count).sum::<usize>();
+        let start = section_count(&self.chunks[..changed.start]);
+        let sections = start..start + section_count(&self.chunks[changed.clone()]);
+        let line_end = match ranges.get(changed.end) {
+            // the blank line before the next chunk is not in the chunks
+            Some((_, line_number)) => line_number - 1,
+            None => self.input.split('\n').count() + 1,
+        };
+        let lines = match ranges.get(changed.start) {
+            Some((_, line_number)) if !changed.is_empty() => *line_number..line_end,
+            _ => line_end..line_end,
+        };
+
+        Changes {
+            sections: match (&previous, &self.result) {
+                (Ok((previous_ast, _)), Ok((ast, _))) => {
+                    changed_sections(previous_ast, ast, sections)
+                }
+                (_, Ok((ast, _))) => 0..ast.len(),
+                (_, Err(_)) => 0..0,
+            },
+            lines,
+        }
+    }
+
+    /// the result of the whole input from the chunks
+    fn assemble(&self) -> Result<(Ast, Vec<Diagnostic>), ErrorInfoWithPosition> {
+        check_input_size(&self.input, &self.options)?;
+
+        // the error found first by a full parse may be in another chunk, or across the chunks
+        if self.chunks.iter().any(|chunk| chunk.parsed.is_err()) {
+            return parse_chord_progression_string_with_warnings(&self.input, &self.options);
+        }
+
+        let mut parsed = ParsedSections::default();
+        let mut tokens: Vec<TokenWithPosition> = vec![];
+        for chunk in self
+            .chunks
+            .iter()
+            .filter_map(|chunk| chunk.parsed.as_ref().ok())
+        {
+            let offset = parsed.sections.len();
+            parsed
+                .sections
+                .extend(chunk.parsed.sections.iter().cloned());
+            parsed
+                .navigation_marks
+                .extend(chunk.parsed.navigation_marks.iter().cloned());
+            parsed
+                .chord_positions
+                .extend(chunk.parsed.chord_positions.iter().cloned());
+            parsed.section_references.extend(
+                chunk
+                    .parsed
+                    .section_references
+                    .iter()
+                    .map(|(index, position)| (index + offset, position.clone())),
+            );
+            tokens.extend(chunk.tokens.iter().cloned());
+        }
+
+        Ok((finish_sections(parsed)?, warnings(&tokens)))
+    }
+}
+
+/// the re-parsed sections, and the sections with `@use` whose referenced sections are changed
+fn changed_sections(previous: &Ast, ast: &Ast, reparsed: Range<usize>) -> Range<usize> {
+    let removed = previous.len() as isize - ast.len() as isize;
+    let (mut start, mut end) = (reparsed.start, reparsed.end);
+
+    for (index, section) in ast.iter().enumerate() {
+        if reparsed.contains(&index)
+            || !section
+                .meta_infos
+                .iter()
+                .any(|meta_info| matches!(meta_info, SectionMeta::Use(_)))
+        {
+            continue;
+        }
+
+        let previous_index = if index < reparsed.start {
+            Some(index)
+        } else {
+            index.checked_add_signed(removed)
+        };
+        if previous_index.and_then(|index| previous.get(index)) != Some(section) {
+            start = start.min(index);
+            end = end.max(index + 1);
+        }
+    }
+
+    if start > end {
+        return reparsed;
+    }
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_chord_progression_string_with_warnings;
+
+    const INPUT: &str = "@section=Intro
+[key=C][time=3/4]C - F
+G - C // end of the intro
+
+@section=Verse
+|: Am - <segno> F
+G - C :|
+
+@section=Bridge
+@use=Verse
+
+[key=D]D - G, A
+<D.S.> Bm - A
+";
+
+    fn assert_same_as_full_parse(parse: &IncrementalParse) {
+        assert_eq!(
+            parse.result(),
+            &parse_chord_progression_string_with_warnings(parse.input(), &parse.options),
+            "{:?}",
+            parse.input()
+        );
+    }
+
+    fn edit(parse: &mut IncrementalParse, from: &str, to: &str) -> Changes {
+        let start = parse.input().find(from).unwrap();
+        parse.edit(&TextEdit {
+            range: start..start + from.len(),
+            text: to.to_string(),
+        })
+    }
+
+    #[test]
+    fn only_changed_chunks_are_parsed() {
+        let mut parse = IncrementalParse::new(INPUT, ParseOptions::default());
+        // the blank line after `@use` is not a boundary of the chunks
+        assert_eq!(parse.chunks.len(), 3);
+        assert_same_as_full_parse(&parse);
+
+        let changes = edit(&mut parse, "[key=D]D", "[key=D]Em");
+        assert_eq!(
+            changes,
+            Changes {
+                sections: 2..4,
+                lines: 9..15,
+            }
+        );
+        assert_same_as_full_parse(&parse);
+
+        // the verse is used by the bridge
+        let changes = edit(&mut parse, "Am - ", "A - ");
+        assert_eq!(changes.sections, 1..3);
+        assert_eq!(changes.lines, 5..8);
+        assert_same_as_full_parse(&parse);
+
+        // a new section shifts the following ones
+        let changes = edit(&mut parse, "G - C :|", "G - C :|\n\nE");
+        assert_eq!(changes.sections, 2..3);
+        assert_eq!(changes.lines, 9..10);
+        assert_eq!(parse.chunks[3].line_number, 11);
+        assert_same_as_full_parse(&parse);
+    }
+
+    #[test]
+    fn errors_are_the_same_as_full_parse() {
+        let mut parse = IncrementalParse::new(INPUT, ParseOptions::default());
+
+        // the tokenizer's error comes before the parser's one in the earlier section
+        edit(&mut parse, "C - F", "C - Cx");
+        edit(&mut parse, "Bm - A", "Bm - A,\n");
+        assert_same_as_full_parse(&parse);
+        assert!(parse.result().is_err());
+
+        edit(&mut parse, "Bm - A,\n", "Bm - A");
+        assert_same_as_full_parse(&parse);
+        let changes = edit(&mut parse, "C - Cx", "C - F");
+        assert_eq!(changes.sections, 0..4);
+        assert_same_as_full_parse(&parse);
+        assert!(parse.result().is_ok());
+
+        // the navigation marks and the beats are validated over the sections
+        edit(&mut parse, "<segno> ", "");
+        assert_same_as_full_parse(&parse);
+        assert!(parse.result().is_err());
+        edit(&mut parse, "Am - F", "Am - <segno> F");
+        edit(&mut parse, "G, A", "G, A, B, C");
+        assert_same_as_full_parse(&parse);
+        assert!(parse.result().is_err());
+    }
+
+    #[test]
+    fn blank_line_after_section_meta_infos_is_not_a_boundary() {
+        let mut parse =
+            IncrementalParse::new("@section=A\n=\n\n@repeat=2", ParseOptions::default());
+        assert_same_as_full_parse(&parse);
+
+        edit(&mut parse, "=\n", "C\n");
+        assert_same_as_full_parse(&parse);
+        edit(&mut parse, "C\n", "");
+        assert_same_as_full_parse(&parse);
+        assert_eq!(parse.chunks.len(), 1);
+    }
+
+    #[test]
+    fn random_edits_are_the_same_as_full_parse() {
+        const TEXTS: [&str; 24] = [
+            "",
+            "\n",
+            "\n\n",
+            "%",
+            "@section=X\n",
+            "@use=Verse\n",
+            "@repeat=2",
+            "=",
+            "//",
+            "// x\n",
+            "C",
+            "Am(7)",
+            " - ",
+            ",",
+            "<segno>",
+            "<D.S.>",
+            "|:",
+            ":|",
+            "{D|",
+            "}",
+            "[key=D]",
+            "[time=3/4]",
+            "(",
+            "  ",
+        ];
+
+        // pseudo random edits of the input, some of which make errors
+        let mut seed: u32 = 1;
+        let mut random = |max: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as usize % max
+        };
+        for options in [
+            ParseOptions::default(),
+            ParseOptions {
+                allow_consecutive_blank_lines: true,
+                ..Default::default()
+            },
+        ] {
+            for _ in 0..200 {
+                let mut parse = IncrementalParse::new(INPUT, options.clone());
+                for _ in 0..30 {
+                    let boundaries: Vec<usize> = parse
+                        .input()
+                        .char_indices()
+                        .map(|(index, _)| index)
+                        .chain([parse.input().len()])
+                        .collect();
+                    let start = random(boundaries.len());
+                    let end = (start + random(8)).min(boundaries.len() - 1);
+                    parse.edit(&TextEdit {
+                        range: boundaries[start]..boundaries[end],
+                        text: TEXTS[random(TEXTS.len())].to_string(),
+                    });
+                    assert_same_as_full_parse(&parse);
+                }
+            }
+        }
+    }
+}
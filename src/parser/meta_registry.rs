@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+/// Validator of the value of a custom meta info
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum MetaValueValidator {
+    /// any value
+    String,
+    /// integer, optionally limited to the range from `min` to `max`
+    Integer {
+        #[serde(default)]
+        min: Option<i64>,
+        #[serde(default)]
+        max: Option<i64>,
+    },
+    /// one of the values
+    Enum(Vec<String>),
+    /// regular expression which the whole value should match
+    Regex(String),
+}
+
+impl MetaValueValidator {
+    /// check the value. the error is what the value is expected to be.
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        match self {
+            MetaValueValidator::String => Ok(()),
+            MetaValueValidator::Integer { min, max } => {
+                let expected = match (min, max) {
+                    (Some(min), Some(max)) => format!("integer from {} to {}", min, max),
+                    (Some(min), None) => format!("integer from {}", min),
+                    (None, Some(max)) => format!("integer up to {}", max),
+                    (None, None) => "integer".to_string(),
+                };
+                match value.parse::<i64>() {
+                    Ok(integer)
+                        if min.is_none_or(|min| min <= integer)
+                            && max.is_none_or(|max| integer <= max) =>
+                    {
+                        Ok(())
+                    }
+                    _ => Err(expected),
+                }
+            }
+            MetaValueValidator::Enum(values) => {
+                if values.iter().any(|candidate| candidate == value) {
+                    Ok(())
+                } else {
+                    Err(format!("one of {}", values.join(", ")))
+                }
+            }
+            MetaValueValidator::Regex(pattern) => {
+                // an invalid pattern matches nothing, and is reported with the value
+                let is_match = Regex::new(&format!("^(?:{})$", pattern))
+                    .map(|regex| regex.is_match(value))
+                    .unwrap_or(false);
+                if is_match {
+                    Ok(())
+                } else {
+                    Err(format!("matching {}", pattern))
+                }
+            }
+        }
+    }
+}
+
+/// Custom meta keys declared by the host application, in addition to the built-in ones
+///
+/// The values of the registered keys are checked by their validators,
+/// and appear in the AST as `SectionMeta::Custom` and `ChordInfoMeta::Custom`.
+/// The built-in keys (like `section` and `key`) can't be overridden.
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::parser::meta_registry::{MetaRegistry, MetaValueValidator};
+/// use chord_progression_parser::parse_chord_progression_string_with_registry;
+///
+/// let registry = MetaRegistry::new()
+///     .with_section_meta("mood", MetaValueValidator::Enum(vec!["calm".to_string(), "loud".to_string()]))
+///     .with_chord_meta("capo", MetaValueValidator::Integer { min: Some(0), max: Some(12) });
+/// let ast = parse_chord_progression_string_with_registry("@mood=calm\n[capo=2]C - G", &registry);
+/// assert!(ast.is_ok());
+/// ```
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaRegistry {
+    /// keys of `@key=value`
+    #[serde(default)]
+    pub section_meta: HashMap<String, MetaValueValidator>,
+    /// keys of `[key=value]`
+    #[serde(default)]
+    pub chord_meta: HashMap<String, MetaValueValidator>,
+}
+
+impl MetaRegistry {
+    pub fn new() -> MetaRegistry {
+        MetaRegistry::default()
+    }
+
+    pub fn with_section_meta(mut self, key: &str, validator: MetaValueValidator) -> MetaRegistry {
+        self.section_meta.insert(key.to_string(), validator);
+        self
+    }
+
+    pub fn with_chord_meta(mut self, key: &str, validator: MetaValueValidator) -> MetaRegistry {
+        self.chord_meta.insert(key.to_string(), validator);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validators() {
+        assert!(MetaValueValidator::String.validate("anything").is_ok());
+
+        let integer = MetaValueValidator::Integer {
+            min: Some(0),
+            max: Some(12),
+        };
+        assert!(integer.validate("12").is_ok());
+        assert_eq!(
+            integer.validate("13"),
+            Err("integer from 0 to 12".to_string())
+        );
+        assert!(integer.validate("two").is_err());
+
+        let enumeration = MetaValueValidator::Enum(vec!["calm".to_string(), "loud".to_string()]);
+        assert!(enumeration.validate("loud").is_ok());
+        assert_eq!(
+            enumeration.validate("quiet"),
+            Err("one of calm, loud".to_string())
+        );
+
+        let regex = MetaValueValidator::Regex("[A-Z]{2}-\\d+".to_string());
+        assert!(regex.validate("AB-12").is_ok());
+        // the whole value should match
+        assert!(regex.validate("xAB-12").is_err());
+        assert!(MetaValueValidator::Regex("(".to_string())
+            .validate("(")
+            .is_err());
+    }
+
+    #[test]
+    fn deserialize_from_json() {
+        let registry: MetaRegistry = serde_json::from_str(
+            r#"{
+                "sectionMeta": { "mood": { "type": "enum", "value": ["calm"] } },
+                "chordMeta": { "capo": { "type": "integer", "value": { "min": 0 } } }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            registry,
+            MetaRegistry::new()
+                .with_section_meta("mood", MetaValueValidator::Enum(vec!["calm".to_string()]))
+                .with_chord_meta(
+                    "capo",
+                    MetaValueValidator::Integer {
+                        min: Some(0),
+                        max: None
+                    }
+                )
+        );
+    }
+}
@@ -1,60 +1,324 @@
-mod types;
-
+pub mod beat;
+pub mod chord_macro;
+pub mod incremental;
+pub mod meta_registry;
+pub mod navigation;
+pub mod options;
+pub mod section_reference;
+pub mod semantic;
+pub mod types;
+
+use std::collections::VecDeque;
 use std::str::FromStr;
 
+use crate::diagnostic::Diagnostic;
 use crate::error_code::{ErrorCode, ErrorInfo, ErrorInfoWithPosition};
-use crate::tokenizer::types::token::Token;
+use crate::tokenizer::lexer::Lexer;
+use crate::tokenizer::types::token::TokenKind;
+use crate::tokenizer::types::token_ref::{TokenRef, TokenRefWithPosition};
 use crate::tokenizer::types::token_with_position::TokenWithPosition;
 use crate::util::position::Position;
-
+use beat::validate_beats;
+use chord_macro::{expand_chord_macros, is_chord_macro};
+use navigation::validate_navigation_marks;
+use options::{normalize_tokens, Normalizer, ParseOptions};
+use section_reference::resolve_section_references;
+use semantic::{
+    key_change_warnings, trailing_comma_warnings, validate_meta_info_placement, MetaInfoPlacement,
+};
+
+use types::articulation::Articulation;
 pub use types::ast::Ast;
 use types::chord::Chord;
 use types::chord_detailed::ChordDetailed;
 use types::chord_expression::ChordExpression;
 use types::chord_info::ChordInfo;
 use types::chord_info_meta::ChordInfoMeta;
+use types::dynamics::Dynamics;
+use types::navigation_mark::NavigationMark;
+use types::poly_chord::PolyChord;
 use types::section::Section;
 use types::section_meta::SectionMeta;
+use types::section_reference::SectionReference;
+use types::time_signature::TimeSignature;
 
 use self::types::chord_block::ChordBlock;
 use self::types::extension::Extension;
+use self::types::key::Key;
 
 pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, ErrorInfoWithPosition> {
+    parse_with_options(token_with_position_list, &ParseOptions::default())
+}
+
+/// Parse the tokens with the options, like the custom meta keys and the lenient rules
+pub fn parse_with_options(
+    token_with_position_list: &[TokenWithPosition],
+    options: &ParseOptions,
+) -> Result<Ast, ErrorInfoWithPosition> {
+    Ok(parse_with_warnings(token_with_position_list, options)?.0)
+}
+
+/// Parse the tokens with the options, and also return the warnings of the valid input
+/// (like a key change with no effect), in the order of the positions
+pub fn parse_with_warnings(
+    token_with_position_list: &[TokenWithPosition],
+    options: &ParseOptions,
+) -> Result<(Ast, Vec<Diagnostic>), ErrorInfoWithPosition> {
+    let token_with_position_list = &prepare_tokens(token_with_position_list, options)?;
+    let ast = parse_tokens(token_with_position_list, options)?;
+
+    Ok((ast, warnings(token_with_position_list)))
+}
+
+/// Parse the input while it is tokenized, without collecting the tokens or copying their values.
+/// The result is the same as [`tokenize`](crate::tokenizer::tokenize) and [`parse_with_options`].
+/// Only the tokens from the first chord macro (like `@def X=C7` or `$X`) are collected,
+/// since the definitions are for the whole input.
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::parser::options::ParseOptions;
+/// use chord_progression_parser::parser::{parse_input, parse_with_options};
+/// use chord_progression_parser::tokenizer::tokenize;
+///
+/// let input = "@section=A\n[key=C]C - F - G7(9)/B";
+/// let options = ParseOptions::default();
+/// assert_eq!(
+///     parse_input(input, &options),
+///     parse_with_options(&tokenize(input).unwrap(), &options)
+/// );
+/// ```
+pub fn parse_input(input: &str, options: &ParseOptions) -> Result<Ast, ErrorInfoWithPosition> {
+    finish_sections(parse_input_sections(input, options, Some)?)
+}
+
+/// [`parse_input`] which also returns the tokens parsed to the AST (the same as [`prepare_tokens`] of the tokens of the input),
+/// for the warnings and the lint rules
+pub(crate) fn parse_input_with_tokens(
+    input: &str,
+    options: &ParseOptions,
+) -> Result<(Ast, Vec<TokenWithPosition>), ErrorInfoWithPosition> {
+    let mut tokens: Vec<TokenWithPosition> = vec![];
+    let parsed = parse_input_sections(input, options, |token_with_position| {
+        tokens.push(token_with_position.to_token_with_position());
+        Some(token_with_position)
+    })?;
+
+    Ok((finish_sections(parsed)?, tokens))
+}
+
+/// Parse the sections of the input, before the validations over the whole input.
+/// `filter` is called with each token of the input before it is parsed, and the token is skipped when it returns `None`.
+pub(crate) fn parse_input_sections<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+    filter: impl FnMut(TokenRefWithPosition<'a>) -> Option<TokenRefWithPosition<'a>>,
+) -> Result<ParsedSections, ErrorInfoWithPosition> {
+    let mut tokens = TokenStream {
+        lexer: Lexer::new(input),
+        normalizer: Normalizer::new(options),
+        options,
+        placement: MetaInfoPlacement::default(),
+        buffer: VecDeque::new(),
+        expanded: false,
+        error: None,
+    };
+    let parsed = parse_sections(tokens.by_ref().filter_map(filter), options);
+
+    // the tokens after the parser stopped are read, since their errors come before the ones of the parser
+    for _ in tokens.by_ref() {}
+    if let Some(error) = tokens.error {
+        return Err(error);
+    }
+    tokens.placement.finish()?;
+
+    parsed
+}
+
+/// Tokens of the lexer rewritten by the options, and validated one by one as [`prepare_tokens`].
+/// It ends at the error of the lexer or of the chord macros.
+struct TokenStream<'a, 'o> {
+    lexer: Lexer<'a>,
+    normalizer: Normalizer<'o>,
+    options: &'o ParseOptions,
+    placement: MetaInfoPlacement<'a>,
+    /// tokens read ahead, or all the rest with the chord macros expanded
+    buffer: VecDeque<TokenRefWithPosition<'a>>,
+    expanded: bool,
+    error: Option<ErrorInfoWithPosition>,
+}
+
+impl<'a> TokenStream<'a, '_> {
+    /// next token of the lexer rewritten by the options
+    fn lexed(&mut self) -> Option<TokenRefWithPosition<'a>> {
+        loop {
+            match self.lexer.next()? {
+                Ok(lexeme) => {
+                    if let Some(token_with_position) = self
+                        .normalizer
+                        .normalize(lexeme.to_token_ref_with_position())
+                    {
+                        return Some(token_with_position);
+                    }
+                }
+                Err(error) => {
+                    self.error = Some(error);
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// read the rest of the tokens, and expand the chord macros in them
+    fn expand(&mut self, mut tokens: Vec<TokenRefWithPosition<'a>>) {
+        self.expanded = true;
+        while let Some(token_with_position) = self.lexed() {
+            tokens.push(token_with_position);
+        }
+        if self.error.is_some() {
+            return;
+        }
+
+        match expand_chord_macros(&tokens, self.options) {
+            Ok(tokens) => self.buffer.extend(tokens),
+            Err(error) => self.error = Some(error),
+        }
+    }
+}
+
+impl<'a> Iterator for TokenStream<'a, '_> {
+    type Item = TokenRefWithPosition<'a>;
+
+    fn next(&mut self) -> Option<TokenRefWithPosition<'a>> {
+        if self.error.is_some() {
+            return None;
+        }
+
+        if !self.expanded && self.buffer.is_empty() {
+            let mut tokens = vec![self.lexed()?];
+            // `@` is held back until its key is read, since `@def` defines a chord macro
+            while tokens.last().unwrap().token == TokenRef::SectionMetaInfoStart {
+                let Some(token_with_position) = self.lexed() else {
+                    break;
+                };
+                tokens.push(token_with_position);
+            }
+
+            if tokens.iter().any(|token| is_chord_macro(&token.token)) {
+                self.expand(tokens);
+                if self.error.is_some() {
+                    return None;
+                }
+            } else {
+                self.buffer.extend(tokens);
+            }
+        }
+
+        let token_with_position = self.buffer.pop_front()?;
+        self.placement.push(&token_with_position);
+        Some(token_with_position)
+    }
+}
+
+/// warnings of the prepared tokens, in the order of the positions
+pub(crate) fn warnings(token_with_position_list: &[TokenWithPosition]) -> Vec<Diagnostic> {
+    let mut warnings: Vec<Diagnostic> = trailing_comma_warnings(token_with_position_list)
+        .into_iter()
+        .chain(key_change_warnings(token_with_position_list))
+        .map(Diagnostic::from)
+        .collect();
+    warnings.sort_by_key(|warning| (warning.position.line_number, warning.position.column_number));
+
+    warnings
+}
+
+/// tokens rewritten by the options and the chord macros, which are parsed to the AST
+pub(crate) fn prepare_tokens(
+    token_with_position_list: &[TokenWithPosition],
+    options: &ParseOptions,
+) -> Result<Vec<TokenWithPosition>, ErrorInfoWithPosition> {
+    let token_with_position_list = expand_chord_macros(
+        &normalize_tokens(token_with_position_list, options),
+        options,
+    )?;
+    let token_with_position_list: Vec<TokenWithPosition> = token_with_position_list
+        .iter()
+        .map(TokenRefWithPosition::to_token_with_position)
+        .collect();
+    validate_meta_info_placement(&token_with_position_list)?;
+
+    Ok(token_with_position_list)
+}
+
+pub(crate) fn parse_tokens(
+    token_with_position_list: &[TokenWithPosition],
+    options: &ParseOptions,
+) -> Result<Ast, ErrorInfoWithPosition> {
+    finish_sections(parse_sections(
+        token_with_position_list
+            .iter()
+            .map(TokenRefWithPosition::from),
+        options,
+    )?)
+}
+
+/// Sections parsed from the tokens, and what is validated over the whole input after parsing
+#[derive(Debug, PartialEq, Clone, Default)]
+pub(crate) struct ParsedSections {
+    pub sections: Vec<Section>,
+    /// navigation marks in the order of appearance
+    pub navigation_marks: Vec<(NavigationMark, Position)>,
+    /// positions of the chords in the order of appearance
+    pub chord_positions: Vec<Position>,
+    /// index of the section with `@use` and the position of its value
+    pub section_references: Vec<(usize, Position)>,
+}
+
+/// validate the navigation marks and the beats, and resolve `@use`
+pub(crate) fn finish_sections(parsed: ParsedSections) -> Result<Ast, ErrorInfoWithPosition> {
+    let mut sections = parsed.sections;
+
+    validate_navigation_marks(&parsed.navigation_marks)?;
+    validate_beats(&sections, &parsed.chord_positions)?;
+    resolve_section_references(&mut sections, &parsed.section_references)?;
+
+    Ok(sections)
+}
+
+/// Parse the sections from the tokens, which are consumed one by one (like the ones of a slice, or made from [`Lexer`](crate::tokenizer::lexer::Lexer))
+pub(crate) fn parse_sections<'a>(
+    token_with_position_list: impl IntoIterator<Item = TokenRefWithPosition<'a>>,
+    options: &ParseOptions,
+) -> Result<ParsedSections, ErrorInfoWithPosition> {
+    let registry = &options.registry;
+    let mut token_with_position_list = token_with_position_list.into_iter().peekable();
+
     // if no token_with_position_list, return empty Ast
-    if token_with_position_list.is_empty() {
-        return Ok(Vec::new());
+    if token_with_position_list.peek().is_none() {
+        return Ok(ParsedSections::default());
     }
 
     let mut sections: Vec<Section> = vec![Section {
         meta_infos: Vec::new(),
         chord_blocks: Vec::new(),
     }];
-    let mut token_with_position_list = token_with_position_list.iter().peekable();
     let mut tmp_chord_info_meta_infos: Vec<ChordInfoMeta> = Vec::new();
+    // navigation marks in the order of appearance, for validation after parsing
+    let mut navigation_marks: Vec<(NavigationMark, Position)> = Vec::new();
+    // positions of the chords in the order of appearance, for validating the beats after parsing
+    let mut chord_positions: Vec<Position> = Vec::new();
+    // index of the section with `@use` and the position of its value, resolved after parsing
+    let mut section_references: Vec<(usize, Position)> = Vec::new();
 
-    // return previous args
-    let mut get_previous_token_with_position = {
-        let mut previous_token_with_position: Option<TokenWithPosition> = None;
-
-        // クロージャは `token_with_position` を受け取り、前回の値を返します。
-        move |token_with_position: Option<TokenWithPosition>| -> Option<TokenWithPosition> {
-            // 現在の値を一時変数に保存します。
-            let current = previous_token_with_position.clone();
-
-            // 新しい値で更新します。
-            previous_token_with_position = token_with_position;
-
-            // 前回の値を返します。
-            current
-        }
-    };
+    // kind of the previous token
+    let mut previous_kind: Option<TokenKind> = None;
 
     while let Some(token_with_position) = token_with_position_list.next() {
-        let previous = get_previous_token_with_position(Some(token_with_position.clone()));
+        let previous = previous_kind.replace(token_with_position.token.kind());
 
-        match token_with_position.token.clone() {
+        match &token_with_position.token {
             // section meta info
-            Token::SectionMetaInfoStart => {
+            TokenRef::SectionMetaInfoStart => {
                 // last section's chord_blocks is not empty
                 let is_new_section = !sections.last().unwrap().chord_blocks.is_empty();
 
@@ -66,63 +330,76 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                     });
                 }
 
-                // if next token is not Token::SectionMetaInfoKey, return error
-                let section_meta_info_key = match &token_with_position_list.next().unwrap().token {
-                    Token::SectionMetaInfoKey(value) => value,
+                // if next token is not TokenRef::SectionMetaInfoKey, return error
+                let section_meta_info_key_token_with_position = token_with_position_list.next();
+                let section_meta_info_key = match section_meta_info_key_token_with_position
+                    .as_ref()
+                    .map(|next| &next.token)
+                {
+                    Some(TokenRef::SectionMetaInfoKey(value)) => value,
                     _ => {
                         return Err(ErrorInfoWithPosition {
                             error: ErrorInfo {
                                 code: ErrorCode::Smik2,
                                 additional_info: None,
+                                cause: None,
                             },
-                            position: token_with_position.position.clone().clone(),
+                            position: token_with_position.position.clone(),
                         })
                     }
                 };
 
-                // if next token is not Token::Equal, return error
-                match token_with_position_list.next().unwrap().token {
-                    Token::Equal => {}
+                // if next token is not TokenRef::Equal, return error
+                match token_with_position_list
+                    .next()
+                    .map(|next| next.token == TokenRef::Equal)
+                {
+                    Some(true) => {}
                     _ => {
                         return Err(ErrorInfoWithPosition {
                             error: ErrorInfo {
                                 code: ErrorCode::Smik2,
                                 additional_info: None,
+                                cause: None,
                             },
                             position: token_with_position.position.clone(),
                         })
                     }
                 }
 
-                // if next token is not Token::SectionMetaInfoValue, return error
-                let section_meta_info_value = match &token_with_position_list.next().unwrap().token
-                {
-                    Token::SectionMetaInfoValue(value) => value,
-                    _ => {
-                        return Err(ErrorInfoWithPosition {
-                            error: ErrorInfo {
-                                code: ErrorCode::Smiv1,
-                                additional_info: None,
-                            },
-                            position: token_with_position.position.clone(),
-                        })
-                    }
-                };
+                // if next token is not TokenRef::SectionMetaInfoValue, return error
+                let section_meta_info_value_token_with_position = token_with_position_list.next();
+                let (section_meta_info_value, section_meta_info_value_position) =
+                    match section_meta_info_value_token_with_position.as_ref() {
+                        Some(TokenRefWithPosition {
+                            token: TokenRef::SectionMetaInfoValue(value),
+                            position,
+                        }) => (value, position),
+                        _ => {
+                            return Err(ErrorInfoWithPosition {
+                                error: ErrorInfo {
+                                    code: ErrorCode::Smiv1,
+                                    additional_info: None,
+                                    cause: None,
+                                },
+                                position: token_with_position.position.clone(),
+                            })
+                        }
+                    };
 
                 // add section meta info to last section
-                match section_meta_info_key.as_str() {
+                match section_meta_info_key.as_ref() {
                     "section" => sections
                         .last_mut()
                         .unwrap()
                         .meta_infos
-                        .push(SectionMeta::Section(section_meta_info_value.clone())),
+                        .push(SectionMeta::Section(section_meta_info_value.to_string())),
                     "repeat" => {
                         // if section_meta_info_value cannot parse as u32, return error
                         if section_meta_info_value.parse::<u32>().is_err() {
-                            let cloned_token_with_position = token_with_position.clone();
                             let error_section_meta_info_value_column_number =
-                                cloned_token_with_position.position.column_number
-                                    + section_meta_info_key.as_str().len()
+                                token_with_position.position.column_number
+                                    + section_meta_info_key.as_ref().len()
                                     + 1
                                     + section_meta_info_value.len();
 
@@ -130,15 +407,28 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                 error: ErrorInfo {
                                     code: ErrorCode::Smiv3,
                                     additional_info: None,
+                                    cause: None,
                                 },
                                 position: Position {
-                                    line_number: cloned_token_with_position.position.line_number,
+                                    line_number: token_with_position.position.line_number,
                                     column_number: error_section_meta_info_value_column_number,
-                                    length: cloned_token_with_position.position.length,
+                                    length: token_with_position.position.length,
                                 },
                             });
                         }
 
+                        // the section is not played with 0
+                        if section_meta_info_value.parse::<u32>() == Ok(0) {
+                            return Err(ErrorInfoWithPosition {
+                                error: ErrorInfo {
+                                    code: ErrorCode::Smiv9,
+                                    additional_info: None,
+                                    cause: None,
+                                },
+                                position: section_meta_info_value_position.clone(),
+                            });
+                        }
+
                         sections
                             .last_mut()
                             .unwrap()
@@ -147,11 +437,62 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                 section_meta_info_value.parse::<u32>().unwrap(),
                             ));
                     }
+                    "include" => sections
+                        .last_mut()
+                        .unwrap()
+                        .meta_infos
+                        .push(SectionMeta::Include(section_meta_info_value.to_string())),
+                    "use" => {
+                        let reference = match SectionReference::from_str(section_meta_info_value) {
+                            Ok(reference) => reference,
+                            Err(error) => {
+                                return Err(ErrorInfoWithPosition {
+                                    error,
+                                    position: section_meta_info_value_position.clone(),
+                                })
+                            }
+                        };
+
+                        section_references
+                            .push((sections.len() - 1, section_meta_info_value_position.clone()));
+                        sections
+                            .last_mut()
+                            .unwrap()
+                            .meta_infos
+                            .push(SectionMeta::Use(reference));
+                    }
+                    key if registry.section_meta.contains_key(key) => {
+                        if let Err(expected) =
+                            registry.section_meta[key].validate(section_meta_info_value)
+                        {
+                            return Err(ErrorInfoWithPosition {
+                                error: ErrorInfo {
+                                    code: ErrorCode::Smiv8,
+                                    additional_info: Some(format!(
+                                        "{} ({})",
+                                        section_meta_info_value, expected
+                                    )),
+                                    cause: None,
+                                },
+                                position: section_meta_info_value_position.clone(),
+                            });
+                        }
+
+                        sections
+                            .last_mut()
+                            .unwrap()
+                            .meta_infos
+                            .push(SectionMeta::Custom {
+                                key: key.to_string(),
+                                value: section_meta_info_value.to_string(),
+                            });
+                    }
                     _ => {
                         return Err(ErrorInfoWithPosition {
                             error: ErrorInfo {
                                 code: ErrorCode::Smik1,
                                 additional_info: Some(section_meta_info_key.to_string()),
+                                cause: None,
                             },
                             position: Position {
                                 line_number: token_with_position.position.line_number,
@@ -162,12 +503,30 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                     }
                 }
 
+                if !options.duplicate_meta.apply(
+                    &mut sections.last_mut().unwrap().meta_infos,
+                    SectionMeta::key,
+                ) {
+                    return Err(ErrorInfoWithPosition {
+                        error: ErrorInfo {
+                            code: ErrorCode::Smik3,
+                            additional_info: Some(section_meta_info_key.to_string()),
+                            cause: None,
+                        },
+                        position: Position {
+                            line_number: token_with_position.position.line_number,
+                            column_number: token_with_position.position.column_number + 1,
+                            length: section_meta_info_key.len(),
+                        },
+                    });
+                }
+
                 if token_with_position_list.peek().is_none() {
                     continue;
                 }
 
                 match token_with_position_list.peek().unwrap().token {
-                    Token::LineBreak => {
+                    TokenRef::LineBreak => {
                         token_with_position_list.next();
 
                         if token_with_position_list.peek().is_none() {
@@ -175,21 +534,41 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                         }
 
                         match token_with_position_list.peek().unwrap().token {
-                            Token::LineBreak => {
+                            TokenRef::LineBreak => {
                                 token_with_position_list.next();
 
-                                match token_with_position_list.peek().unwrap().token {
-                                    Token::LineBreak => {
+                                match token_with_position_list.peek().map(|next| &next.token) {
+                                    Some(TokenRef::LineBreak) => {
                                         // if line break appears three times in a row, return error
                                         return Err(ErrorInfoWithPosition {
                                             error: ErrorInfo {
                                                 code: ErrorCode::Bl1,
                                                 additional_info: None,
+                                                cause: None,
                                             },
                                             position: token_with_position.position.clone(),
                                         });
                                     }
-                                    _ => { /* Nothing */ }
+                                    Some(_) => {
+                                        // the section with `@use` or `@include` has no chords, so a blank line ends it
+                                        let is_reference =
+                                            sections.last().unwrap().meta_infos.iter().any(
+                                                |meta_info| {
+                                                    matches!(
+                                                        meta_info,
+                                                        SectionMeta::Use(_)
+                                                            | SectionMeta::Include(_)
+                                                    )
+                                                },
+                                            );
+                                        if is_reference {
+                                            sections.push(Section {
+                                                meta_infos: Vec::new(),
+                                                chord_blocks: Vec::new(),
+                                            });
+                                        }
+                                    }
+                                    None => { /* Nothing */ }
                                 }
                             }
                             _ => { /* Nothing */ }
@@ -200,6 +579,7 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                             error: ErrorInfo {
                                 code: ErrorCode::Smiv2,
                                 additional_info: None,
+                                cause: None,
                             },
                             position: token_with_position_list.peek().unwrap().position.clone(),
                         });
@@ -207,63 +587,125 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                 }
             }
             // meta info
-            Token::MetaInfoStart => {
+            TokenRef::MetaInfoStart => {
                 //(
 
-                // if next token is not Token::MetaInfoKey, return error
-                let meta_info_key = match &token_with_position_list.next().unwrap().token {
-                    Token::MetaInfoKey(value) => value,
+                // if next token is not TokenRef::MetaInfoKey, return error
+                let meta_info_key_token_with_position = token_with_position_list.next();
+                let meta_info_key = match meta_info_key_token_with_position
+                    .as_ref()
+                    .map(|next| &next.token)
+                {
+                    Some(TokenRef::MetaInfoKey(value)) => value,
                     _ => {
                         return Err(ErrorInfoWithPosition {
                             error: ErrorInfo {
                                 code: ErrorCode::Cimk2,
                                 additional_info: None,
+                                cause: None,
                             },
-                            position: token_with_position.position.clone().clone(),
+                            position: token_with_position.position.clone(),
                         })
                     }
                 };
 
-                // if next token is not Token::Equal, return error
-                match token_with_position_list.next().unwrap().token {
-                    Token::Equal => {}
-                    _ => {
-                        return Err(ErrorInfoWithPosition {
-                            error: ErrorInfo {
-                                code: ErrorCode::Cimk1,
-                                additional_info: None,
-                            },
-                            position: token_with_position.position.clone(),
-                        })
+                let meta_info_key_position = Position {
+                    line_number: token_with_position.position.line_number,
+                    column_number: token_with_position.position.column_number + 1,
+                    length: meta_info_key.len(),
+                };
+
+                // flag (like "[fermata]") has no value
+                if let Some(TokenRef::MetaInfoEnd) =
+                    token_with_position_list.peek().map(|next| &next.token)
+                {
+                    token_with_position_list.next();
+
+                    match ChordInfoMeta::from_flag(meta_info_key) {
+                        Some(meta_info) => {
+                            tmp_chord_info_meta_infos.push(meta_info);
+                            if !options
+                                .duplicate_meta
+                                .apply(&mut tmp_chord_info_meta_infos, ChordInfoMeta::key)
+                            {
+                                return Err(ErrorInfoWithPosition {
+                                    error: ErrorInfo {
+                                        code: ErrorCode::Cimk6,
+                                        additional_info: Some(meta_info_key.to_string()),
+                                        cause: None,
+                                    },
+                                    position: meta_info_key_position,
+                                });
+                            }
+                        }
+                        None => {
+                            let code = match meta_info_key.as_ref() {
+                                "key" | "time" | "tempo" | "dynamics" | "articulation" => {
+                                    ErrorCode::Cimk4
+                                }
+                                key if registry.chord_meta.contains_key(key) => ErrorCode::Cimk4,
+                                _ => ErrorCode::Cimk3,
+                            };
+                            return Err(ErrorInfoWithPosition {
+                                error: ErrorInfo {
+                                    code,
+                                    additional_info: Some(meta_info_key.to_string()),
+                                    cause: None,
+                                },
+                                position: meta_info_key_position,
+                            });
+                        }
                     }
+                    continue;
                 }
 
-                // if next token is not Token::MetaInfoValue, return error
-                let meta_info_value = match &token_with_position_list.next().unwrap().token {
-                    Token::MetaInfoValue(value) => value,
+                // if next token is not TokenRef::Equal, return error
+                match token_with_position_list
+                    .next()
+                    .map(|next| next.token == TokenRef::Equal)
+                {
+                    Some(true) => {}
                     _ => {
                         return Err(ErrorInfoWithPosition {
                             error: ErrorInfo {
-                                code: ErrorCode::Cimv2,
+                                code: ErrorCode::Cimk1,
                                 additional_info: None,
+                                cause: None,
                             },
                             position: token_with_position.position.clone(),
                         })
                     }
-                };
+                }
+
+                // if next token is not TokenRef::MetaInfoValue, return error
+                let meta_info_value_token_with_position = token_with_position_list.next();
+                let (meta_info_value, meta_info_value_position) =
+                    match meta_info_value_token_with_position.as_ref() {
+                        Some(TokenRefWithPosition {
+                            token: TokenRef::MetaInfoValue(value),
+                            position,
+                        }) => (value, position),
+                        _ => {
+                            return Err(ErrorInfoWithPosition {
+                                error: ErrorInfo {
+                                    code: ErrorCode::Cimv2,
+                                    additional_info: None,
+                                    cause: None,
+                                },
+                                position: token_with_position.position.clone(),
+                            })
+                        }
+                    };
 
                 // add meta info to last chord block
-                match meta_info_key.as_str() {
+                match meta_info_key.as_ref() {
                     "key" => {
-                        let key_name = match meta_info_value.parse() {
+                        let key_name = match Key::from_str(meta_info_value) {
                             Ok(key) => key,
-                            Err(_) => {
+                            Err(error) => {
                                 return Err(ErrorInfoWithPosition {
-                                    error: ErrorInfo {
-                                        code: ErrorCode::Cimv4,
-                                        additional_info: None,
-                                    },
-                                    position: token_with_position.position.clone(),
+                                    error,
+                                    position: meta_info_value_position.clone(),
                                 })
                             }
                         };
@@ -271,47 +713,166 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                         // add ChordInfoMeta to temporary variable
                         tmp_chord_info_meta_infos.push(ChordInfoMeta::Key(key_name));
                     }
+                    "time" => {
+                        let time_signature = match TimeSignature::from_str(meta_info_value) {
+                            Ok(time_signature) => time_signature,
+                            Err(error) => {
+                                return Err(ErrorInfoWithPosition {
+                                    error,
+                                    position: meta_info_value_position.clone(),
+                                })
+                            }
+                        };
+
+                        tmp_chord_info_meta_infos.push(ChordInfoMeta::Time(time_signature));
+                    }
+                    "tempo" => {
+                        let tempo = match meta_info_value.parse::<u32>() {
+                            Ok(tempo) if (1..=999).contains(&tempo) => tempo,
+                            _ => {
+                                return Err(ErrorInfoWithPosition {
+                                    error: ErrorInfo {
+                                        code: ErrorCode::Cimv6,
+                                        additional_info: Some(meta_info_value.to_string()),
+                                        cause: None,
+                                    },
+                                    position: meta_info_value_position.clone(),
+                                })
+                            }
+                        };
+
+                        tmp_chord_info_meta_infos.push(ChordInfoMeta::Tempo(tempo));
+                    }
+                    "dynamics" => {
+                        let dynamics = match Dynamics::from_str(meta_info_value) {
+                            Ok(dynamics) => dynamics,
+                            Err(error) => {
+                                return Err(ErrorInfoWithPosition {
+                                    error,
+                                    position: meta_info_value_position.clone(),
+                                })
+                            }
+                        };
+
+                        tmp_chord_info_meta_infos.push(ChordInfoMeta::Dynamics(dynamics));
+                    }
+                    "articulation" => {
+                        let articulation = match Articulation::from_str(meta_info_value) {
+                            Ok(articulation) => articulation,
+                            Err(error) => {
+                                return Err(ErrorInfoWithPosition {
+                                    error,
+                                    position: meta_info_value_position.clone(),
+                                })
+                            }
+                        };
+
+                        tmp_chord_info_meta_infos.push(ChordInfoMeta::Articulation(articulation));
+                    }
+                    key if ChordInfoMeta::from_flag(key).is_some() => {
+                        return Err(ErrorInfoWithPosition {
+                            error: ErrorInfo {
+                                code: ErrorCode::Cimk5,
+                                additional_info: Some(key.to_string()),
+                                cause: None,
+                            },
+                            position: meta_info_key_position,
+                        });
+                    }
+                    key if registry.chord_meta.contains_key(key) => {
+                        if let Err(expected) = registry.chord_meta[key].validate(meta_info_value) {
+                            return Err(ErrorInfoWithPosition {
+                                error: ErrorInfo {
+                                    code: ErrorCode::Cimv9,
+                                    additional_info: Some(format!(
+                                        "{} ({})",
+                                        meta_info_value, expected
+                                    )),
+                                    cause: None,
+                                },
+                                position: meta_info_value_position.clone(),
+                            });
+                        }
+
+                        tmp_chord_info_meta_infos.push(ChordInfoMeta::Custom {
+                            key: key.to_string(),
+                            value: meta_info_value.to_string(),
+                        });
+                    }
                     _ => {
                         return Err(ErrorInfoWithPosition {
                             error: ErrorInfo {
                                 code: ErrorCode::Cimk3,
                                 additional_info: None,
+                                cause: None,
                             },
                             position: token_with_position.position.clone(),
                         });
                     }
                 }
 
-                // if next token is not Token::MetaInfoEnd, return error
-                match token_with_position_list.next().unwrap().token {
-                    Token::MetaInfoEnd => {}
+                // if next token is not TokenRef::MetaInfoEnd, return error
+                match token_with_position_list
+                    .next()
+                    .map(|next| next.token == TokenRef::MetaInfoEnd)
+                {
+                    Some(true) => {}
                     _ => {
                         return Err(ErrorInfoWithPosition {
                             error: ErrorInfo {
                                 code: ErrorCode::Cimv3,
                                 additional_info: None,
+                                cause: None,
                             },
                             position: token_with_position.position.clone(),
                         })
                     }
                 }
+
+                if !options
+                    .duplicate_meta
+                    .apply(&mut tmp_chord_info_meta_infos, ChordInfoMeta::key)
+                {
+                    return Err(ErrorInfoWithPosition {
+                        error: ErrorInfo {
+                            code: ErrorCode::Cimk6,
+                            additional_info: Some(meta_info_key.to_string()),
+                            cause: None,
+                        },
+                        position: meta_info_key_position,
+                    });
+                }
             }
             // chord
-            Token::Chord(chord_string) => {
+            TokenRef::Chord(chord_string) => {
+                chord_positions.push(token_with_position.position.clone());
+
                 // for chord expression of "_" or "?" or "%"
                 if chord_string.eq("_") || chord_string.eq("?") || chord_string.eq("%") {
-                    if sections.last_mut().unwrap().chord_blocks.is_empty() && chord_string == "%" {
+                    // with the option, "%" can repeat the last chord of the previous section
+                    let has_previous_chord = options.allow_same_at_section_start
+                        && sections.iter().any(|section| {
+                            section
+                                .chord_blocks
+                                .iter()
+                                .any(|chord_block| matches!(chord_block, ChordBlock::Bar(_)))
+                        });
+                    if sections.last_mut().unwrap().chord_blocks.is_empty()
+                        && chord_string == "%"
+                        && !has_previous_chord
+                    {
                         return Err(ErrorInfoWithPosition {
                             error: ErrorInfo {
                                 code: ErrorCode::Chb1,
                                 additional_info: None,
+                                cause: None,
                             },
                             position: token_with_position.position.clone(),
                         });
                     }
 
                     let new_chord_info = ChordInfo {
-                        chord_expression: match chord_string.as_str() {
+                        chord_expression: match chord_string.as_ref() {
                             "?" => ChordExpression::UnIdentified,
                             "%" => ChordExpression::Same,
                             "_" => ChordExpression::NoChord,
@@ -320,17 +881,18 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                     error: ErrorInfo {
                                         code: ErrorCode::Cho1,
                                         additional_info: None,
+                                        cause: None,
                                     },
                                     position: token_with_position.position.clone(),
                                 });
                             }
                         },
                         denominator: None,
-                        meta_infos: tmp_chord_info_meta_infos.clone(),
+                        meta_infos: std::mem::take(&mut tmp_chord_info_meta_infos),
                     };
 
                     // if previous token is comma,
-                    if previous.is_some() && previous.clone().unwrap().token == Token::Comma {
+                    if previous == Some(TokenKind::Comma) {
                         // add ChordInfo to last chord blocks
                         let chord_block = sections
                             .last_mut()
@@ -341,7 +903,7 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
 
                         match chord_block {
                             // create new Bar and add ChordInfo
-                            ChordBlock::Br => {
+                            ChordBlock::Br | ChordBlock::Navigation(_) => {
                                 // create new bar
                                 // TODO: if sections.last_mut().unwrap().chord_blocks.is_empty() { で囲うのはは不要？
                                 sections
@@ -355,9 +917,6 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                 cb.push(new_chord_info);
                             }
                         };
-
-                        // reset tmp_chord_info_meta_infos
-                        tmp_chord_info_meta_infos = Vec::new();
                     } else {
                         // add ChordInfo to last chord block
                         sections
@@ -365,29 +924,31 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                             .unwrap()
                             .chord_blocks
                             .push(ChordBlock::Bar(vec![new_chord_info]));
-
-                        // reset tmp_chord_info_meta_infos
-                        tmp_chord_info_meta_infos = Vec::new();
                     }
 
                     continue;
                 }
 
-                let chord_detailed_result = ChordDetailed::from_str(&chord_string);
+                // "{D|C7}" is a polychord
+                let chord_expression_result = if chord_string.starts_with('{') {
+                    PolyChord::from_str(chord_string).map(ChordExpression::PolyChord)
+                } else {
+                    ChordDetailed::from_str(chord_string).map(|detailed| {
+                        ChordExpression::Chord(Chord {
+                            plain: chord_string.to_string(),
+                            detailed,
+                        })
+                    })
+                };
 
                 // mutate line_number and column_number
-                if let Ok(detailed) = chord_detailed_result {
-                    let chord = Chord {
-                        plain: chord_string.clone(),
-                        detailed,
-                    };
-
+                if let Ok(chord_expression) = chord_expression_result {
                     // if previous token is comma,
-                    if previous.is_some() && previous.clone().unwrap().token == Token::Comma {
+                    if previous == Some(TokenKind::Comma) {
                         let new_chord_info = ChordInfo {
-                            chord_expression: ChordExpression::Chord(chord),
+                            chord_expression,
                             denominator: None,
-                            meta_infos: tmp_chord_info_meta_infos.clone(),
+                            meta_infos: std::mem::take(&mut tmp_chord_info_meta_infos),
                         };
 
                         // add ChordInfo to last chord blocks
@@ -400,7 +961,7 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
 
                         match chord_block {
                             // create new Bar and add ChordInfo
-                            ChordBlock::Br => {
+                            ChordBlock::Br | ChordBlock::Navigation(_) => {
                                 // create new bar
                                 // TODO: if sections.last_mut().unwrap().chord_blocks.is_empty() { で囲うのはは不要？
                                 sections
@@ -423,31 +984,23 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                             .unwrap()
                             .chord_blocks
                             .push(ChordBlock::Bar(vec![ChordInfo {
-                                chord_expression: ChordExpression::Chord(chord),
+                                chord_expression,
                                 denominator: None,
-                                meta_infos: tmp_chord_info_meta_infos.clone(),
+                                meta_infos: std::mem::take(&mut tmp_chord_info_meta_infos),
                             }]));
                     }
-
-                    // reset tmp_chord_info_meta_infos
-                    tmp_chord_info_meta_infos = Vec::new();
                 } else {
                     return Err(ErrorInfoWithPosition {
                         error: ErrorInfo {
                             code: ErrorCode::Cho1,
-                            additional_info: Some(
-                                [
-                                    chord_detailed_result.err().unwrap().code.to_string(),
-                                    chord_string.to_string(),
-                                ]
-                                .join(": "),
-                            ),
+                            additional_info: Some(chord_string.to_string()),
+                            cause: Some(chord_expression_result.err().unwrap().code),
                         },
                         position: token_with_position.position.clone(),
                     });
                 }
             }
-            Token::LineBreak => {
+            TokenRef::LineBreak => {
                 // if next token is not exist, continue to finish loop
                 let peeked_next_token_with_position_list = token_with_position_list.peek();
                 if peeked_next_token_with_position_list.is_none() {
@@ -456,7 +1009,7 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                 // if some ChordInfo in current Bar
                 if !sections.last().unwrap().chord_blocks.is_empty() {
                     // and next token is not LineBreak
-                    if peeked_next_token_with_position_list.unwrap().token != Token::LineBreak {
+                    if peeked_next_token_with_position_list.unwrap().token != TokenRef::LineBreak {
                         // create Br
                         sections
                             .last_mut()
@@ -468,8 +1021,8 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
 
                 // if "current" token is LineBreak
                 match peeked_next_token_with_position_list.unwrap().token {
-                    // if Token::LineBreak appears two or more times in a row, create new section
-                    Token::LineBreak => {
+                    // if TokenRef::LineBreak appears two or more times in a row, create new section
+                    TokenRef::LineBreak => {
                         token_with_position_list.next();
 
                         // if next is none, break
@@ -479,19 +1032,24 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
 
                         // if next is ChordBlockSeparator, create new section
                         match token_with_position_list.peek().unwrap().token {
-                            Token::MetaInfoStart | Token::Chord(_) => {
+                            TokenRef::MetaInfoStart
+                            | TokenRef::Chord(_)
+                            | TokenRef::RepeatStart
+                            | TokenRef::RepeatEnd
+                            | TokenRef::NavigationMark(_) => {
                                 // create new section
                                 sections.push(Section {
                                     meta_infos: Vec::new(),
                                     chord_blocks: Vec::new(),
                                 });
                             }
-                            Token::LineBreak => {
+                            TokenRef::LineBreak => {
                                 // error
                                 return Err(ErrorInfoWithPosition {
                                     error: ErrorInfo {
                                         code: ErrorCode::Bl1,
                                         additional_info: None,
+                                        cause: None,
                                     },
                                     position: token_with_position.position.clone(),
                                 });
@@ -502,33 +1060,44 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                     _ => { /* Nothing */ }
                 }
             }
-            Token::Extension(ext_str) => {
+            TokenRef::Extension(ext_str) => {
                 // if ext_str doesn't in Extension enum, error
-                if Extension::from_str(&ext_str).is_err() {
-                    let cloned_token_with_position = token_with_position.clone();
+                if Extension::from_str(ext_str).is_err() {
                     return Err(ErrorInfoWithPosition {
                         error: ErrorInfo {
                             code: ErrorCode::Ext1,
                             additional_info: Some(ext_str.to_string()),
+                            cause: None,
                         },
                         position: Position {
-                            line_number: cloned_token_with_position.position.line_number,
-                            column_number: cloned_token_with_position.position.column_number,
+                            line_number: token_with_position.position.line_number,
+                            column_number: token_with_position.position.column_number,
                             length: ext_str.len(),
                         },
                     });
                 }
 
                 if let Some(last_section) = sections.last_mut() {
-                    let last_chord_block = last_section.chord_blocks.last_mut().unwrap();
+                    // the extension needs the chord before it, like the denominator
+                    let Some(last_chord_block) = last_section.chord_blocks.last_mut() else {
+                        return Err(ErrorInfoWithPosition {
+                            error: ErrorInfo {
+                                code: ErrorCode::Cho3,
+                                additional_info: None,
+                                cause: None,
+                            },
+                            position: token_with_position.position.clone(),
+                        });
+                    };
 
-                    // if last chord_block is br, error
+                    // if last chord_block is br (or navigation mark), error
                     match last_chord_block {
-                        ChordBlock::Br => {
+                        ChordBlock::Br | ChordBlock::Navigation(_) => {
                             return Err(ErrorInfoWithPosition {
                                 error: ErrorInfo {
                                     code: ErrorCode::Ext3,
                                     additional_info: None,
+                                    cause: None,
                                 },
                                 position: token_with_position.position.clone(),
                             });
@@ -540,9 +1109,22 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                 ChordExpression::UnIdentified => {}
                                 ChordExpression::Same => {}
                                 ChordExpression::NoChord => {}
+                                // the extensions belong to one of the chords, like "{D(9)|C}"
+                                ChordExpression::PolyChord(_) => {
+                                    return Err(ErrorInfoWithPosition {
+                                        error: ErrorInfo {
+                                            code: ErrorCode::Cho6,
+                                            additional_info: Some(ext_str.to_string()),
+                                            cause: None,
+                                        },
+                                        position: token_with_position.position.clone(),
+                                    });
+                                }
                                 ChordExpression::Chord(c) => {
                                     let mut parsed_extensions =
-                                        vec![Extension::from_str(&ext_str).unwrap()];
+                                        vec![Extension::from_str(ext_str).unwrap()];
+                                    // spellings as written (like "-5"), kept in plain
+                                    let mut written_extensions = vec![ext_str.to_string()];
 
                                     // REFACTOR: please remove this flag variable for refactoring
                                     let mut is_previous_token_is_comma = false;
@@ -550,12 +1132,13 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                     for t in token_with_position_list.by_ref() {
                                         // validation
                                         match &t.token {
-                                            Token::Comma => {
+                                            TokenRef::Comma => {
                                                 if is_previous_token_is_comma {
                                                     return Err(ErrorInfoWithPosition {
                                                         error: ErrorInfo {
                                                             code: ErrorCode::Ext2,
                                                             additional_info: None,
+                                                            cause: None,
                                                         },
                                                         position: t.position.clone(),
                                                     });
@@ -566,7 +1149,7 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                         }
 
                                         match &t.token {
-                                            Token::ExtensionEnd => {
+                                            TokenRef::ExtensionEnd => {
                                                 let peeked_token_with_position_list =
                                                     token_with_position_list.peek();
 
@@ -575,13 +1158,14 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                                 }
 
                                                 // if next token is ExtensionStart, error
-                                                if let Token::ExtensionStart =
+                                                if let TokenRef::ExtensionStart =
                                                     peeked_token_with_position_list.unwrap().token
                                                 {
                                                     return Err(ErrorInfoWithPosition {
                                                         error: ErrorInfo {
                                                             code: ErrorCode::Ext4,
                                                             additional_info: None,
+                                                            cause: None,
                                                         },
                                                         position: token_with_position
                                                             .position
@@ -591,16 +1175,13 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
 
                                                 break;
                                             }
-                                            Token::Comma => {
+                                            TokenRef::Comma => {
                                                 continue;
                                             }
-                                            Token::Extension(ext_str) => {
+                                            TokenRef::Extension(ext_str) => {
                                                 if Extension::from_str(ext_str).is_err() {
-                                                    let cloned_token_with_position =
-                                                        token_with_position.clone();
-
                                                     let extensions_before_current_length =
-                                                        parsed_extensions
+                                                        written_extensions
                                                             .iter()
                                                             .map(|e| e.to_string() + ",")
                                                             .collect::<Vec<String>>()
@@ -609,13 +1190,16 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                                     return Err(ErrorInfoWithPosition {
                                                         error: ErrorInfo {
                                                             code: ErrorCode::Ext1,
-                                                            additional_info: Some(ext_str.to_string()),
+                                                            additional_info: Some(
+                                                                ext_str.to_string(),
+                                                            ),
+                                                            cause: None,
                                                         },
                                                         position: Position {
-                                                            line_number: cloned_token_with_position
+                                                            line_number: token_with_position
                                                                 .position
                                                                 .line_number,
-                                                            column_number: cloned_token_with_position
+                                                            column_number: token_with_position
                                                                 .position
                                                                 .column_number
                                                                 + extensions_before_current_length
@@ -626,20 +1210,20 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                                 }
                                                 parsed_extensions
                                                     .push(Extension::from_str(ext_str).unwrap());
+                                                written_extensions.push(ext_str.to_string());
                                             }
                                             _ => {
-                                                let cloned_token_with_position =
-                                                    token_with_position.clone();
                                                 return Err(ErrorInfoWithPosition {
                                                     error: ErrorInfo {
                                                         code: ErrorCode::Ext1,
                                                         additional_info: Some(t.token.to_string()),
+                                                        cause: None,
                                                     },
                                                     position: Position {
-                                                        line_number: cloned_token_with_position
+                                                        line_number: token_with_position
                                                             .position
                                                             .line_number,
-                                                        column_number: cloned_token_with_position
+                                                        column_number: token_with_position
                                                             .position
                                                             .column_number,
                                                         length: t.token.to_string().len(),
@@ -648,14 +1232,8 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                             }
                                         }
                                     }
-                                    let extension_str_with_parenthesis = format!(
-                                        "({})",
-                                        parsed_extensions
-                                            .iter()
-                                            .map(|e| e.to_string())
-                                            .collect::<Vec<String>>()
-                                            .join(",")
-                                    );
+                                    let extension_str_with_parenthesis =
+                                        format!("({})", written_extensions.join(","));
 
                                     last_cb.last_mut().unwrap().chord_expression =
                                         ChordExpression::Chord(Chord {
@@ -668,7 +1246,12 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                                 base: c.detailed.base.clone(),
                                                 accidental: c.detailed.accidental.clone(),
                                                 chord_type: c.detailed.chord_type.clone(),
-                                                extensions: parsed_extensions,
+                                                // keep inline extensions like "7" of "C7(9)"
+                                                extensions: [
+                                                    c.detailed.extensions.clone(),
+                                                    parsed_extensions,
+                                                ]
+                                                .concat(),
                                             },
                                         });
                                 }
@@ -677,12 +1260,13 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                     }
                 }
             }
-            Token::Denominator(denominator) => {
+            TokenRef::Denominator(denominator) => {
                 if sections.last_mut().unwrap().chord_blocks.last().is_none() {
                     return Err(ErrorInfoWithPosition {
                         error: ErrorInfo {
                             code: ErrorCode::Cho3,
                             additional_info: None,
+                            cause: None,
                         },
                         position: token_with_position.position.clone(),
                     });
@@ -695,12 +1279,13 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                     .last_mut()
                     .unwrap()
                 {
-                    ChordBlock::Br => {
-                        // if previous chord block is br, error
+                    ChordBlock::Br | ChordBlock::Navigation(_) => {
+                        // if previous chord block is br (or navigation mark), error
                         return Err(ErrorInfoWithPosition {
                             error: ErrorInfo {
                                 code: ErrorCode::Cho3,
                                 additional_info: None,
+                                cause: None,
                             },
                             position: token_with_position.position.clone(),
                         });
@@ -712,49 +1297,51 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                                 error: ErrorInfo {
                                     code: ErrorCode::Den1,
                                     additional_info: None,
+                                    cause: None,
                                 },
                                 position: token_with_position.position.clone(),
                             });
                         }
 
-                        cb.last_mut().unwrap().denominator = Some(denominator.clone());
+                        cb.last_mut().unwrap().denominator = Some(denominator.to_string());
                     }
                 }
             }
-            Token::Comma => { /* Nothing */ }
-            Token::ChordBlockSeparator => {
+            TokenRef::Comma => {
+                // a comma continues the bar, so it can't begin the section (like "@section=A\n, C")
+                if sections.last().unwrap().chord_blocks.is_empty() {
+                    return Err(ErrorInfoWithPosition {
+                        error: ErrorInfo {
+                            code: ErrorCode::Tkn1,
+                            additional_info: Some(token_with_position.token.to_string()),
+                            cause: None,
+                        },
+                        position: token_with_position.position.clone(),
+                    });
+                }
+            }
+            TokenRef::ChordBlockSeparator => {
                 // if previous is not Chord, error
-                match previous {
-                    None => {
-                        return Err(ErrorInfoWithPosition {
-                            error: ErrorInfo {
-                                code: ErrorCode::Cho3,
-                                additional_info: token_with_position.token.to_string().into(),
-                            },
-                            position: token_with_position.position.clone(),
-                        });
-                    }
-                    _ => {
-                        let previous_token = previous.unwrap().token;
-                        match previous_token {
-                            Token::Chord(_)
-                            | Token::Denominator(_)
-                            | Token::Extension(_)
-                            | Token::ExtensionEnd => { /* Nothing */ }
-                            _ => {
-                                return Err(ErrorInfoWithPosition {
-                                    error: ErrorInfo {
-                                        code: ErrorCode::Cho3,
-                                        additional_info: token_with_position
-                                            .token
-                                            .to_string()
-                                            .into(),
-                                    },
-                                    position: token_with_position.position.clone(),
-                                });
-                            }
-                        }
-                    }
+                if !matches!(
+                    previous,
+                    Some(
+                        TokenKind::Chord
+                            | TokenKind::Denominator
+                            | TokenKind::Extension
+                            | TokenKind::ExtensionEnd
+                            | TokenKind::RepeatStart
+                            | TokenKind::RepeatEnd
+                            | TokenKind::NavigationMark
+                    )
+                ) {
+                    return Err(ErrorInfoWithPosition {
+                        error: ErrorInfo {
+                            code: ErrorCode::Cho3,
+                            additional_info: token_with_position.token.to_string().into(),
+                            cause: None,
+                        },
+                        position: token_with_position.position.clone(),
+                    });
                 }
 
                 // ?
@@ -764,7 +1351,7 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
 
                 // if last and second last token is BreakLine, create new Section
                 match token_with_position_list.peek().unwrap().token {
-                    Token::ChordBlockSeparator => {
+                    TokenRef::ChordBlockSeparator => {
                         // TODO: ここにも例の（コードブロックなのかCSVなインフォの方か）のif分岐を作成？
                         // if chord_blocks is empty, make new bar
                         if sections.last_mut().unwrap().chord_blocks.is_empty() {
@@ -783,13 +1370,13 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                             .last_mut()
                             .unwrap()
                         {
-                            ChordBlock::Br => {}
+                            ChordBlock::Br | ChordBlock::Navigation(_) => {}
                             ChordBlock::Bar(cb) => {
                                 // create new bar
                                 cb.push(ChordInfo {
                                     chord_expression: ChordExpression::NoChord,
                                     denominator: None,
-                                    meta_infos: tmp_chord_info_meta_infos.clone(),
+                                    meta_infos: std::mem::take(&mut tmp_chord_info_meta_infos),
                                 });
                             }
                         }
@@ -797,30 +1384,54 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
                     _ => { /* Nothing */ }
                 }
             }
-            Token::Equal => { /* Nothing */ }
-            Token::Slash => { /* Nothing */ }
-            Token::ExtensionStart => {
+            TokenRef::Equal => { /* Nothing */ }
+            TokenRef::Slash => { /* Nothing */ }
+            TokenRef::ExtensionStart => {
                 // if next token is not Extension, error
-                match token_with_position_list.peek().unwrap().token {
-                    Token::Extension(_) => { /* Nothing */ }
+                match token_with_position_list.peek().map(|next| &next.token) {
+                    Some(TokenRef::Extension(_)) => { /* Nothing */ }
                     _ => {
                         return Err(ErrorInfoWithPosition {
                             error: ErrorInfo {
                                 code: ErrorCode::Ext2,
                                 additional_info: None,
+                                cause: None,
                             },
                             position: token_with_position.position.clone(),
                         });
                     }
                 }
             }
-            Token::ExtensionEnd => { /* Nothing */ }
+            TokenRef::ExtensionEnd => { /* Nothing */ }
+            TokenRef::RepeatStart | TokenRef::RepeatEnd | TokenRef::NavigationMark(_) => {
+                let mark_string = match &token_with_position.token {
+                    TokenRef::NavigationMark(mark_string) => mark_string.clone(),
+                    token => token.to_string().into(),
+                };
+                let mark = match NavigationMark::from_str(&mark_string) {
+                    Ok(mark) => mark,
+                    Err(error) => {
+                        return Err(ErrorInfoWithPosition {
+                            error,
+                            position: token_with_position.position.clone(),
+                        })
+                    }
+                };
+
+                navigation_marks.push((mark.clone(), token_with_position.position.clone()));
+                sections
+                    .last_mut()
+                    .unwrap()
+                    .chord_blocks
+                    .push(ChordBlock::Navigation(mark));
+            }
             _ => {
                 // invalid token
                 return Err(ErrorInfoWithPosition {
                     error: ErrorInfo {
                         code: ErrorCode::Tkn1,
                         additional_info: Some(token_with_position.token.to_string()),
+                        cause: None,
                     },
                     position: token_with_position.position.clone(),
                 });
@@ -828,18 +1439,102 @@ pub fn parse(token_with_position_list: &[TokenWithPosition]) -> Result<Ast, Erro
         }
     }
 
-    Ok(sections)
+    Ok(ParsedSections {
+        sections,
+        navigation_marks,
+        chord_positions,
+        section_references,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tokenizer::tokenize;
+    use crate::tokenizer::types::token::Token;
     use types::accidental::Accidental;
     use types::base::Base;
     use types::chord_info::ChordInfo;
     use types::chord_type::ChordType;
     use types::extension::Extension;
 
+    #[test]
+    fn parse_input_is_the_same_as_tokenize_and_parse() {
+        const PIECES: [&str; 32] = [
+            "C",
+            "G7",
+            "-",
+            ",",
+            "(",
+            ")",
+            "9",
+            "/",
+            "[key=D]",
+            "[",
+            "]",
+            "=",
+            "@",
+            "@section=A\n",
+            "@use=A\n",
+            "@def X=Am\n",
+            "$X",
+            " ",
+            "\n",
+            "\n\n",
+            "\r",
+            "// x",
+            "<segno>",
+            "<",
+            "|:",
+            ":|",
+            "{D|C}",
+            "6",
+            "%",
+            "_",
+            "o",
+            "Section",
+        ];
+        let mut options = ParseOptions {
+            allow_consecutive_blank_lines: true,
+            case_insensitive_section_keys: true,
+            ..Default::default()
+        };
+        options
+            .chord_aliases
+            .insert("7".to_string(), "M7".to_string());
+
+        // pseudo random inputs of the pieces, mostly invalid
+        let mut seed: u32 = 1;
+        let mut random = |max: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as usize % max
+        };
+        for _ in 0..20000 {
+            let input: String = (0..random(16))
+                .map(|_| PIECES[random(PIECES.len())])
+                .collect();
+            for options in [&ParseOptions::default(), &options] {
+                assert_eq!(
+                    parse_input(&input, options),
+                    tokenize(&input).and_then(|tokens| parse_with_options(&tokens, options)),
+                    "{:?}",
+                    input
+                );
+                // the warnings and the lint rules read the tokens parsed to the AST
+                if let Ok((_, tokens)) = parse_input_with_tokens(&input, options) {
+                    assert_eq!(
+                        Ok(tokens),
+                        tokenize(&input).and_then(|tokens| prepare_tokens(&tokens, options)),
+                        "{:?}",
+                        input
+                    );
+                }
+            }
+        }
+    }
+
     #[cfg(test)]
     mod success {
         use super::*;
@@ -1276,7 +1971,7 @@ mod tests {
                     },
                 },
                 TokenWithPosition {
-                    token: Token::SectionMetaInfoKey("section".to_string()),
+                    token: Token::SectionMetaInfoKey("repeat".to_string()),
                     position: Position {
                         line_number: 3,
                         column_number: 2,
@@ -1292,18 +1987,18 @@ mod tests {
                     },
                 },
                 TokenWithPosition {
-                    token: Token::SectionMetaInfoValue("AA".to_string()),
+                    token: Token::SectionMetaInfoValue("2".to_string()),
                     position: Position {
                         line_number: 3,
                         column_number: 10,
-                        length: 2,
+                        length: 1,
                     },
                 },
                 TokenWithPosition {
                     token: Token::LineBreak,
                     position: Position {
                         line_number: 3,
-                        column_number: 12,
+                        column_number: 11,
                         length: 1,
                     },
                 },
@@ -1314,7 +2009,7 @@ mod tests {
                 Ok([Section {
                     meta_infos: vec![
                         SectionMeta::Section("A".to_string()),
-                        SectionMeta::Section("AA".to_string())
+                        SectionMeta::Repeat(2)
                     ],
                     chord_blocks: Vec::new(),
                 }]
@@ -1828,6 +2523,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Chb1,
                         additional_info: None,
+                        cause: None,
                     },
                     position: Position {
                         line_number: 2,
@@ -1906,6 +2602,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Ext2,
                         additional_info: None,
+                        cause: None,
                     },
                     position: Position {
                         line_number: 1,
@@ -2008,6 +2705,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Bl1,
                         additional_info: None,
+                        cause: None,
                     },
                     position: Position {
                         line_number: 1,
@@ -2078,6 +2776,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Ext1,
                         additional_info: Some("1".to_string()),
+                        cause: None,
                     },
                     position: Position {
                         line_number: 1,
@@ -2156,6 +2855,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Ext4,
                         additional_info: None,
+                        cause: None,
                     },
                     position: Position {
                         line_number: 1,
@@ -2226,6 +2926,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Bl1,
                         additional_info: None,
+                        cause: None,
                     },
                     position: Position {
                         line_number: 1,
@@ -2280,6 +2981,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Ext1,
                         additional_info: Some("1".to_string()),
+                        cause: None,
                     },
                     position: Position {
                         line_number: 1,
@@ -2341,6 +3043,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Smiv3,
                         additional_info: None,
+                        cause: None,
                     },
                     position: Position {
                         line_number: 1,
@@ -2402,6 +3105,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Smik1,
                         additional_info: Some("asdf".to_string()),
+                        cause: None,
                     },
                     position: Position {
                         line_number: 1,
@@ -2471,6 +3175,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Smiv2,
                         additional_info: None,
+                        cause: None,
                     },
                     position: Position {
                         line_number: 1,
@@ -2516,6 +3221,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Cho3,
                         additional_info: None,
+                        cause: None,
                     },
                     position: Position {
                         line_number: 1,
@@ -2577,6 +3283,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Den1,
                         additional_info: None,
+                        cause: None,
                     },
                     position: Position {
                         line_number: 1,
@@ -2630,6 +3337,7 @@ mod tests {
                     error: ErrorInfo {
                         code: ErrorCode::Ext2,
                         additional_info: None,
+                        cause: None,
                     },
                     position: Position {
                         line_number: 1,
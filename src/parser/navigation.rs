@@ -0,0 +1,181 @@
+use crate::error_code::{ErrorCode, ErrorInfo, ErrorInfoWithPosition};
+use crate::util::position::Position;
+
+use super::types::navigation_mark::NavigationMark;
+
+fn error(code: ErrorCode, position: &Position) -> ErrorInfoWithPosition {
+    ErrorInfoWithPosition {
+        error: ErrorInfo {
+            code,
+            additional_info: None,
+            cause: None,
+        },
+        position: position.clone(),
+    }
+}
+
+/// Check repeats are paired and every jump has its target.
+/// `<coda>` has to come after `<to coda>`, or the jump would go backwards.
+/// `marks` are the navigation marks of the whole chord progression in the order of appearance.
+pub fn validate_navigation_marks(
+    marks: &[(NavigationMark, Position)],
+) -> Result<(), ErrorInfoWithPosition> {
+    let has = |target: NavigationMark| marks.iter().any(|(mark, _)| *mark == target);
+    let mut open_repeat: Option<&Position> = None;
+
+    for (index, (mark, position)) in marks.iter().enumerate() {
+        match mark {
+            NavigationMark::RepeatStart => {
+                if open_repeat.is_some() {
+                    return Err(error(ErrorCode::Nav4, position));
+                }
+                open_repeat = Some(position);
+            }
+            NavigationMark::RepeatEnd => {
+                if open_repeat.is_none() {
+                    return Err(error(ErrorCode::Nav3, position));
+                }
+                open_repeat = None;
+            }
+            _ => {}
+        }
+
+        let needs_segno = matches!(
+            mark,
+            NavigationMark::DalSegno
+                | NavigationMark::DalSegnoAlFine
+                | NavigationMark::DalSegnoAlCoda
+        );
+        if needs_segno && !has(NavigationMark::Segno) {
+            return Err(error(ErrorCode::Nav5, position));
+        }
+
+        let needs_coda = matches!(
+            mark,
+            NavigationMark::DaCapoAlCoda | NavigationMark::DalSegnoAlCoda
+        );
+        if needs_coda && !has(NavigationMark::Coda) {
+            return Err(error(ErrorCode::Nav6, position));
+        }
+
+        let has_coda_after = marks[index..]
+            .iter()
+            .any(|(mark, _)| *mark == NavigationMark::Coda);
+        if *mark == NavigationMark::ToCoda && !has_coda_after {
+            return Err(error(ErrorCode::Nav6, position));
+        }
+
+        let needs_fine = matches!(
+            mark,
+            NavigationMark::DaCapoAlFine | NavigationMark::DalSegnoAlFine
+        );
+        if needs_fine && !has(NavigationMark::Fine) {
+            return Err(error(ErrorCode::Nav7, position));
+        }
+    }
+
+    match open_repeat {
+        Some(position) => Err(error(ErrorCode::Nav2, position)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marks(marks: &[NavigationMark]) -> Vec<(NavigationMark, Position)> {
+        marks
+            .iter()
+            .enumerate()
+            .map(|(index, mark)| {
+                (
+                    mark.clone(),
+                    Position {
+                        line_number: 1,
+                        column_number: index + 1,
+                        length: 1,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn error_code_and_column(marks: &[(NavigationMark, Position)]) -> (ErrorCode, usize) {
+        let error = validate_navigation_marks(marks).unwrap_err();
+        (error.error.code, error.position.column_number)
+    }
+
+    #[test]
+    fn valid_marks() {
+        assert!(validate_navigation_marks(&marks(&[
+            NavigationMark::RepeatStart,
+            NavigationMark::Volta(vec![1]),
+            NavigationMark::RepeatEnd,
+            NavigationMark::Volta(vec![2]),
+            NavigationMark::Segno,
+            NavigationMark::ToCoda,
+            NavigationMark::DalSegnoAlCoda,
+            NavigationMark::Coda,
+        ]))
+        .is_ok());
+    }
+
+    #[test]
+    fn unmatched_repeats() {
+        assert_eq!(
+            error_code_and_column(&marks(&[
+                NavigationMark::RepeatEnd,
+                NavigationMark::RepeatStart
+            ])),
+            (ErrorCode::Nav3, 1)
+        );
+        assert_eq!(
+            error_code_and_column(&marks(&[
+                NavigationMark::RepeatStart,
+                NavigationMark::RepeatEnd,
+                NavigationMark::RepeatStart
+            ])),
+            (ErrorCode::Nav2, 3)
+        );
+        assert_eq!(
+            error_code_and_column(&marks(&[
+                NavigationMark::RepeatStart,
+                NavigationMark::RepeatStart,
+                NavigationMark::RepeatEnd
+            ])),
+            (ErrorCode::Nav4, 2)
+        );
+    }
+
+    #[test]
+    fn missing_jump_target() {
+        assert_eq!(
+            error_code_and_column(&marks(&[NavigationMark::Coda, NavigationMark::DalSegno])),
+            (ErrorCode::Nav5, 2)
+        );
+        assert_eq!(
+            error_code_and_column(&marks(&[
+                NavigationMark::ToCoda,
+                NavigationMark::DaCapoAlCoda
+            ])),
+            (ErrorCode::Nav6, 1)
+        );
+        assert_eq!(
+            error_code_and_column(&marks(&[NavigationMark::DaCapoAlFine])),
+            (ErrorCode::Nav7, 1)
+        );
+    }
+
+    #[test]
+    fn coda_before_to_coda() {
+        assert_eq!(
+            error_code_and_column(&marks(&[
+                NavigationMark::Coda,
+                NavigationMark::ToCoda,
+                NavigationMark::DaCapoAlCoda
+            ])),
+            (ErrorCode::Nav6, 2)
+        );
+    }
+}
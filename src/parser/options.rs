@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::error_code::{ErrorCode, ErrorInfo, ErrorInfoWithPosition};
+use crate::tokenizer::types::token_ref::{TokenRef, TokenRefWithPosition};
+use crate::tokenizer::types::token_with_position::TokenWithPosition;
+use crate::util::note::split_note;
+use crate::util::position::Position;
+
+use super::meta_registry::MetaRegistry;
+
+/// What to do with the meta infos of the same key in a section (like two `@repeat`) or before a chord (like `[key=C][key=D]`)
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DuplicateMetaPolicy {
+    /// keep all of them
+    Allow,
+    KeepFirst,
+    KeepLast,
+    /// `SMIK-3` for the section, `CIMK-6` for the chord (the default)
+    #[default]
+    Error,
+}
+
+impl DuplicateMetaPolicy {
+    /// apply the policy to the meta info pushed last. false if it is a duplicate, which is an error.
+    pub(crate) fn apply<T>(self, meta_infos: &mut Vec<T>, key: impl Fn(&T) -> &str) -> bool {
+        let Some((last, previous)) = meta_infos.split_last() else {
+            return true;
+        };
+        let last_key = key(last).to_string();
+        if !previous.iter().any(|meta_info| key(meta_info) == last_key) {
+            return true;
+        }
+
+        match self {
+            DuplicateMetaPolicy::Allow => {}
+            DuplicateMetaPolicy::KeepFirst => {
+                meta_infos.pop();
+            }
+            DuplicateMetaPolicy::KeepLast => {
+                let last_index = meta_infos.len() - 1;
+                let mut index = 0;
+                meta_infos.retain(|meta_info| {
+                    index += 1;
+                    index - 1 == last_index || key(meta_info) != last_key
+                });
+            }
+            DuplicateMetaPolicy::Error => return false,
+        }
+        true
+    }
+}
+
+/// Options of the parser. The default is strict, like the editor, and each option makes it more lenient.
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::parser::options::ParseOptions;
+/// use chord_progression_parser::parse_chord_progression_string_with_options;
+///
+/// let options = ParseOptions {
+///     allow_consecutive_blank_lines: true,
+///     case_insensitive_section_keys: true,
+///     ..Default::default()
+/// };
+/// let ast = parse_chord_progression_string_with_options("@Section=A\nC\n\n\n\nG", &options);
+/// assert!(ast.is_ok());
+/// ```
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ParseOptions {
+    /// allow more than one blank line (or line of whitespaces) in a row, instead of `BL-1`
+    pub allow_consecutive_blank_lines: bool,
+    /// allow `%` at the beginning of a section, repeating the last chord of the previous section, instead of `CHB-1`
+    pub allow_same_at_section_start: bool,
+    /// chord qualities read as other ones, like `"ma7": "M7"` for `Cma7`. the AST has the chord written with the replacement.
+    pub chord_aliases: HashMap<String, String>,
+    /// read the section meta keys in lower case, like `@Section=A`
+    pub case_insensitive_section_keys: bool,
+    pub duplicate_meta: DuplicateMetaPolicy,
+    /// maximum size of the input in bytes, `OTHER-2` if larger
+    pub max_input_size: Option<usize>,
+    /// custom meta keys
+    pub registry: MetaRegistry,
+}
+
+/// `OTHER-2` at the first character beyond the maximum size
+pub fn check_input_size(input: &str, options: &ParseOptions) -> Result<(), ErrorInfoWithPosition> {
+    let max_input_size = match options.max_input_size {
+        Some(max_input_size) if input.len() > max_input_size => max_input_size,
+        _ => return Ok(()),
+    };
+
+    let mut end = max_input_size;
+    while !input.is_char_boundary(end) {
+        end -= 1;
+    }
+    let accepted = &input[..end];
+    let last_line = accepted.rsplit('\n').next().unwrap_or_default();
+
+    Err(ErrorInfoWithPosition {
+        error: ErrorInfo {
+            code: ErrorCode::Other2,
+            additional_info: Some(format!("{} > {} bytes", input.len(), max_input_size)),
+            cause: None,
+        },
+        position: Position {
+            line_number: accepted.matches('\n').count() + 1,
+            column_number: last_line.chars().count() + 1,
+            length: 1,
+        },
+    })
+}
+
+/// Rewrites the tokens by the lenient options one by one, before parsing
+pub(crate) struct Normalizer<'o> {
+    options: &'o ParseOptions,
+    /// line breaks in a row at the end of the rewritten tokens
+    line_breaks: usize,
+}
+
+impl<'o> Normalizer<'o> {
+    pub(crate) fn new(options: &'o ParseOptions) -> Normalizer<'o> {
+        Normalizer {
+            options,
+            line_breaks: 0,
+        }
+    }
+
+    /// the token rewritten, or `None` if it is dropped
+    pub(crate) fn normalize<'a>(
+        &mut self,
+        token_with_position: TokenRefWithPosition<'a>,
+    ) -> Option<TokenRefWithPosition<'a>> {
+        let options = self.options;
+        let token = match token_with_position.token {
+            // a blank line is two line breaks in a row, so the third one is dropped
+            TokenRef::LineBreak
+                if options.allow_consecutive_blank_lines && self.line_breaks >= 2 =>
+            {
+                return None
+            }
+            TokenRef::SectionMetaInfoKey(key) if options.case_insensitive_section_keys => {
+                TokenRef::SectionMetaInfoKey(key.to_lowercase().into())
+            }
+            TokenRef::Chord(chord) if !options.chord_aliases.is_empty() => {
+                TokenRef::Chord(replace_alias(&chord, &options.chord_aliases).into())
+            }
+            token => token,
+        };
+
+        self.line_breaks = match token {
+            TokenRef::LineBreak => self.line_breaks + 1,
+            _ => 0,
+        };
+        Some(TokenRefWithPosition {
+            token,
+            position: token_with_position.position,
+        })
+    }
+}
+
+/// tokens rewritten by the lenient options, before parsing
+pub(crate) fn normalize_tokens<'a>(
+    tokens: &'a [TokenWithPosition],
+    options: &ParseOptions,
+) -> Vec<TokenRefWithPosition<'a>> {
+    let mut normalizer = Normalizer::new(options);
+
+    tokens
+        .iter()
+        .filter_map(|token_with_position| normalizer.normalize(token_with_position.into()))
+        .collect()
+}
+
+/// replace the longest alias the quality of the chord (after the root) starts with
+fn replace_alias(chord: &str, chord_aliases: &HashMap<String, String>) -> String {
+    let quality = match split_note(chord) {
+        Some((_, _, quality)) => quality,
+        None => return chord.to_string(),
+    };
+    let root = &chord[..chord.len() - quality.len()];
+
+    match chord_aliases
+        .iter()
+        .filter(|(alias, _)| !alias.is_empty() && quality.starts_with(alias.as_str()))
+        .max_by_key(|(alias, _)| alias.len())
+    {
+        Some((alias, replacement)) => [root, replacement, &quality[alias.len()..]].concat(),
+        None => chord.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_meta_policy() {
+        let apply = |policy: DuplicateMetaPolicy| {
+            let mut meta_infos = vec![("key", 1), ("time", 2), ("key", 3)];
+            let is_ok = policy.apply(&mut meta_infos, |(key, _)| key);
+            (is_ok, meta_infos)
+        };
+
+        assert_eq!(
+            apply(DuplicateMetaPolicy::Allow),
+            (true, vec![("key", 1), ("time", 2), ("key", 3)])
+        );
+        assert_eq!(
+            apply(DuplicateMetaPolicy::KeepFirst),
+            (true, vec![("key", 1), ("time", 2)])
+        );
+        assert_eq!(
+            apply(DuplicateMetaPolicy::KeepLast),
+            (true, vec![("time", 2), ("key", 3)])
+        );
+        assert!(!apply(DuplicateMetaPolicy::Error).0);
+    }
+
+    #[test]
+    fn longest_alias_is_replaced() {
+        let chord_aliases = HashMap::from([
+            ("ma".to_string(), "M".to_string()),
+            ("ma7".to_string(), "M7".to_string()),
+            ("h".to_string(), "ø".to_string()),
+        ]);
+
+        assert_eq!(replace_alias("Cma7", &chord_aliases), "CM7");
+        assert_eq!(replace_alias("F#ma9", &chord_aliases), "F#M9");
+        assert_eq!(replace_alias("Bbh7", &chord_aliases), "Bbø7");
+        assert_eq!(replace_alias("Am7", &chord_aliases), "Am7");
+    }
+
+    #[test]
+    fn input_size() {
+        let options = ParseOptions {
+            max_input_size: Some(6),
+            ..Default::default()
+        };
+
+        assert!(check_input_size("C - G", &options).is_ok());
+        let error_info = check_input_size("C - G\nAm", &options).unwrap_err();
+        assert_eq!(error_info.error.code, ErrorCode::Other2);
+        assert_eq!(
+            error_info.position,
+            Position {
+                line_number: 2,
+                column_number: 1,
+                length: 1,
+            }
+        );
+    }
+}
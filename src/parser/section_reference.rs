@@ -0,0 +1,188 @@
+use crate::error_code::{ErrorCode, ErrorInfo, ErrorInfoWithPosition};
+use crate::transposer::{transpose, transposed_spelling};
+use crate::util::position::Position;
+
+use super::types::chord_block::ChordBlock;
+use super::types::section::Section;
+use super::types::section_meta::SectionMeta;
+use super::types::section_reference::SectionReference;
+
+fn reference_of(section: &Section) -> Option<&SectionReference> {
+    section
+        .meta_infos
+        .iter()
+        .find_map(|meta_info| match meta_info {
+            SectionMeta::Use(reference) => Some(reference),
+            _ => None,
+        })
+}
+
+/// Fill the chord blocks of the sections with `@use` by cloning (and transposing) the referenced sections.
+/// `references` are the index of the section with `@use` and the position of its value.
+pub fn resolve_section_references(
+    sections: &mut [Section],
+    references: &[(usize, Position)],
+) -> Result<(), ErrorInfoWithPosition> {
+    for (index, position) in references.iter() {
+        if !sections[*index].chord_blocks.is_empty() {
+            return Err(ErrorInfoWithPosition {
+                error: ErrorInfo {
+                    code: ErrorCode::Smiv7,
+                    additional_info: None,
+                    cause: None,
+                },
+                position: position.clone(),
+            });
+        }
+    }
+
+    // resolve all before filling, so that every reference is resolved from the sections as written
+    let mut resolved: Vec<(usize, Vec<ChordBlock>)> = vec![];
+    for (index, position) in references.iter() {
+        let chord_blocks =
+            resolve(sections, *index, &mut vec![]).map_err(|error| ErrorInfoWithPosition {
+                error,
+                position: position.clone(),
+            })?;
+        resolved.push((*index, chord_blocks));
+    }
+
+    for (index, chord_blocks) in resolved {
+        sections[index].chord_blocks = chord_blocks;
+    }
+
+    Ok(())
+}
+
+/// chord blocks of the section, following the references. `visiting` is for detecting cycles.
+fn resolve(
+    sections: &[Section],
+    index: usize,
+    visiting: &mut Vec<usize>,
+) -> Result<Vec<ChordBlock>, ErrorInfo> {
+    let reference = match reference_of(&sections[index]) {
+        Some(reference) => reference,
+        None => return Ok(sections[index].chord_blocks.clone()),
+    };
+
+    if visiting.contains(&index) {
+        return Err(ErrorInfo {
+            code: ErrorCode::Smiv5,
+            additional_info: Some(reference.name.clone()),
+            cause: None,
+        });
+    }
+
+    let target = sections
+        .iter()
+        .position(|section| {
+            section
+                .meta_infos
+                .contains(&SectionMeta::Section(reference.name.clone()))
+        })
+        .ok_or_else(|| ErrorInfo {
+            code: ErrorCode::Smiv4,
+            additional_info: Some(reference.name.clone()),
+            cause: None,
+        })?;
+
+    visiting.push(index);
+    let chord_blocks = resolve(sections, target, visiting)?;
+    visiting.pop();
+
+    if reference.transpose == 0 {
+        return Ok(chord_blocks);
+    }
+
+    let ast = vec![Section {
+        meta_infos: vec![],
+        chord_blocks,
+    }];
+    let spelling = transposed_spelling(&ast, reference.transpose);
+    Ok(transpose(&ast, reference.transpose, spelling)
+        .remove(0)
+        .chord_blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error_code::ErrorCode;
+    use crate::parse_chord_progression_string;
+    use crate::parser::types::section_meta::SectionMeta;
+    use crate::parser::types::section_reference::SectionReference;
+    use crate::util::position::Position;
+
+    #[test]
+    fn chord_blocks_are_cloned() {
+        let ast = parse_chord_progression_string(
+            "@section=Verse\nC - F\n\n@section=Verse2\n@repeat=2\n@use=Verse\n\n@section=Chorus\nG",
+        )
+        .unwrap();
+
+        assert_eq!(ast.len(), 3);
+        assert_eq!(ast[1].chord_blocks, ast[0].chord_blocks);
+        assert_eq!(
+            ast[1].meta_infos,
+            vec![
+                SectionMeta::Section("Verse2".to_string()),
+                SectionMeta::Repeat(2),
+                SectionMeta::Use(SectionReference {
+                    name: "Verse".to_string(),
+                    transpose: 0,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn transposed_and_forward_reference() {
+        let ast = parse_chord_progression_string(
+            "@use=Verse:-2\n\n@use=Verse:+3\n\n@section=Verse\n[key=C]C - G/B",
+        )
+        .unwrap();
+
+        assert_eq!(
+            ast[0].chord_blocks,
+            parse_chord_progression_string("[key=Bb]Bb - F/A").unwrap()[0].chord_blocks
+        );
+        assert_eq!(
+            ast[1].chord_blocks,
+            parse_chord_progression_string("[key=Eb]Eb - Bb/D").unwrap()[0].chord_blocks
+        );
+    }
+
+    #[test]
+    fn invalid_references() {
+        let code = |input: &str| {
+            parse_chord_progression_string(input)
+                .unwrap_err()
+                .error
+                .code
+        };
+
+        assert_eq!(code("@section=A\nC\n\n@use=B"), ErrorCode::Smiv4);
+        assert_eq!(code("@section=A\n@use=A"), ErrorCode::Smiv5);
+        assert_eq!(
+            code("@section=A\n@use=B\n\n@section=B\n@use=A"),
+            ErrorCode::Smiv5
+        );
+        assert_eq!(code("@section=A\nC\n\n@use=A\nF"), ErrorCode::Smiv7);
+        assert_eq!(code("@section=A\nC\n\n@use=A:x"), ErrorCode::Smiv6);
+    }
+
+    #[test]
+    fn error_position_is_the_value() {
+        let error_info =
+            parse_chord_progression_string("@section=A\nC\n\n@use=Bridge").unwrap_err();
+
+        assert_eq!(error_info.error.additional_info, Some("Bridge".to_string()));
+        assert_eq!(
+            error_info.position,
+            Position {
+                line_number: 4,
+                column_number: 6,
+                length: 6,
+            }
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use std::str::FromStr;
+
+use crate::error_code::{ErrorCode, ErrorInfo, ErrorInfoWithPosition};
+use crate::tokenizer::types::token::Token;
+use crate::tokenizer::types::token_ref::{TokenRef, TokenRefWithPosition};
+use crate::tokenizer::types::token_with_position::TokenWithPosition;
+use crate::util::position::Position;
+
+use super::types::key::Key;
+
+fn error_at(
+    code: ErrorCode,
+    additional_info: Option<String>,
+    position: &Position,
+) -> ErrorInfoWithPosition {
+    ErrorInfoWithPosition {
+        error: ErrorInfo {
+            code,
+            additional_info,
+            cause: None,
+        },
+        position: position.clone(),
+    }
+}
+
+/// Validator of the placement of the meta infos, fed with the tokens one by one.
+/// See [`validate_meta_info_placement`].
+#[derive(Debug, Default)]
+pub(crate) struct MetaInfoPlacement<'a> {
+    /// key of the first meta info waiting for the chord
+    waiting: Option<TokenRefWithPosition<'a>>,
+    /// the last token started a meta info, so the next one is its key
+    is_key_next: bool,
+    is_previous_line_break: bool,
+    /// the section ended while the meta info was waiting
+    is_ended: bool,
+}
+
+impl<'a> MetaInfoPlacement<'a> {
+    pub(crate) fn push(&mut self, token_with_position: &TokenRefWithPosition<'a>) {
+        if self.is_ended {
+            return;
+        }
+        if std::mem::take(&mut self.is_key_next) {
+            self.waiting = Some(token_with_position.clone());
+        }
+
+        let is_line_break = token_with_position.token == TokenRef::LineBreak;
+        match &token_with_position.token {
+            TokenRef::MetaInfoStart if self.waiting.is_none() => self.is_key_next = true,
+            TokenRef::Chord(_) => self.waiting = None,
+            // a blank line or a section meta info ends the section
+            TokenRef::LineBreak if self.is_previous_line_break && self.waiting.is_some() => {
+                self.is_ended = true
+            }
+            TokenRef::SectionMetaInfoStart if self.waiting.is_some() => self.is_ended = true,
+            _ => {}
+        }
+        self.is_previous_line_break = is_line_break;
+    }
+
+    pub(crate) fn finish(self) -> Result<(), ErrorInfoWithPosition> {
+        match self.waiting {
+            Some(key) => Err(error_at(
+                ErrorCode::Cimk7,
+                match key.token {
+                    TokenRef::MetaInfoKey(key) => Some(key.into_owned()),
+                    _ => None,
+                },
+                &key.position,
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Meta infos (like `[key=D]`) belong to the next chord, so they should be followed by a chord in the same section.
+/// Otherwise they are dropped at the end of the input, or moved to the chord of the next section.
+pub fn validate_meta_info_placement(
+    tokens: &[TokenWithPosition],
+) -> Result<(), ErrorInfoWithPosition> {
+    let mut placement = MetaInfoPlacement::default();
+    for token_with_position in tokens {
+        placement.push(&token_with_position.into());
+    }
+
+    placement.finish()
+}
+
+/// Warnings of the key changes which have no effect (`SEM-1`) or are reverted at the next chord (`SEM-2`).
+/// The keys are compared as written, so `C#` and `Db` are different keys.
+pub fn key_change_warnings(tokens: &[TokenWithPosition]) -> Vec<ErrorInfoWithPosition> {
+    let mut warnings: Vec<ErrorInfoWithPosition> = vec![];
+    let mut current_key: Option<Key> = None;
+    // the last key written before the next chord, and the position of the value
+    let mut waiting: Option<(Key, &Position)> = None;
+    // key change at the previous chord: the key before it, the key after it, and the position
+    let mut previous_change: Option<(Option<Key>, Key, &Position)> = None;
+
+    for (index, token_with_position) in tokens.iter().enumerate() {
+        match &token_with_position.token {
+            Token::MetaInfoKey(key) if key == "key" => {
+                if let Some(TokenWithPosition {
+                    token: Token::MetaInfoValue(value),
+                    position,
+                }) = tokens.get(index + 2)
+                {
+                    if let Ok(key) = Key::from_str(value) {
+                        waiting = Some((key, position));
+                    }
+                }
+            }
+            Token::Chord(_) => {
+                let change = match waiting.take() {
+                    Some((key, position)) if current_key.as_ref() == Some(&key) => {
+                        warnings.push(error_at(ErrorCode::Sem1, Some(key.to_string()), position));
+                        None
+                    }
+                    Some((key, position)) => {
+                        if let Some((Some(before), changed, changed_position)) = &previous_change {
+                            if *before == key {
+                                warnings.push(error_at(
+                                    ErrorCode::Sem2,
+                                    Some(format!("{} -> {} -> {}", before, changed, key)),
+                                    changed_position,
+                                ));
+                            }
+                        }
+                        let before = current_key.replace(key.clone());
+                        Some((before, key, position))
+                    }
+                    None => None,
+                };
+                previous_change = change;
+            }
+            _ => {}
+        }
+    }
+
+    warnings
+}
+
+/// Warnings of the commas not followed by a chord in the bar (`CHB-4`), like `C/D,` at the end of the input.
+/// They are ignored by the parser.
+pub fn trailing_comma_warnings(tokens: &[TokenWithPosition]) -> Vec<ErrorInfoWithPosition> {
+    tokens
+        .iter()
+        .enumerate()
+        .filter(|(index, token_with_position)| {
+            token_with_position.token == Token::Comma
+                && !matches!(
+                    tokens.get(index + 1).map(|next| &next.token),
+                    Some(Token::Chord(_) | Token::MetaInfoStart)
+                )
+        })
+        .map(|(_, token_with_position)| {
+            error_at(ErrorCode::Chb4, None, &token_with_position.position)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostic::Severity;
+    use crate::error_code::ErrorCode;
+    use crate::parse_chord_progression_string;
+    use crate::parser::options::ParseOptions;
+    use crate::parser::parse_with_warnings;
+    use crate::tokenizer::tokenize;
+    use crate::util::position::Position;
+
+    fn warnings(input: &str) -> Vec<(ErrorCode, Option<String>, Position)> {
+        let tokens = tokenize(input).unwrap();
+        let (_, warnings) = parse_with_warnings(&tokens, &ParseOptions::default()).unwrap();
+        warnings
+            .into_iter()
+            .map(|warning| {
+                assert_eq!(warning.severity, Severity::Warning);
+                (
+                    warning.error.code,
+                    warning.error.additional_info,
+                    warning.position,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn meta_info_without_chord() {
+        for (input, line_number, column_number) in [
+            ("C - [key=D]", 1, 6),
+            ("C - [tempo=90]\n\n@section=B\nG", 1, 6),
+            ("@section=A\n[fermata]\n@section=B\nG", 2, 2),
+        ] {
+            let error_info = parse_chord_progression_string(input).unwrap_err();
+            assert_eq!(error_info.error.code, ErrorCode::Cimk7, "{}", input);
+            assert_eq!(
+                (
+                    error_info.position.line_number,
+                    error_info.position.column_number
+                ),
+                (line_number, column_number),
+                "{}",
+                input
+            );
+        }
+
+        // the meta info at the end of the line is for the first chord of the next line
+        assert!(parse_chord_progression_string("C - [key=D]\nD").is_ok());
+    }
+
+    #[test]
+    fn key_change_without_effect() {
+        assert_eq!(
+            warnings("[key=C]C - F - [key=C]G"),
+            vec![(
+                ErrorCode::Sem1,
+                Some("C".to_string()),
+                Position {
+                    line_number: 1,
+                    column_number: 21,
+                    length: 1,
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn key_change_reverted_at_next_chord() {
+        assert_eq!(
+            warnings("[key=C]C - [key=D]A - [key=C]G"),
+            vec![(
+                ErrorCode::Sem2,
+                Some("C -> D -> C".to_string()),
+                Position {
+                    line_number: 1,
+                    column_number: 17,
+                    length: 1,
+                }
+            )]
+        );
+
+        // a key change lasting more than one chord is not reverted immediately
+        assert_eq!(warnings("[key=C]C - [key=D]A - D - [key=C]G"), vec![]);
+    }
+
+    #[test]
+    fn trailing_comma() {
+        assert_eq!(
+            warnings("G, <2.> F - C/D,"),
+            vec![
+                (
+                    ErrorCode::Chb4,
+                    None,
+                    Position {
+                        line_number: 1,
+                        column_number: 2,
+                        length: 1,
+                    }
+                ),
+                (
+                    ErrorCode::Chb4,
+                    None,
+                    Position {
+                        line_number: 1,
+                        column_number: 16,
+                        length: 1,
+                    }
+                )
+            ]
+        );
+        assert_eq!(warnings("C,[key=D]D,E"), vec![]);
+    }
+}
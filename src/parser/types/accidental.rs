@@ -8,4 +8,120 @@ pub enum Accidental {
     Sharp,
     #[serde(rename = "b")]
     Flat,
+    #[serde(rename = "##")]
+    DoubleSharp,
+    #[serde(rename = "bb")]
+    DoubleFlat,
+    #[serde(rename = "♮")]
+    Natural,
+}
+
+/// Symbols used to print accidentals
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum AccidentalNotation {
+    /// `#`, `b`, `##`, `bb` (natural is omitted)
+    #[default]
+    Ascii,
+    /// `♯`, `♭`, `𝄪`, `𝄫`, `♮`
+    Unicode,
+}
+
+/// accepted spellings, longest first so that "bb" is not read as "b"
+const ACCIDENTAL_SPELLINGS: [(&str, Accidental); 11] = [
+    ("♯♯", Accidental::DoubleSharp),
+    ("♭♭", Accidental::DoubleFlat),
+    ("##", Accidental::DoubleSharp),
+    ("bb", Accidental::DoubleFlat),
+    ("𝄪", Accidental::DoubleSharp),
+    ("𝄫", Accidental::DoubleFlat),
+    ("#", Accidental::Sharp),
+    ("♯", Accidental::Sharp),
+    ("b", Accidental::Flat),
+    ("♭", Accidental::Flat),
+    ("♮", Accidental::Natural),
+];
+
+impl Accidental {
+    /// semitones to add to the natural note
+    pub fn semitones(&self) -> i8 {
+        match self {
+            Accidental::Sharp => 1,
+            Accidental::Flat => -1,
+            Accidental::DoubleSharp => 2,
+            Accidental::DoubleFlat => -2,
+            Accidental::Natural => 0,
+        }
+    }
+
+    /// split the accidental at the start of the string, e.g. "♭m7" -> (Some(Flat), "m7")
+    pub fn split_prefix(s: &str) -> (Option<Accidental>, &str) {
+        ACCIDENTAL_SPELLINGS
+            .iter()
+            .find_map(|(spelling, accidental)| {
+                s.strip_prefix(spelling)
+                    .map(|rest| (Some(accidental.clone()), rest))
+            })
+            .unwrap_or((None, s))
+    }
+
+    pub fn to_string_with(&self, notation: AccidentalNotation) -> &'static str {
+        match (notation, self) {
+            (AccidentalNotation::Ascii, Accidental::Sharp) => "#",
+            (AccidentalNotation::Ascii, Accidental::Flat) => "b",
+            (AccidentalNotation::Ascii, Accidental::DoubleSharp) => "##",
+            (AccidentalNotation::Ascii, Accidental::DoubleFlat) => "bb",
+            (AccidentalNotation::Ascii, Accidental::Natural) => "",
+            (AccidentalNotation::Unicode, Accidental::Sharp) => "♯",
+            (AccidentalNotation::Unicode, Accidental::Flat) => "♭",
+            (AccidentalNotation::Unicode, Accidental::DoubleSharp) => "𝄪",
+            (AccidentalNotation::Unicode, Accidental::DoubleFlat) => "𝄫",
+            (AccidentalNotation::Unicode, Accidental::Natural) => "♮",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_ascii_and_unicode() {
+        assert_eq!(
+            Accidental::split_prefix("#m"),
+            (Some(Accidental::Sharp), "m")
+        );
+        assert_eq!(
+            Accidental::split_prefix("♭7"),
+            (Some(Accidental::Flat), "7")
+        );
+        assert_eq!(
+            Accidental::split_prefix("bb"),
+            (Some(Accidental::DoubleFlat), "")
+        );
+        assert_eq!(
+            Accidental::split_prefix("𝄪"),
+            (Some(Accidental::DoubleSharp), "")
+        );
+        assert_eq!(
+            Accidental::split_prefix("♮"),
+            (Some(Accidental::Natural), "")
+        );
+        assert_eq!(Accidental::split_prefix("m7"), (None, "m7"));
+    }
+
+    #[test]
+    fn render_with_notation() {
+        assert_eq!(
+            Accidental::DoubleFlat.to_string_with(AccidentalNotation::Ascii),
+            "bb"
+        );
+        assert_eq!(
+            Accidental::DoubleFlat.to_string_with(AccidentalNotation::Unicode),
+            "𝄫"
+        );
+        assert_eq!(
+            Accidental::Natural.to_string_with(AccidentalNotation::Ascii),
+            ""
+        );
+    }
 }
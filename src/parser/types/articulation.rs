@@ -0,0 +1,36 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+use typeshare::typeshare;
+
+use crate::error_code::{ErrorCode, ErrorInfo};
+
+/// Articulation of the chord (a band hit), written like `[articulation=accent]`
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Copy, Display, EnumIter, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Articulation {
+    Accent,
+    Staccato,
+    Tenuto,
+    Marcato,
+    /// short and accented hit
+    Stab,
+}
+
+impl FromStr for Articulation {
+    type Err = ErrorInfo;
+
+    fn from_str(s: &str) -> Result<Self, ErrorInfo> {
+        Articulation::iter()
+            .find(|articulation| articulation.to_string() == s)
+            .ok_or_else(|| ErrorInfo {
+                code: ErrorCode::Cimv8,
+                additional_info: Some(s.to_string()),
+                cause: None,
+            })
+    }
+}
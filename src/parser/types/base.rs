@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
 use typeshare::typeshare;
 
 #[typeshare]
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, EnumIter)]
 pub enum Base {
     A,
     B,
@@ -12,3 +13,33 @@ pub enum Base {
     F,
     G,
 }
+
+impl Base {
+    /// pitch class of the natural note (C = 0, ..., B = 11)
+    pub fn pitch_class(&self) -> u8 {
+        match self {
+            Base::C => 0,
+            Base::D => 2,
+            Base::E => 4,
+            Base::F => 5,
+            Base::G => 7,
+            Base::A => 9,
+            Base::B => 11,
+        }
+    }
+
+    /// the note name `steps` letters above, e.g. `C.up(2)` is E and `A.up(2)` is C
+    pub fn up(&self, steps: usize) -> Base {
+        const BASES: [Base; 7] = [
+            Base::C,
+            Base::D,
+            Base::E,
+            Base::F,
+            Base::G,
+            Base::A,
+            Base::B,
+        ];
+        let index = BASES.iter().position(|base| base == self).unwrap();
+        BASES[(index + steps) % 7].clone()
+    }
+}
@@ -1,4 +1,5 @@
 use super::bar::Bar;
+use super::navigation_mark::NavigationMark;
 use serde::{Deserialize, Serialize};
 use typeshare::typeshare;
 
@@ -8,4 +9,5 @@ use typeshare::typeshare;
 pub enum ChordBlock {
     Bar(Bar),
     Br, // break of line
+    Navigation(NavigationMark),
 }
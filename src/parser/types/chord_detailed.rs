@@ -1,11 +1,11 @@
 use std::str::FromStr;
 
 use crate::error_code::{ErrorCode, ErrorInfo};
+use crate::util::note::{accidental_to_spell, note_to_string, pitch_class, split_note};
 use typeshare::typeshare;
 
 use super::{accidental::Accidental, base::Base, chord_type::ChordType, extension::Extension};
 use serde::{Deserialize, Serialize};
-use strum::VariantNames;
 
 #[typeshare]
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -17,85 +17,131 @@ pub struct ChordDetailed {
     pub extensions: Vec<Extension>,
 }
 
-fn try_remove_prefix(input: &str, prefix: &str) -> String {
-    match input.strip_prefix(prefix) {
-        Some(stripped) => String::from(stripped),
-        None => String::from(input),
+/// spellings of the major seventh family, like "maj7" and "Δ9" (= "M7", "M9")
+const MAJOR_ALIASES: [&str; 3] = ["maj", "Maj", "Δ"];
+
+/// Split the chord quality written before the extensions,
+/// like "m", "min", "-", "aug", "+", "dim", "°", "ø", "maj", "Δ" and "5" (power chord).
+/// Some qualities imply extensions (e.g. "Δ" is "M7", "ø" is "m(o)").
+fn split_chord_type(s: &str) -> (ChordType, Vec<Extension>, &str) {
+    let starts_with_digit = |rest: &str| rest.starts_with(|c: char| c.is_ascii_digit());
+
+    // "5" alone is the power chord, while "(5)" is the fifth added to the triad
+    if s == "5" {
+        return (ChordType::Power, vec![], "");
+    }
+
+    // "maj7", "Δ9" and "M7" are extensions, so they are left to parse_inline_extensions
+    if let Some(rest) = MAJOR_ALIASES
+        .iter()
+        .chain(["M"].iter())
+        .find_map(|alias| s.strip_prefix(alias))
+    {
+        if starts_with_digit(rest) {
+            return (ChordType::Major, vec![], s);
+        }
+        if s.starts_with('Δ') {
+            return (ChordType::Major, vec![Extension::MajorSeven], rest);
+        }
+        return (ChordType::Major, vec![], rest);
+    }
+
+    if let Some(rest) = s.strip_prefix('ø') {
+        // "ø7" is the same as "ø"
+        let rest = rest.strip_prefix('7').unwrap_or(rest);
+        return (ChordType::Minor, vec![Extension::HalfDiminish], rest);
     }
+
+    let chord_types = [
+        ("min", ChordType::Minor),
+        ("mi", ChordType::Minor),
+        ("m", ChordType::Minor),
+        ("-", ChordType::Minor),
+        ("aug", ChordType::Augmented),
+        ("+", ChordType::Augmented),
+        ("dim", ChordType::Diminished),
+        ("°", ChordType::Diminished),
+        ("o", ChordType::Diminished),
+    ];
+
+    for (prefix, chord_type) in chord_types {
+        if let Some(rest) = s.strip_prefix(prefix) {
+            return (chord_type, vec![], rest);
+        }
+    }
+
+    (ChordType::Major, vec![], s)
 }
 
-impl ChordDetailed {
-    pub fn from_str(s: &str) -> Result<Self, ErrorInfo> {
-        let base = match s.chars().next() {
-            Some('A') => Base::A,
-            Some('B') => Base::B,
-            Some('C') => Base::C,
-            Some('D') => Base::D,
-            Some('E') => Base::E,
-            Some('F') => Base::F,
-            Some('G') => Base::G,
-            _ => {
+/// Parse extensions written without parenthesis, like "7b9" of "C7b9" or "maj7" of "Cmaj7"
+fn parse_inline_extensions(s: &str) -> Result<Vec<Extension>, ErrorInfo> {
+    let spellings = Extension::spellings();
+
+    let mut extensions: Vec<Extension> = vec![];
+    let mut rest = s;
+
+    while !rest.is_empty() {
+        match spellings
+            .iter()
+            .find(|(spelling, _)| rest.starts_with(spelling))
+        {
+            Some((spelling, extension)) => {
+                extensions.push(extension.clone());
+                rest = &rest[spelling.len()..];
+            }
+            None => {
                 return Err(ErrorInfo {
-                    code: ErrorCode::Bs1,
-                    additional_info: None,
+                    code: ErrorCode::Ext1,
+                    additional_info: Some(rest.to_string()),
+                    cause: None,
                 })
             }
-        };
+        }
+    }
 
-        let mut idx = 1; // Start after the base note
+    Ok(extensions)
+}
 
-        let accidental = match s.chars().nth(idx) {
-            Some('#') => {
-                idx += 1;
-                Some(Accidental::Sharp)
-            }
-            Some('b') => {
-                idx += 1;
-                Some(Accidental::Flat)
+impl FromStr for ChordDetailed {
+    type Err = ErrorInfo;
+
+    fn from_str(s: &str) -> Result<Self, ErrorInfo> {
+        let (base, accidental, chord_str_without_base) = match split_note(s) {
+            Some(note) => note,
+            None => {
+                return Err(ErrorInfo {
+                    code: ErrorCode::Bs1,
+                    additional_info: None,
+                    cause: None,
+                })
             }
-            // No accidental
-            _ => None,
         };
 
-        let chord_str_without_base = &s[idx..];
+        // extensions can be written inline (like "7" of "C7") and in parenthesis (like "(9)" of "C7(9)")
+        let (chord_str_without_parenthesis, extensions_str_with_parenthesis) =
+            match chord_str_without_base.find('(') {
+                Some(parenthesis_idx) => chord_str_without_base.split_at(parenthesis_idx),
+                None => (chord_str_without_base, ""),
+            };
 
-        let chord_type = if chord_str_without_base.starts_with('m') {
-            ChordType::Minor
-        } else if chord_str_without_base.starts_with('M') {
-            ChordType::Major
-        } else if chord_str_without_base.starts_with("aug") {
-            ChordType::Augmented
-        } else if chord_str_without_base.starts_with("dim") {
-            ChordType::Diminished
-        } else {
-            ChordType::Major
-        };
+        let (chord_type, mut extensions, inline_extensions_str) =
+            split_chord_type(chord_str_without_parenthesis);
+        extensions.extend(parse_inline_extensions(inline_extensions_str)?);
 
-        let extensions_str_with_parenthesis = try_remove_prefix(
-            chord_str_without_base,
-            match chord_type {
-                ChordType::Minor => "m",
-                ChordType::Major => "M",
-                ChordType::Augmented => "aug",
-                ChordType::Diminished => "dim",
-            },
-        );
-
-        if extensions_str_with_parenthesis.eq("") {
+        if extensions_str_with_parenthesis.is_empty() {
             return Ok(ChordDetailed {
                 base,
                 accidental,
                 chord_type,
-                extensions: vec![],
+                extensions,
             });
         }
 
-        if !extensions_str_with_parenthesis.starts_with('(')
-            || !extensions_str_with_parenthesis.ends_with(')')
-        {
+        if !extensions_str_with_parenthesis.ends_with(')') {
             return Err(ErrorInfo {
                 code: ErrorCode::Ext3,
                 additional_info: Some(extensions_str_with_parenthesis.to_string()),
+                cause: None,
             });
         }
 
@@ -103,34 +149,33 @@ impl ChordDetailed {
         let extensions_str =
             &extensions_str_with_parenthesis[1..extensions_str_with_parenthesis.len() - 1];
 
-        if extensions_str.eq("") {
+        if extensions_str.is_empty() {
             return Ok(ChordDetailed {
                 base,
                 accidental,
                 chord_type,
-                extensions: vec![],
+                extensions,
             });
         }
 
-        let mut sorted_extensions = (*Extension::VARIANTS.clone()).to_vec();
-        sorted_extensions.sort_by_key(|b| std::cmp::Reverse(b.len()));
+        let spellings = Extension::spellings();
 
         let extensions_str_vec: Vec<&str> = extensions_str.split(',').collect();
 
-        let mut parsed_extensions: Vec<Extension> = vec![];
         // loop extensions_str_vec
         for extension_str in extensions_str_vec.iter() {
-            let extension_str_result = sorted_extensions
+            let extension_str_result = spellings
                 .iter()
-                .find(|e| extension_str.starts_with(**e));
+                .find(|(spelling, _)| extension_str.starts_with(spelling));
             match extension_str_result {
-                Some(extension_str_result) => {
-                    parsed_extensions.push(Extension::from_str(extension_str_result).unwrap());
+                Some((_, extension)) => {
+                    extensions.push(extension.clone());
                 }
                 None => {
                     return Err(ErrorInfo {
                         code: ErrorCode::Ext1,
                         additional_info: Some(extension_str.to_string()),
+                        cause: None,
                     })
                 }
             }
@@ -140,11 +185,134 @@ impl ChordDetailed {
             base,
             accidental,
             chord_type,
-            extensions: parsed_extensions,
+            extensions,
         })
     }
 }
 
+impl ChordDetailed {
+    /// Chord symbol in the canonical form (like "Cm(7,b5)"),
+    /// with extensions printed in the spellings given in `preferred` (like `&["-5"]` for "Cm(7,-5)")
+    pub fn to_string_with_aliases(&self, preferred: &[&str]) -> String {
+        let chord_type = match self.chord_type {
+            ChordType::Major => "",
+            ChordType::Minor => "m",
+            ChordType::Augmented => "aug",
+            ChordType::Diminished => "dim",
+            ChordType::Power => "5",
+        };
+        let extensions = if self.extensions.is_empty() {
+            "".to_string()
+        } else {
+            format!(
+                "({})",
+                self.extensions
+                    .iter()
+                    .map(|extension| extension.display_name(preferred))
+                    .collect::<Vec<&str>>()
+                    .join(",")
+            )
+        };
+
+        [
+            note_to_string(&self.base, &self.accidental),
+            chord_type.to_string(),
+            extensions,
+        ]
+        .concat()
+    }
+
+    /// pitch class of the root (C = 0, ..., B = 11)
+    pub fn root_pitch_class(&self) -> u8 {
+        pitch_class(&self.base, &self.accidental)
+    }
+
+    /// semitones from the root of every chord tone, sorted in ascending order
+    pub fn intervals(&self) -> Vec<u8> {
+        let (mut third, mut fifth) = match self.chord_type {
+            ChordType::Major => (Some(4), Some(7)),
+            ChordType::Minor => (Some(3), Some(7)),
+            ChordType::Augmented => (Some(4), Some(8)),
+            ChordType::Diminished => (Some(3), Some(6)),
+            ChordType::Power => (None, Some(7)),
+        };
+        let mut others: Vec<u8> = vec![];
+
+        for extension in self.extensions.iter() {
+            let definition = extension.definition();
+            if definition.replaces_third {
+                third = None;
+            }
+            if definition.replaces_fifth {
+                fifth = None;
+            }
+
+            match (extension, &self.chord_type) {
+                // the seventh of diminished chord is diminished seventh
+                (Extension::Seven, ChordType::Diminished) => others.push(9),
+                _ => others.extend(definition.intervals),
+            }
+        }
+
+        let mut intervals: Vec<u8> = [Some(0), third, fifth]
+            .into_iter()
+            .flatten()
+            .chain(others)
+            .collect();
+        intervals.sort();
+        intervals.dedup();
+        intervals
+    }
+
+    /// every chord tone spelled with the letter of its degree, in the order of `intervals()`
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use chord_progression_parser::parser::types::chord_detailed::ChordDetailed;
+    /// use chord_progression_parser::util::note::note_to_string;
+    ///
+    /// let notes: Vec<String> = ChordDetailed::from_str("Ebm(7,b5)")
+    ///     .unwrap()
+    ///     .notes()
+    ///     .iter()
+    ///     .map(|(base, accidental)| note_to_string(base, accidental))
+    ///     .collect();
+    /// assert_eq!(notes, ["Eb", "Gb", "Bbb", "Db"]);
+    /// ```
+    pub fn notes(&self) -> Vec<(Base, Option<Accidental>)> {
+        let root = self.root_pitch_class();
+
+        self.intervals()
+            .iter()
+            .map(|interval| {
+                let base = self.base.up(self.degree_of(*interval));
+                let accidental = accidental_to_spell(&base, (root + interval) % 12);
+                (base, accidental)
+            })
+            .collect()
+    }
+
+    /// degree (0 for the root, 1 for the second, ...) which the interval is spelled as
+    fn degree_of(&self, interval: u8) -> usize {
+        let has = |extension: Extension| self.extensions.contains(&extension);
+
+        match interval {
+            0 => 0,
+            1 | 2 | 13 | 14 | 15 => 1,
+            3 | 4 => 2,
+            5 | 16 | 17 | 18 => 3,
+            6 if has(Extension::SharpFour) => 3,
+            6 | 7 => 4,
+            8 if has(Extension::FlatSix) => 5,
+            8 => 4,
+            9 if self.chord_type == ChordType::Diminished && has(Extension::Seven) => 6,
+            9 | 20 | 21 | 22 => 5,
+            _ => 6,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -199,5 +367,257 @@ mod tests {
                 });
             }
         }
+
+        mod aliases {
+            use std::str::FromStr;
+
+            use crate::parser::types::{
+                chord_detailed::ChordDetailed, chord_type::ChordType, extension::Extension,
+            };
+
+            fn type_and_extensions(s: &str) -> (ChordType, Vec<Extension>) {
+                let detailed = ChordDetailed::from_str(s).unwrap();
+                (detailed.chord_type, detailed.extensions)
+            }
+
+            #[test]
+            fn major_seventh() {
+                for s in ["Cmaj7", "CM7", "CΔ", "CΔ7", "C(M7)"] {
+                    assert_eq!(
+                        type_and_extensions(s),
+                        (ChordType::Major, vec![Extension::MajorSeven]),
+                        "{}",
+                        s
+                    );
+                }
+                assert_eq!(
+                    type_and_extensions("Cmaj9"),
+                    (ChordType::Major, vec![Extension::MajorNine])
+                );
+                assert_eq!(type_and_extensions("Cmaj"), (ChordType::Major, vec![]));
+            }
+
+            #[test]
+            fn minor() {
+                for s in ["Cm7", "Cmin7", "Cmi7", "C-7", "Cm(7)"] {
+                    assert_eq!(
+                        type_and_extensions(s),
+                        (ChordType::Minor, vec![Extension::Seven]),
+                        "{}",
+                        s
+                    );
+                }
+                assert_eq!(
+                    type_and_extensions("CmM7"),
+                    (ChordType::Minor, vec![Extension::MajorSeven])
+                );
+                assert_eq!(
+                    type_and_extensions("Cmmaj7"),
+                    (ChordType::Minor, vec![Extension::MajorSeven])
+                );
+            }
+
+            #[test]
+            fn diminished_and_augmented() {
+                assert_eq!(type_and_extensions("C°"), (ChordType::Diminished, vec![]));
+                assert_eq!(
+                    type_and_extensions("Co7"),
+                    (ChordType::Diminished, vec![Extension::Seven])
+                );
+                assert_eq!(type_and_extensions("C+"), (ChordType::Augmented, vec![]));
+                assert_eq!(
+                    type_and_extensions("C+7"),
+                    (ChordType::Augmented, vec![Extension::Seven])
+                );
+                for s in ["Cø", "Cø7", "Cm(o)"] {
+                    assert_eq!(
+                        type_and_extensions(s),
+                        (ChordType::Minor, vec![Extension::HalfDiminish]),
+                        "{}",
+                        s
+                    );
+                }
+            }
+
+            #[test]
+            fn inline_extensions() {
+                assert_eq!(
+                    type_and_extensions("C7b9#11"),
+                    (
+                        ChordType::Major,
+                        vec![
+                            Extension::Seven,
+                            Extension::FlatNine,
+                            Extension::SharpEleven
+                        ]
+                    )
+                );
+                assert_eq!(
+                    type_and_extensions("Cm7b5"),
+                    (
+                        ChordType::Minor,
+                        vec![Extension::Seven, Extension::FlatFive]
+                    )
+                );
+                assert_eq!(
+                    type_and_extensions("C7sus4(9)"),
+                    (
+                        ChordType::Major,
+                        vec![Extension::Seven, Extension::Sus4, Extension::Nine]
+                    )
+                );
+            }
+
+            #[test]
+            fn extension_aliases_in_parenthesis() {
+                assert_eq!(
+                    type_and_extensions("C(-5,+5,maj9)"),
+                    (
+                        ChordType::Major,
+                        vec![
+                            Extension::FlatFive,
+                            Extension::SharpFive,
+                            Extension::MajorNine
+                        ]
+                    )
+                );
+                assert_eq!(
+                    type_and_extensions("C7(no3,omit5)"),
+                    (
+                        ChordType::Major,
+                        vec![Extension::Seven, Extension::Omit3, Extension::Omit5]
+                    )
+                );
+            }
+
+            #[test]
+            fn print_with_preferred_aliases() {
+                let detailed = ChordDetailed::from_str("Cm(7,-5)").unwrap();
+                assert_eq!(detailed.to_string_with_aliases(&[]), "Cm(7,b5)");
+                assert_eq!(detailed.to_string_with_aliases(&["-5"]), "Cm(7,-5)");
+                assert_eq!(
+                    ChordDetailed::from_str("Bbmaj7")
+                        .unwrap()
+                        .to_string_with_aliases(&["Δ7"]),
+                    "Bb(Δ7)"
+                );
+            }
+
+            #[test]
+            fn invalid_inline_extension() {
+                let error = ChordDetailed::from_str("C7x").unwrap_err();
+                assert_eq!(error.code, crate::error_code::ErrorCode::Ext1);
+                assert_eq!(error.additional_info, Some("x".to_string()));
+            }
+        }
+
+        mod intervals {
+            use std::str::FromStr;
+
+            use crate::parser::types::chord_detailed::ChordDetailed;
+            use crate::parser::types::chord_type::ChordType;
+            use crate::parser::types::extension::Extension;
+
+            #[test]
+            fn triads() {
+                assert_eq!(ChordDetailed::from_str("C").unwrap().intervals(), [0, 4, 7]);
+                assert_eq!(
+                    ChordDetailed::from_str("Cm").unwrap().intervals(),
+                    [0, 3, 7]
+                );
+                assert_eq!(
+                    ChordDetailed::from_str("Caug").unwrap().intervals(),
+                    [0, 4, 8]
+                );
+                assert_eq!(
+                    ChordDetailed::from_str("Cdim").unwrap().intervals(),
+                    [0, 3, 6]
+                );
+            }
+
+            #[test]
+            fn extensions_replace_third_and_fifth() {
+                assert_eq!(
+                    ChordDetailed::from_str("C(sus4,7)").unwrap().intervals(),
+                    [0, 5, 7, 10]
+                );
+                assert_eq!(
+                    ChordDetailed::from_str("Cm(b5,7)").unwrap().intervals(),
+                    [0, 3, 6, 10]
+                );
+                assert_eq!(
+                    ChordDetailed::from_str("Cdim(7)").unwrap().intervals(),
+                    [0, 3, 6, 9]
+                );
+                assert_eq!(
+                    ChordDetailed::from_str("C(7,omit3)").unwrap().intervals(),
+                    [0, 7, 10]
+                );
+                assert_eq!(
+                    ChordDetailed::from_str("C(no5)").unwrap().intervals(),
+                    [0, 4]
+                );
+            }
+
+            #[test]
+            fn power_altered_and_added_tones() {
+                let intervals = |s: &str| ChordDetailed::from_str(s).unwrap().intervals();
+
+                assert_eq!(intervals("C5"), [0, 7]);
+                assert_eq!(intervals("C(5)"), [0, 4, 7]);
+                assert_eq!(intervals("C(7,5)"), [0, 4, 7, 10]);
+                assert_eq!(
+                    ChordDetailed::from_str("C5").unwrap().chord_type,
+                    ChordType::Power
+                );
+                assert_eq!(
+                    ChordDetailed::from_str("C(5)").unwrap().extensions,
+                    [Extension::Five]
+                );
+                assert_eq!(intervals("C(6/9)"), [0, 4, 7, 9, 14]);
+                assert_eq!(intervals("C69"), [0, 4, 7, 9, 14]);
+                assert_eq!(intervals("C7alt"), [0, 4, 6, 8, 10, 13, 15]);
+                assert_eq!(intervals("C(add2)"), [0, 2, 4, 7]);
+                assert_eq!(intervals("C(add4)"), [0, 4, 5, 7]);
+                assert_eq!(intervals("C(#4)"), [0, 4, 6, 7]);
+                assert_eq!(intervals("C(b2)"), [0, 1, 4, 7]);
+                assert_eq!(intervals("C(sus)"), [0, 5, 7]);
+                assert_eq!(intervals("Csus"), [0, 5, 7]);
+            }
+
+            #[test]
+            fn root_pitch_class() {
+                assert_eq!(ChordDetailed::from_str("C").unwrap().root_pitch_class(), 0);
+                assert_eq!(
+                    ChordDetailed::from_str("F#m").unwrap().root_pitch_class(),
+                    6
+                );
+                assert_eq!(
+                    ChordDetailed::from_str("Cb").unwrap().root_pitch_class(),
+                    11
+                );
+            }
+
+            #[test]
+            fn unicode_and_double_accidentals() {
+                let pitch = |s: &str| ChordDetailed::from_str(s).unwrap().root_pitch_class();
+
+                assert_eq!(
+                    ChordDetailed::from_str("C♯m(7)").unwrap(),
+                    ChordDetailed::from_str("C#m(7)").unwrap()
+                );
+                assert_eq!(pitch("B♭"), 10);
+                assert_eq!(pitch("F𝄪"), 7);
+                assert_eq!(pitch("F##"), 7);
+                assert_eq!(pitch("Cbb"), 10);
+                assert_eq!(pitch("E♮m"), 4);
+                assert_eq!(
+                    ChordDetailed::from_str("A𝄫(7)")
+                        .unwrap()
+                        .to_string_with_aliases(&[]),
+                    "Abb(7)"
+                );
+            }
+        }
     }
 }
@@ -1,4 +1,5 @@
 use super::chord::Chord;
+use super::poly_chord::PolyChord;
 use serde::{Deserialize, Serialize};
 use typeshare::typeshare;
 
@@ -7,7 +8,8 @@ use typeshare::typeshare;
 #[serde(tag = "type", content = "value", rename_all = "camelCase")]
 pub enum ChordExpression {
     Chord(Chord),
-    UnIdentified, // ?
-    NoChord,      // -
-    Same,         // %
+    PolyChord(PolyChord), // {D|C7}
+    UnIdentified,         // ?
+    NoChord,              // -
+    Same,                 // %
 }
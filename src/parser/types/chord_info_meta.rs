@@ -1,4 +1,7 @@
+use super::articulation::Articulation;
+use super::dynamics::Dynamics;
 use super::key::Key;
+use super::time_signature::TimeSignature;
 use serde::{Deserialize, Serialize};
 use typeshare::typeshare;
 
@@ -7,5 +10,45 @@ use typeshare::typeshare;
 #[serde(tag = "type", content = "value", rename_all = "camelCase")]
 pub enum ChordInfoMeta {
     Key(Key),
-    // or more
+    /// carried forward to the following bars
+    Time(TimeSignature),
+    /// BPM, like `[tempo=120]`
+    Tempo(u32),
+    Ritardando,  // [rit]
+    Accelerando, // [accel]
+    Fermata,     // [fermata]
+    Dynamics(Dynamics),
+    Articulation(Articulation),
+    /// meta info of a key registered in the `MetaRegistry`, like `[capo=2]`
+    Custom {
+        key: String,
+        value: String,
+    },
+}
+
+impl ChordInfoMeta {
+    /// key written in `[key=value]` (or `[key]` of the flag)
+    pub fn key(&self) -> &str {
+        match self {
+            ChordInfoMeta::Key(_) => "key",
+            ChordInfoMeta::Time(_) => "time",
+            ChordInfoMeta::Tempo(_) => "tempo",
+            ChordInfoMeta::Ritardando => "rit",
+            ChordInfoMeta::Accelerando => "accel",
+            ChordInfoMeta::Fermata => "fermata",
+            ChordInfoMeta::Dynamics(_) => "dynamics",
+            ChordInfoMeta::Articulation(_) => "articulation",
+            ChordInfoMeta::Custom { key, .. } => key,
+        }
+    }
+
+    /// the meta info written without a value, like `[fermata]`
+    pub fn from_flag(key: &str) -> Option<ChordInfoMeta> {
+        match key {
+            "rit" => Some(ChordInfoMeta::Ritardando),
+            "accel" => Some(ChordInfoMeta::Accelerando),
+            "fermata" => Some(ChordInfoMeta::Fermata),
+            _ => None,
+        }
+    }
 }
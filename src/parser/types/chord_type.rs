@@ -12,4 +12,7 @@ pub enum ChordType {
     Augmented,
     #[serde(rename = "dim")]
     Diminished,
+    /// power chord like `C5`, of the root and the fifth only
+    #[serde(rename = "5")]
+    Power,
 }
@@ -0,0 +1,36 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+use typeshare::typeshare;
+
+use crate::error_code::{ErrorCode, ErrorInfo};
+
+/// Dynamics, written like `[dynamics=mf]`
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Copy, Display, EnumIter, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Dynamics {
+    Pp,
+    P,
+    Mp,
+    Mf,
+    F,
+    Ff,
+}
+
+impl FromStr for Dynamics {
+    type Err = ErrorInfo;
+
+    fn from_str(s: &str) -> Result<Self, ErrorInfo> {
+        Dynamics::iter()
+            .find(|dynamics| dynamics.to_string() == s)
+            .ok_or_else(|| ErrorInfo {
+                code: ErrorCode::Cimv7,
+                additional_info: Some(s.to_string()),
+                cause: None,
+            })
+    }
+}
@@ -1,12 +1,18 @@
+use std::str::FromStr;
+use std::sync::OnceLock;
+
 use serde::{Deserialize, Serialize};
-use strum_macros::{Display, EnumString, EnumVariantNames};
+use strum_macros::{Display, EnumIter, VariantNames};
 use typeshare::typeshare;
 
+use crate::error_code::{ErrorCode, ErrorInfo};
+
 #[typeshare]
-#[derive(
-    Debug, PartialEq, Clone, Display, EnumString, EnumVariantNames, Serialize, Deserialize,
-)]
+#[derive(Debug, PartialEq, Clone, Display, VariantNames, EnumIter, Serialize, Deserialize)]
 pub enum Extension {
+    #[strum(serialize = "b2")]
+    #[serde(rename = "b2")]
+    FlatTwo,
     #[strum(serialize = "2")]
     #[serde(rename = "2")]
     Two,
@@ -19,6 +25,9 @@ pub enum Extension {
     #[strum(serialize = "4")]
     #[serde(rename = "4")]
     Four,
+    #[strum(serialize = "#4")]
+    #[serde(rename = "#4")]
+    SharpFour,
     #[strum(serialize = "b5")]
     #[serde(rename = "b5")]
     FlatFive,
@@ -34,6 +43,9 @@ pub enum Extension {
     #[strum(serialize = "6")]
     #[serde(rename = "6")]
     Six,
+    #[strum(serialize = "6/9")]
+    #[serde(rename = "6/9")]
+    SixNine,
     #[strum(serialize = "7")]
     #[serde(rename = "7")]
     Seven,
@@ -76,6 +88,12 @@ pub enum Extension {
     #[strum(serialize = "M13")]
     #[serde(rename = "M13")]
     MajorThirteen,
+    #[strum(serialize = "add2")]
+    #[serde(rename = "add2")]
+    Add2,
+    #[strum(serialize = "add4")]
+    #[serde(rename = "add4")]
+    Add4,
     #[strum(serialize = "add9")]
     #[serde(rename = "add9")]
     Add9,
@@ -94,4 +112,439 @@ pub enum Extension {
     #[strum(serialize = "o")]
     #[serde(rename = "o")]
     HalfDiminish,
+    #[strum(serialize = "alt")]
+    #[serde(rename = "alt")]
+    Altered,
+    #[strum(serialize = "omit3")]
+    #[serde(rename = "omit3")]
+    Omit3,
+    #[strum(serialize = "omit5")]
+    #[serde(rename = "omit5")]
+    Omit5,
+}
+
+/// Spellings and chord tones of an extension
+#[derive(Debug, PartialEq)]
+pub struct ExtensionDefinition {
+    pub extension: Extension,
+    /// canonical spelling, used for serialization
+    pub name: &'static str,
+    /// other accepted spellings
+    pub aliases: &'static [&'static str],
+    /// semitones from the root added by the extension
+    pub intervals: &'static [u8],
+    /// the extension replaces (or omits) the third of the triad, like `sus4` and `omit3`
+    pub replaces_third: bool,
+    /// the extension replaces (or omits) the fifth of the triad, like `b5` and `omit5`
+    pub replaces_fifth: bool,
+}
+
+pub const EXTENSION_DEFINITIONS: [ExtensionDefinition; 37] = [
+    ExtensionDefinition {
+        extension: Extension::FlatTwo,
+        name: "b2",
+        aliases: &["-2"],
+        intervals: &[1],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Two,
+        name: "2",
+        aliases: &[],
+        intervals: &[2],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Three,
+        name: "3",
+        aliases: &[],
+        intervals: &[4],
+        replaces_third: true,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::FlatThree,
+        name: "b3",
+        aliases: &["-3"],
+        intervals: &[3],
+        replaces_third: true,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Four,
+        name: "4",
+        aliases: &[],
+        intervals: &[5],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::SharpFour,
+        name: "#4",
+        aliases: &["+4"],
+        intervals: &[6],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::FlatFive,
+        name: "b5",
+        aliases: &["-5"],
+        intervals: &[6],
+        replaces_third: false,
+        replaces_fifth: true,
+    },
+    ExtensionDefinition {
+        extension: Extension::Five,
+        name: "5",
+        aliases: &[],
+        intervals: &[7],
+        replaces_third: false,
+        replaces_fifth: true,
+    },
+    ExtensionDefinition {
+        extension: Extension::SharpFive,
+        name: "#5",
+        aliases: &["+5"],
+        intervals: &[8],
+        replaces_third: false,
+        replaces_fifth: true,
+    },
+    ExtensionDefinition {
+        extension: Extension::FlatSix,
+        name: "b6",
+        aliases: &["-6"],
+        intervals: &[8],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Six,
+        name: "6",
+        aliases: &[],
+        intervals: &[9],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::SixNine,
+        name: "6/9",
+        aliases: &["69"],
+        intervals: &[9, 14],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Seven,
+        name: "7",
+        aliases: &[],
+        intervals: &[10],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::FlatNine,
+        name: "b9",
+        aliases: &["-9"],
+        intervals: &[13],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Nine,
+        name: "9",
+        aliases: &[],
+        intervals: &[14],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::SharpNine,
+        name: "#9",
+        aliases: &["+9"],
+        intervals: &[15],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::FlatEleven,
+        name: "b11",
+        aliases: &["-11"],
+        intervals: &[16],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Eleven,
+        name: "11",
+        aliases: &[],
+        intervals: &[17],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::SharpEleven,
+        name: "#11",
+        aliases: &["+11"],
+        intervals: &[18],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::FlatThirteen,
+        name: "b13",
+        aliases: &["-13"],
+        intervals: &[20],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Thirteen,
+        name: "13",
+        aliases: &[],
+        intervals: &[21],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::SharpThirteen,
+        name: "#13",
+        aliases: &["+13"],
+        intervals: &[22],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::MajorSeven,
+        name: "M7",
+        aliases: &["maj7", "Maj7", "Δ7", "Δ"],
+        intervals: &[11],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::MajorNine,
+        name: "M9",
+        aliases: &["maj9", "Maj9", "Δ9"],
+        intervals: &[11, 14],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::MajorEleven,
+        name: "M11",
+        aliases: &["maj11", "Maj11", "Δ11"],
+        intervals: &[11, 14, 17],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::MajorThirteen,
+        name: "M13",
+        aliases: &["maj13", "Maj13", "Δ13"],
+        intervals: &[11, 14, 21],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Add2,
+        name: "add2",
+        aliases: &[],
+        intervals: &[2],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Add4,
+        name: "add4",
+        aliases: &[],
+        intervals: &[5],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Add9,
+        name: "add9",
+        aliases: &[],
+        intervals: &[14],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Add11,
+        name: "add11",
+        aliases: &[],
+        intervals: &[17],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Add13,
+        name: "add13",
+        aliases: &[],
+        intervals: &[21],
+        replaces_third: false,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Sus2,
+        name: "sus2",
+        aliases: &[],
+        intervals: &[2],
+        replaces_third: true,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Sus4,
+        name: "sus4",
+        aliases: &["sus"],
+        intervals: &[5],
+        replaces_third: true,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::HalfDiminish,
+        name: "o",
+        aliases: &["ø", "ø7"],
+        intervals: &[3, 6, 10],
+        replaces_third: true,
+        replaces_fifth: true,
+    },
+    ExtensionDefinition {
+        extension: Extension::Altered,
+        name: "alt",
+        aliases: &[],
+        intervals: &[6, 8, 10, 13, 15],
+        replaces_third: false,
+        replaces_fifth: true,
+    },
+    ExtensionDefinition {
+        extension: Extension::Omit3,
+        name: "omit3",
+        aliases: &["no3"],
+        intervals: &[],
+        replaces_third: true,
+        replaces_fifth: false,
+    },
+    ExtensionDefinition {
+        extension: Extension::Omit5,
+        name: "omit5",
+        aliases: &["no5"],
+        intervals: &[],
+        replaces_third: false,
+        replaces_fifth: true,
+    },
+];
+
+impl Extension {
+    pub fn definition(&self) -> &'static ExtensionDefinition {
+        EXTENSION_DEFINITIONS
+            .iter()
+            .find(|definition| definition.extension == *self)
+            .unwrap()
+    }
+
+    /// The first spelling in `preferred` which means this extension, or the canonical one.
+    ///
+    /// # Example
+    /// ```rust
+    /// use chord_progression_parser::parser::types::extension::Extension;
+    ///
+    /// let preferred = ["-5", "maj9"];
+    /// assert_eq!(Extension::FlatFive.display_name(&preferred), "-5");
+    /// assert_eq!(Extension::MajorNine.display_name(&preferred), "maj9");
+    /// assert_eq!(Extension::Seven.display_name(&preferred), "7");
+    /// ```
+    pub fn display_name<'a>(&self, preferred: &[&'a str]) -> &'a str {
+        let definition = self.definition();
+        preferred
+            .iter()
+            .find(|spelling| definition.aliases.contains(spelling))
+            .copied()
+            .unwrap_or(definition.name)
+    }
+
+    /// every spelling (canonical names and aliases), longest first.
+    /// the order is for reading extensions written in a row, like "7b9".
+    /// made once, since they are read for every chord.
+    pub fn spellings() -> &'static [(&'static str, Extension)] {
+        static SPELLINGS: OnceLock<Vec<(&'static str, Extension)>> = OnceLock::new();
+
+        SPELLINGS.get_or_init(|| {
+            let mut spellings: Vec<(&'static str, Extension)> = EXTENSION_DEFINITIONS
+                .iter()
+                .flat_map(|definition| {
+                    std::iter::once(definition.name)
+                        .chain(definition.aliases.iter().copied())
+                        .map(|spelling| (spelling, definition.extension.clone()))
+                })
+                .collect();
+            spellings.sort_by_key(|(spelling, _)| std::cmp::Reverse(spelling.len()));
+            spellings
+        })
+    }
+}
+
+impl FromStr for Extension {
+    type Err = ErrorInfo;
+
+    /// parse the canonical name or an alias
+    fn from_str(s: &str) -> Result<Self, ErrorInfo> {
+        EXTENSION_DEFINITIONS
+            .iter()
+            .find(|definition| definition.name == s || definition.aliases.contains(&s))
+            .map(|definition| definition.extension.clone())
+            .ok_or(ErrorInfo {
+                code: ErrorCode::Ext1,
+                additional_info: Some(s.to_string()),
+                cause: None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn every_extension_is_defined_once() {
+        for extension in Extension::iter() {
+            let definitions: Vec<&ExtensionDefinition> = EXTENSION_DEFINITIONS
+                .iter()
+                .filter(|definition| definition.extension == extension)
+                .collect();
+
+            assert_eq!(definitions.len(), 1, "{}", extension);
+            assert_eq!(definitions[0].name, extension.to_string());
+        }
+    }
+
+    #[test]
+    fn spellings_are_unique() {
+        let spellings: Vec<&str> = Extension::spellings()
+            .iter()
+            .map(|(spelling, _)| *spelling)
+            .collect();
+        let mut unique_spellings = spellings.clone();
+        unique_spellings.sort();
+        unique_spellings.dedup();
+
+        assert_eq!(spellings.len(), unique_spellings.len());
+    }
+
+    #[test]
+    fn aliases_are_parsed_to_canonical() {
+        assert_eq!(Extension::from_str("-5").unwrap(), Extension::FlatFive);
+        assert_eq!(Extension::from_str("+5").unwrap(), Extension::SharpFive);
+        assert_eq!(Extension::from_str("maj9").unwrap(), Extension::MajorNine);
+        assert_eq!(Extension::from_str("no3").unwrap(), Extension::Omit3);
+        assert_eq!(Extension::from_str("omit5").unwrap(), Extension::Omit5);
+        assert_eq!(Extension::from_str("b5").unwrap().to_string(), "b5");
+        assert_eq!(Extension::from_str("sus").unwrap(), Extension::Sus4);
+        assert_eq!(Extension::from_str("69").unwrap(), Extension::SixNine);
+        assert_eq!(Extension::from_str("-4").unwrap_err().code, ErrorCode::Ext1);
+    }
 }
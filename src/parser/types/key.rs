@@ -1,126 +1,390 @@
-use serde::{Deserialize, Serialize};
-use strum_macros::EnumString;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use strum_macros::{Display, EnumIter};
 use typeshare::typeshare;
 
-#[typeshare]
-#[allow(non_camel_case_types)]
-#[derive(Debug, PartialEq, Clone, EnumString, Serialize, Deserialize)]
+use crate::error_code::{ErrorCode, ErrorInfo};
+use crate::util::note::{accidental_to_spell, note_to_string, pitch_class, split_note};
+
+use super::{accidental::Accidental, base::Base};
+
+/// Mode of the key. Major and minor are the same as ionian and aeolian.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Display, EnumIter)]
+pub enum Mode {
+    #[strum(serialize = "major")]
+    Major,
+    #[strum(serialize = "dorian")]
+    Dorian,
+    #[strum(serialize = "phrygian")]
+    Phrygian,
+    #[strum(serialize = "lydian")]
+    Lydian,
+    #[strum(serialize = "mixolydian")]
+    Mixolydian,
+    #[strum(serialize = "minor")]
+    Minor,
+    #[strum(serialize = "locrian")]
+    Locrian,
+}
+
+/// intervals of the major scale, which are rotated for the other modes
+const MAJOR_SCALE: [u8; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+impl Mode {
+    /// accepted names of the mode written after the tonic, e.g. "D" + "dorian".
+    /// words are case insensitive.
+    const NAMES: [(&'static str, Mode); 13] = [
+        ("", Mode::Major),
+        ("m", Mode::Minor),
+        ("major", Mode::Major),
+        ("minor", Mode::Minor),
+        ("ionian", Mode::Major),
+        ("aeolian", Mode::Minor),
+        ("dorian", Mode::Dorian),
+        ("phrygian", Mode::Phrygian),
+        ("lydian", Mode::Lydian),
+        ("mixolydian", Mode::Mixolydian),
+        ("locrian", Mode::Locrian),
+        ("maj", Mode::Major),
+        ("min", Mode::Minor),
+    ];
+
+    fn from_name(name: &str) -> Option<Mode> {
+        Mode::NAMES
+            .iter()
+            .find(|(mode_name, _)| match *mode_name {
+                // "M" would be confused with "m"
+                "" | "m" => *mode_name == name,
+                _ => mode_name.eq_ignore_ascii_case(name),
+            })
+            .map(|(_, mode)| *mode)
+    }
+
+    /// 0 for major (ionian), 1 for dorian, ..., 6 for locrian
+    fn degree(&self) -> usize {
+        match self {
+            Mode::Major => 0,
+            Mode::Dorian => 1,
+            Mode::Phrygian => 2,
+            Mode::Lydian => 3,
+            Mode::Mixolydian => 4,
+            Mode::Minor => 5,
+            Mode::Locrian => 6,
+        }
+    }
+
+    /// intervals of the scale from the tonic
+    pub fn scale(&self) -> [u8; 7] {
+        let degree = self.degree();
+        let mut scale = [0; 7];
+        for (i, interval) in scale.iter_mut().enumerate() {
+            let note = MAJOR_SCALE[(degree + i) % 7] + 12 - MAJOR_SCALE[degree];
+            *interval = note % 12;
+        }
+        scale
+    }
+
+    /// the suffix written after the tonic, e.g. "" for major, "m" for minor
+    fn suffix(&self) -> String {
+        match self {
+            Mode::Major => "".to_string(),
+            Mode::Minor => "m".to_string(),
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// Key of the chord progression, written like `C`, `F#m`, `Ddorian` or `?`.
+///
+/// It is serialized as the string, so major and minor keys are the same JSON as before (`"Bb"`, `"C#m"`).
+/// The string is typed in TS as `KeyName` of `resources/key_name.ts`.
+#[typeshare(serialized_as = "KeyName")]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Key {
-    #[strum(serialize = "Cb")]
-    #[serde(rename = "Cb")]
-    Cb_M, //C flat major
-    #[strum(serialize = "Cbm")]
-    #[serde(rename = "Cbm")]
-    Cb_m, //C flat minor
-    #[strum(serialize = "C")]
-    #[serde(rename = "C")]
-    C_M, //C major
-    #[strum(serialize = "Cm")]
-    #[serde(rename = "Cm")]
-    C_m, //C minor
-    #[strum(serialize = "C#")]
-    #[serde(rename = "C#")]
-    Cs_M, //C sharp major
-    #[strum(serialize = "C#m")]
-    #[serde(rename = "C#m")]
-    Cs_m, //C sharp minor
-    #[strum(serialize = "Db")]
-    #[serde(rename = "Db")]
-    Db_M,
-    #[strum(serialize = "Dbm")]
-    #[serde(rename = "Dbm")]
-    Db_m,
-    #[strum(serialize = "D")]
-    #[serde(rename = "D")]
-    D_M,
-    #[strum(serialize = "Dm")]
-    #[serde(rename = "Dm")]
-    D_m,
-    #[strum(serialize = "D#")]
-    #[serde(rename = "D#")]
-    Ds_M,
-    #[strum(serialize = "D#m")]
-    #[serde(rename = "D#m")]
-    Ds_m,
-    #[strum(serialize = "Eb")]
-    #[serde(rename = "Eb")]
-    Eb_M,
-    #[strum(serialize = "Ebm")]
-    #[serde(rename = "Ebm")]
-    Eb_m,
-    #[strum(serialize = "E")]
-    #[serde(rename = "E")]
-    E_M,
-    #[strum(serialize = "Em")]
-    #[serde(rename = "Em")]
-    E_m,
-    #[strum(serialize = "E#")]
-    #[serde(rename = "E#")]
-    Fb_M,
-    #[strum(serialize = "E#m")]
-    #[serde(rename = "E#m")]
-    Fb_m,
-    #[strum(serialize = "F")]
-    #[serde(rename = "F")]
-    F_M,
-    #[strum(serialize = "Fm")]
-    #[serde(rename = "Fm")]
-    F_m,
-    #[strum(serialize = "F#")]
-    #[serde(rename = "F#")]
-    Fs_M,
-    #[strum(serialize = "F#m")]
-    #[serde(rename = "F#m")]
-    Fs_m,
-    #[strum(serialize = "Gb")]
-    #[serde(rename = "Gb")]
-    Gb_M,
-    #[strum(serialize = "Gbm")]
-    #[serde(rename = "Gbm")]
-    Gb_m,
-    #[strum(serialize = "G")]
-    #[serde(rename = "G")]
-    G_M,
-    #[strum(serialize = "Gm")]
-    #[serde(rename = "Gm")]
-    G_m,
-    #[strum(serialize = "G#")]
-    #[serde(rename = "G#")]
-    Gs_M,
-    #[strum(serialize = "G#m")]
-    #[serde(rename = "G#m")]
-    Gs_m,
-    #[strum(serialize = "Ab")]
-    #[serde(rename = "Ab")]
-    Ab_M,
-    #[strum(serialize = "Abm")]
-    #[serde(rename = "Abm")]
-    Ab_m,
-    #[strum(serialize = "A")]
-    #[serde(rename = "A")]
-    A_M,
-    #[strum(serialize = "Am")]
-    #[serde(rename = "Am")]
-    A_m,
-    #[strum(serialize = "A#")]
-    #[serde(rename = "A#")]
-    As_M,
-    #[strum(serialize = "A#m")]
-    #[serde(rename = "A#m")]
-    As_m,
-    #[strum(serialize = "Bb")]
-    #[serde(rename = "Bb")]
-    Bb_M,
-    #[strum(serialize = "Bbm")]
-    #[serde(rename = "Bbm")]
-    Bb_m,
-    #[strum(serialize = "B")]
-    #[serde(rename = "B")]
-    B_M,
-    #[strum(serialize = "Bm")]
-    #[serde(rename = "Bm")]
-    B_m,
-    #[strum(serialize = "?")]
-    #[serde(rename = "?")]
+    Tonal {
+        tonic: Base,
+        accidental: Option<Accidental>,
+        mode: Mode,
+    },
     UnIdentified,
 }
+
+impl Key {
+    pub fn new(tonic: Base, accidental: Option<Accidental>, mode: Mode) -> Key {
+        Key::Tonal {
+            tonic,
+            accidental,
+            mode,
+        }
+    }
+
+    pub fn major(tonic: Base, accidental: Option<Accidental>) -> Key {
+        Key::new(tonic, accidental, Mode::Major)
+    }
+
+    pub fn minor(tonic: Base, accidental: Option<Accidental>) -> Key {
+        Key::new(tonic, accidental, Mode::Minor)
+    }
+
+    pub fn mode(&self) -> Option<Mode> {
+        match self {
+            Key::Tonal { mode, .. } => Some(*mode),
+            Key::UnIdentified => None,
+        }
+    }
+
+    /// pitch class of the tonic (C = 0, ..., B = 11)
+    pub fn tonic_pitch_class(&self) -> Option<u8> {
+        match self {
+            Key::Tonal {
+                tonic, accidental, ..
+            } => Some(pitch_class(tonic, accidental)),
+            Key::UnIdentified => None,
+        }
+    }
+
+    /// number of accidentals in the key signature. sharps are positive and flats are negative.
+    ///
+    /// e.g. `D` -> 2, `Dm` -> -1, `Ddorian` -> 0
+    pub fn key_signature(&self) -> Option<i8> {
+        match self {
+            Key::Tonal {
+                tonic,
+                accidental,
+                mode,
+            } => {
+                // position of the tonic in the circle of fifths from C
+                let fifths = match tonic {
+                    Base::F => -1,
+                    Base::C => 0,
+                    Base::G => 1,
+                    Base::D => 2,
+                    Base::A => 3,
+                    Base::E => 4,
+                    Base::B => 5,
+                };
+                let accidental = accidental.as_ref().map_or(0, |a| a.semitones());
+                // major is 0, and each degree of the mode moves the relative major by a fifth
+                let mode = [0, -2, -4, 1, -1, -3, -5][mode.degree()];
+
+                Some(fifths + 7 * accidental + mode)
+            }
+            Key::UnIdentified => None,
+        }
+    }
+
+    /// notes of the scale spelled with one letter per degree, e.g. `F` -> F, G, A, Bb, C, D, E
+    pub fn scale_notes(&self) -> Option<Vec<(Base, Option<Accidental>)>> {
+        let (tonic, mode) = match self {
+            Key::Tonal { tonic, mode, .. } => (tonic, mode),
+            Key::UnIdentified => return None,
+        };
+        let tonic_pitch_class = self.tonic_pitch_class()?;
+
+        let notes = mode
+            .scale()
+            .iter()
+            .enumerate()
+            .map(|(i, interval)| {
+                let base = tonic.up(i);
+                let accidental = accidental_to_spell(&base, (tonic_pitch_class + interval) % 12);
+                (base, accidental)
+            })
+            .collect();
+
+        Some(notes)
+    }
+
+    /// relative minor of major keys, and relative major of the other keys.
+    ///
+    /// e.g. `C` -> `Am`, `Am` -> `C`, `Ddorian` -> `C`
+    pub fn relative(&self) -> Option<Key> {
+        let mode = self.mode()?;
+        let notes = self.scale_notes()?;
+
+        let (degree, relative_mode) = match mode {
+            Mode::Major => (Mode::Minor.degree(), Mode::Minor),
+            _ => ((7 - mode.degree()) % 7, Mode::Major),
+        };
+        let (tonic, accidental) = notes[degree].clone();
+
+        Some(Key::new(tonic, accidental, relative_mode))
+    }
+
+    /// parallel minor of major keys, and parallel major of the other keys.
+    ///
+    /// e.g. `C` -> `Cm`, `Cm` -> `C`, `Cmixolydian` -> `C`
+    pub fn parallel(&self) -> Option<Key> {
+        match self {
+            Key::Tonal {
+                tonic,
+                accidental,
+                mode,
+            } => Some(Key::new(
+                tonic.clone(),
+                accidental.clone(),
+                match mode {
+                    Mode::Major => Mode::Minor,
+                    _ => Mode::Major,
+                },
+            )),
+            Key::UnIdentified => None,
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Tonal {
+                tonic,
+                accidental,
+                mode,
+            } => write!(f, "{}{}", note_to_string(tonic, accidental), mode.suffix()),
+            Key::UnIdentified => write!(f, "?"),
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = ErrorInfo;
+
+    /// parse the key like `C`, `B♭m`, `Ddorian`, `F# lydian` or `?`
+    fn from_str(s: &str) -> Result<Self, ErrorInfo> {
+        let error = || ErrorInfo {
+            code: ErrorCode::Cimv4,
+            additional_info: Some(s.to_string()),
+            cause: None,
+        };
+
+        if s == "?" {
+            return Ok(Key::UnIdentified);
+        }
+
+        let (tonic, accidental, rest) = split_note(s).ok_or_else(error)?;
+        let accidental = match accidental {
+            // double accidentals are not used for keys
+            Some(Accidental::DoubleSharp) | Some(Accidental::DoubleFlat) => return Err(error()),
+            Some(Accidental::Natural) => None,
+            accidental => accidental,
+        };
+        let mode = Mode::from_name(rest.trim_start()).ok_or_else(error)?;
+
+        Ok(Key::new(tonic, accidental, mode))
+    }
+}
+
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Key::from_str(&s).map_err(|_| serde::de::Error::custom(format!("invalid key: {}", s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    fn key(s: &str) -> Key {
+        Key::from_str(s).unwrap()
+    }
+
+    fn scale(s: &str) -> Vec<String> {
+        key(s)
+            .scale_notes()
+            .unwrap()
+            .iter()
+            .map(|(base, accidental)| note_to_string(base, accidental))
+            .collect()
+    }
+
+    #[test]
+    fn parse_and_print() {
+        assert_eq!(key("C"), Key::major(Base::C, None));
+        assert_eq!(key("F#m"), Key::minor(Base::F, Some(Accidental::Sharp)));
+        assert_eq!(key("B♭"), Key::major(Base::B, Some(Accidental::Flat)));
+        assert_eq!(key("Ddorian"), Key::new(Base::D, None, Mode::Dorian));
+        assert_eq!(
+            key("G Mixolydian"),
+            Key::new(Base::G, None, Mode::Mixolydian)
+        );
+        assert_eq!(key("Aaeolian"), key("Am"));
+        assert_eq!(key("?"), Key::UnIdentified);
+
+        for s in ["C", "C#m", "Ebm", "E#", "Ddorian", "Bblocrian", "?"] {
+            assert_eq!(key(s).to_string(), s);
+        }
+    }
+
+    #[test]
+    fn invalid_key() {
+        for s in ["", "H", "CM", "Cdorianx", "F##", "Cbbm"] {
+            let error = Key::from_str(s).unwrap_err();
+            assert_eq!(error.code, ErrorCode::Cimv4);
+            assert_eq!(error.additional_info, Some(s.to_string()));
+        }
+    }
+
+    #[test]
+    fn json_is_string() {
+        assert_eq!(serde_json::to_string(&key("Bbm")).unwrap(), "\"Bbm\"");
+        assert_eq!(
+            serde_json::from_str::<Key>("\"Ddorian\"").unwrap(),
+            key("Ddorian")
+        );
+    }
+
+    #[test]
+    fn typescript_key_name_has_every_mode() {
+        let key_name = include_str!("../../../resources/key_name.ts");
+
+        for mode in Mode::iter() {
+            assert!(
+                key_name.contains(&format!("\"{}\"", mode.suffix())),
+                "{}",
+                mode
+            );
+        }
+    }
+
+    #[test]
+    fn key_signature() {
+        assert_eq!(key("C").key_signature(), Some(0));
+        assert_eq!(key("D").key_signature(), Some(2));
+        assert_eq!(key("Dm").key_signature(), Some(-1));
+        assert_eq!(key("Ebm").key_signature(), Some(-6));
+        assert_eq!(key("C#").key_signature(), Some(7));
+        assert_eq!(key("Ddorian").key_signature(), Some(0));
+        assert_eq!(key("Flydian").key_signature(), Some(0));
+        assert_eq!(key("Bphrygian").key_signature(), Some(1));
+        assert_eq!(Key::UnIdentified.key_signature(), None);
+    }
+
+    #[test]
+    fn scale_notes() {
+        assert_eq!(scale("F"), ["F", "G", "A", "Bb", "C", "D", "E"]);
+        assert_eq!(scale("C#m"), ["C#", "D#", "E", "F#", "G#", "A", "B"]);
+        assert_eq!(scale("Ddorian"), ["D", "E", "F", "G", "A", "B", "C"]);
+        assert_eq!(scale("E#"), ["E#", "F##", "G##", "A#", "B#", "C##", "D##"]);
+    }
+
+    #[test]
+    fn relative_and_parallel() {
+        assert_eq!(key("C").relative(), Some(key("Am")));
+        assert_eq!(key("Ebm").relative(), Some(key("Gb")));
+        assert_eq!(key("Ddorian").relative(), Some(key("C")));
+        assert_eq!(key("Bblocrian").relative(), Some(key("Cb")));
+        assert_eq!(key("C").parallel(), Some(key("Cm")));
+        assert_eq!(key("F#m").parallel(), Some(key("F#")));
+        assert_eq!(Key::UnIdentified.relative(), None);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod accidental;
+pub mod articulation;
 pub mod ast;
 pub mod bar;
 pub mod base;
@@ -9,7 +10,12 @@ pub mod chord_expression;
 pub mod chord_info;
 pub mod chord_info_meta;
 pub mod chord_type;
+pub mod dynamics;
 pub mod extension;
 pub mod key;
+pub mod navigation_mark;
+pub mod poly_chord;
 pub mod section;
 pub mod section_meta;
+pub mod section_reference;
+pub mod time_signature;
@@ -0,0 +1,172 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::error_code::{ErrorCode, ErrorInfo};
+
+/// Marks which change the order the bars are played in, placed between bars
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum NavigationMark {
+    RepeatStart, // |:
+    RepeatEnd,   // :|
+    /// numbered ending, like `<1.>` or `<1.2.>`. the bars until the next mark are played in the passes.
+    Volta(Vec<u32>),
+    Segno,          // <segno>
+    Coda,           // <coda>
+    ToCoda,         // <to coda>
+    Fine,           // <fine>
+    DaCapo,         // <D.C.>
+    DaCapoAlFine,   // <D.C. al Fine>
+    DaCapoAlCoda,   // <D.C. al Coda>
+    DalSegno,       // <D.S.>
+    DalSegnoAlFine, // <D.S. al Fine>
+    DalSegnoAlCoda, // <D.S. al Coda>
+}
+
+/// names written in `<...>`, compared ignoring case and spaces
+const NAMED_MARKS: [(&str, NavigationMark); 12] = [
+    ("segno", NavigationMark::Segno),
+    ("𝄋", NavigationMark::Segno),
+    ("coda", NavigationMark::Coda),
+    ("𝄌", NavigationMark::Coda),
+    ("tocoda", NavigationMark::ToCoda),
+    ("fine", NavigationMark::Fine),
+    ("d.c.", NavigationMark::DaCapo),
+    ("d.c.alfine", NavigationMark::DaCapoAlFine),
+    ("d.c.alcoda", NavigationMark::DaCapoAlCoda),
+    ("d.s.", NavigationMark::DalSegno),
+    ("d.s.alfine", NavigationMark::DalSegnoAlFine),
+    ("d.s.alcoda", NavigationMark::DalSegnoAlCoda),
+];
+
+impl NavigationMark {
+    /// `D.C.` and `D.S.` (with or without `al Fine` / `al Coda`)
+    pub fn is_jump(&self) -> bool {
+        matches!(
+            self,
+            NavigationMark::DaCapo
+                | NavigationMark::DaCapoAlFine
+                | NavigationMark::DaCapoAlCoda
+                | NavigationMark::DalSegno
+                | NavigationMark::DalSegnoAlFine
+                | NavigationMark::DalSegnoAlCoda
+        )
+    }
+}
+
+impl FromStr for NavigationMark {
+    type Err = ErrorInfo;
+
+    /// parse the mark like `|:`, `:|`, `<1.>`, `<segno>` or `<D.S. al Coda>`
+    fn from_str(s: &str) -> Result<Self, ErrorInfo> {
+        let error = || ErrorInfo {
+            code: ErrorCode::Nav1,
+            additional_info: Some(s.to_string()),
+            cause: None,
+        };
+
+        match s {
+            "|:" => return Ok(NavigationMark::RepeatStart),
+            ":|" => return Ok(NavigationMark::RepeatEnd),
+            _ => {}
+        }
+
+        let name: String = s
+            .strip_prefix('<')
+            .and_then(|s| s.strip_suffix('>'))
+            .ok_or_else(error)?
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase();
+
+        if let Some((_, mark)) = NAMED_MARKS.iter().find(|(mark_name, _)| *mark_name == name) {
+            return Ok(mark.clone());
+        }
+
+        // volta like "1." or "1.2." (or "1,2.")
+        let numbers = name
+            .strip_suffix('.')
+            .ok_or_else(error)?
+            .split(['.', ','])
+            .map(|number| match number.parse::<u32>() {
+                Ok(number) if number > 0 => Ok(number),
+                _ => Err(error()),
+            })
+            .collect::<Result<Vec<u32>, ErrorInfo>>()?;
+
+        Ok(NavigationMark::Volta(numbers))
+    }
+}
+
+impl fmt::Display for NavigationMark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NavigationMark::RepeatStart => write!(f, "|:"),
+            NavigationMark::RepeatEnd => write!(f, ":|"),
+            NavigationMark::Volta(numbers) => write!(
+                f,
+                "<{}.>",
+                numbers
+                    .iter()
+                    .map(|number| number.to_string())
+                    .collect::<Vec<String>>()
+                    .join(".")
+            ),
+            NavigationMark::Segno => write!(f, "<segno>"),
+            NavigationMark::Coda => write!(f, "<coda>"),
+            NavigationMark::ToCoda => write!(f, "<to coda>"),
+            NavigationMark::Fine => write!(f, "<fine>"),
+            NavigationMark::DaCapo => write!(f, "<D.C.>"),
+            NavigationMark::DaCapoAlFine => write!(f, "<D.C. al Fine>"),
+            NavigationMark::DaCapoAlCoda => write!(f, "<D.C. al Coda>"),
+            NavigationMark::DalSegno => write!(f, "<D.S.>"),
+            NavigationMark::DalSegnoAlFine => write!(f, "<D.S. al Fine>"),
+            NavigationMark::DalSegnoAlCoda => write!(f, "<D.S. al Coda>"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_print() {
+        for s in [
+            "|:",
+            ":|",
+            "<1.>",
+            "<1.2.>",
+            "<segno>",
+            "<to coda>",
+            "<D.C. al Fine>",
+            "<D.S. al Coda>",
+        ] {
+            assert_eq!(NavigationMark::from_str(s).unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn spellings_are_loose() {
+        let mark = |s: &str| NavigationMark::from_str(s).unwrap();
+
+        assert_eq!(mark("<To Coda>"), NavigationMark::ToCoda);
+        assert_eq!(mark("<d.c.  al fine>"), NavigationMark::DaCapoAlFine);
+        assert_eq!(mark("<𝄋>"), NavigationMark::Segno);
+        assert_eq!(mark("<1,2.>"), NavigationMark::Volta(vec![1, 2]));
+    }
+
+    #[test]
+    fn invalid_mark() {
+        for s in ["<>", "<da capo>", "<0.>", "<1>", "<1..>", "segno"] {
+            let error = NavigationMark::from_str(s).unwrap_err();
+            assert_eq!(error.code, ErrorCode::Nav1, "{}", s);
+            assert_eq!(error.additional_info, Some(s.to_string()));
+        }
+    }
+}
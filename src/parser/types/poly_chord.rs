@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use super::{accidental::Accidental, base::Base, chord::Chord, chord_detailed::ChordDetailed};
+use crate::error_code::{ErrorCode, ErrorInfo};
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+/// Two chords played at the same time, written like `{D|C7}` (D triad over C7)
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolyChord {
+    pub upper: Chord,
+    pub lower: Chord,
+}
+
+impl FromStr for PolyChord {
+    type Err = ErrorInfo;
+
+    fn from_str(s: &str) -> Result<Self, ErrorInfo> {
+        let inner = match s.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(inner) => inner,
+            None => {
+                return Err(ErrorInfo {
+                    code: ErrorCode::Cho4,
+                    additional_info: Some(s.to_string()),
+                    cause: None,
+                })
+            }
+        };
+
+        let (upper, lower) = match inner.split_once('|') {
+            Some((upper, lower)) if !upper.is_empty() && !lower.is_empty() => {
+                (upper.trim(), lower.trim())
+            }
+            _ => {
+                return Err(ErrorInfo {
+                    code: ErrorCode::Cho5,
+                    additional_info: Some(s.to_string()),
+                    cause: None,
+                })
+            }
+        };
+
+        Ok(PolyChord {
+            upper: Chord {
+                plain: upper.to_string(),
+                detailed: ChordDetailed::from_str(upper)?,
+            },
+            lower: Chord {
+                plain: lower.to_string(),
+                detailed: ChordDetailed::from_str(lower)?,
+            },
+        })
+    }
+}
+
+impl PolyChord {
+    /// notes of the lower chord followed by the notes of the upper chord
+    pub fn notes(&self) -> Vec<(Base, Option<Accidental>)> {
+        [self.lower.detailed.notes(), self.upper.detailed.notes()].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::note::note_to_string;
+
+    #[test]
+    fn upper_and_lower() {
+        let poly_chord = PolyChord::from_str("{D|C7}").unwrap();
+
+        assert_eq!(poly_chord.upper.plain, "D");
+        assert_eq!(poly_chord.lower.plain, "C7");
+        assert_eq!(
+            poly_chord.lower.detailed,
+            ChordDetailed::from_str("C(7)").unwrap()
+        );
+
+        let notes: Vec<String> = poly_chord
+            .notes()
+            .iter()
+            .map(|(base, accidental)| note_to_string(base, accidental))
+            .collect();
+        assert_eq!(notes, ["C", "E", "G", "Bb", "D", "F#", "A"]);
+    }
+
+    #[test]
+    fn parts_can_have_extensions() {
+        let poly_chord = PolyChord::from_str("{Bbm(7)|E♭(9)}").unwrap();
+
+        assert_eq!(poly_chord.upper.plain, "Bbm(7)");
+        assert_eq!(poly_chord.lower.detailed.root_pitch_class(), 3);
+    }
+
+    #[test]
+    fn invalid_poly_chord() {
+        let code = |s: &str| PolyChord::from_str(s).unwrap_err().code;
+
+        assert_eq!(code("{D|C7"), ErrorCode::Cho4);
+        assert_eq!(code("{D}"), ErrorCode::Cho5);
+        assert_eq!(code("{|C}"), ErrorCode::Cho5);
+        assert_eq!(code("{H|C}"), ErrorCode::Bs1);
+    }
+}
@@ -1,3 +1,4 @@
+use super::section_reference::SectionReference;
 use serde::{Deserialize, Serialize};
 use typeshare::typeshare;
 
@@ -7,5 +8,26 @@ use typeshare::typeshare;
 pub enum SectionMeta {
     Section(String),
     Repeat(u32),
-    // or more
+    /// the chord blocks are cloned from the referenced section
+    Use(SectionReference),
+    /// path of the file whose sections are included here
+    Include(String),
+    /// meta info of a key registered in the `MetaRegistry`, like `@mood=calm`
+    Custom {
+        key: String,
+        value: String,
+    },
+}
+
+impl SectionMeta {
+    /// key written in `@key=value`
+    pub fn key(&self) -> &str {
+        match self {
+            SectionMeta::Section(_) => "section",
+            SectionMeta::Repeat(_) => "repeat",
+            SectionMeta::Use(_) => "use",
+            SectionMeta::Include(_) => "include",
+            SectionMeta::Custom { key, .. } => key,
+        }
+    }
 }
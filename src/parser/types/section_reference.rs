@@ -0,0 +1,85 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::error_code::{ErrorCode, ErrorInfo};
+
+/// Reference to another section, written like `@use=Verse` or `@use=Verse:+2`
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionReference {
+    /// name of the referenced section (the value of `@section`)
+    pub name: String,
+    /// semitones to transpose the chords of the referenced section
+    pub transpose: i32,
+}
+
+impl FromStr for SectionReference {
+    type Err = ErrorInfo;
+
+    fn from_str(s: &str) -> Result<Self, ErrorInfo> {
+        let error = || ErrorInfo {
+            code: ErrorCode::Smiv6,
+            additional_info: Some(s.to_string()),
+            cause: None,
+        };
+
+        let (name, transpose) = match s.rsplit_once(':') {
+            Some((name, transpose)) => (name, transpose.parse::<i32>().map_err(|_| error())?),
+            None => (s, 0),
+        };
+        if name.is_empty() {
+            return Err(error());
+        }
+
+        Ok(SectionReference {
+            name: name.to_string(),
+            transpose,
+        })
+    }
+}
+
+impl fmt::Display for SectionReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.transpose {
+            0 => write!(f, "{}", self.name),
+            transpose => write!(f, "{}:{:+}", self.name, transpose),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_print() {
+        let reference = |s: &str| SectionReference::from_str(s).unwrap();
+
+        assert_eq!(
+            reference("Verse"),
+            SectionReference {
+                name: "Verse".to_string(),
+                transpose: 0,
+            }
+        );
+        assert_eq!(reference("Verse 1:-3").transpose, -3);
+        assert_eq!(reference("Verse:2").to_string(), "Verse:+2");
+        assert_eq!(reference("Verse:+0").to_string(), "Verse");
+    }
+
+    #[test]
+    fn invalid_reference() {
+        for s in ["", ":+2", "Verse:", "Verse:up"] {
+            assert_eq!(
+                SectionReference::from_str(s).unwrap_err().code,
+                ErrorCode::Smiv6,
+                "{}",
+                s
+            );
+        }
+    }
+}
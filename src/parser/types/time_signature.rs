@@ -0,0 +1,106 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use super::bar::Bar;
+use super::chord_info_meta::ChordInfoMeta;
+use crate::error_code::{ErrorCode, ErrorInfo};
+
+/// Time signature, written like `[time=3/4]`
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSignature {
+    /// number of beats in a bar (numerator)
+    pub beats: u32,
+    /// note value of a beat (denominator), like 4 for a quarter note
+    pub beat_type: u32,
+}
+
+impl TimeSignature {
+    /// the time signature of the bars before any `[time=...]`
+    pub fn common() -> TimeSignature {
+        TimeSignature {
+            beats: 4,
+            beat_type: 4,
+        }
+    }
+
+    /// length of a bar in quarter notes, multiplied by `unit` to keep it an integer
+    pub fn quarters(&self, unit: u32) -> u32 {
+        unit * self.beats * 4 / self.beat_type
+    }
+
+    /// time signature written in the bar, which is carried forward to the following bars
+    pub fn of_bar(bar: &Bar) -> Option<&TimeSignature> {
+        bar.iter()
+            .flat_map(|chord_info| chord_info.meta_infos.iter())
+            .rev()
+            .find_map(|meta_info| match meta_info {
+                ChordInfoMeta::Time(time_signature) => Some(time_signature),
+                _ => None,
+            })
+    }
+}
+
+impl FromStr for TimeSignature {
+    type Err = ErrorInfo;
+
+    fn from_str(s: &str) -> Result<Self, ErrorInfo> {
+        let error = || ErrorInfo {
+            code: ErrorCode::Cimv5,
+            additional_info: Some(s.to_string()),
+            cause: None,
+        };
+
+        let (beats, beat_type) = s.split_once('/').ok_or_else(error)?;
+        let beats = beats.parse::<u32>().map_err(|_| error())?;
+        let beat_type = beat_type.parse::<u32>().map_err(|_| error())?;
+        if !(1..=64).contains(&beats) || !matches!(beat_type, 1 | 2 | 4 | 8 | 16 | 32) {
+            return Err(error());
+        }
+
+        Ok(TimeSignature { beats, beat_type })
+    }
+}
+
+impl fmt::Display for TimeSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.beats, self.beat_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_print() {
+        let time_signature = TimeSignature::from_str("6/8").unwrap();
+
+        assert_eq!(
+            time_signature,
+            TimeSignature {
+                beats: 6,
+                beat_type: 8,
+            }
+        );
+        assert_eq!(time_signature.to_string(), "6/8");
+        assert_eq!(time_signature.quarters(2), 6);
+        assert_eq!(TimeSignature::common().quarters(1), 4);
+    }
+
+    #[test]
+    fn invalid_time_signature() {
+        for s in ["", "4", "4/", "/4", "0/4", "3/5", "a/4", "4/4/4"] {
+            assert_eq!(
+                TimeSignature::from_str(s).unwrap_err().code,
+                ErrorCode::Cimv5,
+                "{}",
+                s
+            );
+        }
+    }
+}
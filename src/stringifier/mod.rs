@@ -0,0 +1,686 @@
+use crate::parser::types::accidental::AccidentalNotation;
+use crate::parser::types::ast::Ast;
+use crate::parser::types::chord_block::ChordBlock;
+use crate::parser::types::chord_expression::ChordExpression;
+use crate::parser::types::chord_info::ChordInfo;
+use crate::parser::types::chord_info_meta::ChordInfoMeta;
+use crate::parser::types::section::Section;
+use crate::parser::types::section_meta::SectionMeta;
+use crate::util::note::renotate_note;
+use trivia::{section_items, Anchor, Trivia, TriviaLine};
+
+pub mod trivia;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct StringifyOptions {
+    /// rewrite accidentals of chords, denominators and keys with the notation.
+    /// `None` keeps the chords as written, and writes the keys in the notation of their chords
+    /// (as the AST doesn't keep how the keys are written).
+    pub accidental_notation: Option<AccidentalNotation>,
+}
+
+/// Convert the AST back to a chord progression string
+///
+/// The output is the canonical format, so `parse -> stringify -> parse` results in the same AST.
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::parse_chord_progression_string;
+/// use chord_progression_parser::stringifier::stringify;
+///
+/// let ast = parse_chord_progression_string("@section=A\nC-F,G").unwrap();
+/// assert_eq!(stringify(&ast), "@section=A\nC - F,G\n");
+/// ```
+pub fn stringify(ast: &Ast) -> String {
+    stringify_with_options(ast, &StringifyOptions::default())
+}
+
+/// Convert the AST back to a chord progression string with the options
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::parse_chord_progression_string;
+/// use chord_progression_parser::parser::types::accidental::AccidentalNotation;
+/// use chord_progression_parser::stringifier::{stringify_with_options, StringifyOptions};
+///
+/// let ast = parse_chord_progression_string("[key=Bb]Bb-Eb/G").unwrap();
+/// let options = StringifyOptions {
+///     accidental_notation: Some(AccidentalNotation::Unicode),
+/// };
+/// assert_eq!(stringify_with_options(&ast, &options), "[key=B♭]B♭ - E♭/G\n");
+/// ```
+pub fn stringify_with_options(ast: &Ast, options: &StringifyOptions) -> String {
+    stringify_with_trivia(ast, &Trivia::default(), options)
+}
+
+/// Convert the AST back to a chord progression string with the options,
+/// writing back the trivia of the input the AST is parsed from (see [`collect_trivia`](trivia::collect_trivia))
+pub fn stringify_with_trivia(ast: &Ast, trivia: &Trivia, options: &StringifyOptions) -> String {
+    let mut writer = TriviaWriter {
+        trivia,
+        line_index: 0,
+        trailing_comment_index: 0,
+        item: 0,
+    };
+    let mut result = writer.header(
+        ast.iter().any(|section| section_items(section) > 0),
+        options,
+    );
+
+    let mut item = 0;
+    let sections: Vec<String> = ast
+        .iter()
+        .map(|section| {
+            let mut section_result = String::new();
+            for (line, items) in section_lines(section, trivia, item, options) {
+                writer.write_line(&mut section_result, &line, items, options);
+            }
+            item += section_items(section);
+            section_result
+        })
+        .collect();
+    result.push_str(&sections.join("\n"));
+    writer.write_lines(&mut result, |_| true, options);
+
+    result
+}
+
+/// Writer of the lines with the trivia between them, counting the items written
+struct TriviaWriter<'t> {
+    trivia: &'t Trivia,
+    /// index of the next line of the trivia (a comment or a definition)
+    line_index: usize,
+    /// index of the next comment at the end of a line
+    trailing_comment_index: usize,
+    /// index of the next item
+    item: usize,
+}
+
+impl TriviaWriter<'_> {
+    /// the lines of the trivia at the start, and the blank line after them if anything follows
+    fn header(&mut self, has_items: bool, options: &StringifyOptions) -> String {
+        let mut result = String::new();
+        self.write_lines(&mut result, |anchor| anchor == Anchor::Header, options);
+        if !result.is_empty() && has_items {
+            result.push('\n');
+        }
+
+        result
+    }
+
+    /// write the lines of the trivia while `is_written` holds
+    fn write_lines(
+        &mut self,
+        result: &mut String,
+        is_written: impl Fn(Anchor) -> bool,
+        options: &StringifyOptions,
+    ) {
+        while let Some((anchor, line)) = self.trivia.lines.get(self.line_index) {
+            if !is_written(*anchor) {
+                break;
+            }
+            match line {
+                TriviaLine::Comment(comment) => result.push_str(comment),
+                TriviaLine::Definition(definition) => {
+                    result.push_str(&format!(
+                        "@def {}={}",
+                        definition.name,
+                        stringify_chord_info_with_options(&definition.value, options)
+                    ));
+                    if let Some(comment) = &definition.comment {
+                        result.push(' ');
+                        result.push_str(comment);
+                    }
+                }
+            }
+            result.push('\n');
+            self.line_index += 1;
+        }
+    }
+
+    /// write the line of the next `items` items, with the comments before, at the end of and after it
+    fn write_line(
+        &mut self,
+        result: &mut String,
+        line: &str,
+        items: usize,
+        options: &StringifyOptions,
+    ) {
+        let (start, end) = (self.item, self.item + items);
+
+        self.write_lines(
+            result,
+            |anchor| match anchor {
+                Anchor::Header => true,
+                Anchor::Before(item) => item < end,
+                Anchor::After(item) => item < start,
+            },
+            options,
+        );
+        result.push_str(line);
+        while let Some((item, comment)) = self
+            .trivia
+            .trailing_comments
+            .get(self.trailing_comment_index)
+        {
+            if *item >= end {
+                break;
+            }
+            result.push(' ');
+            result.push_str(comment);
+            self.trailing_comment_index += 1;
+        }
+        result.push('\n');
+        self.write_lines(
+            result,
+            |anchor| matches!(anchor, Anchor::After(item) if item < end),
+            options,
+        );
+
+        self.item = end;
+    }
+}
+
+fn notate(s: &str, options: &StringifyOptions) -> String {
+    match options.accidental_notation {
+        Some(notation) => renotate_note(s, notation),
+        None => s.to_string(),
+    }
+}
+
+/// lines of the section, with the number of the items (the section meta infos, the chords and the navigation marks) in each.
+/// `item` is the index of the first item of the section, to find the references to the chord macros of the trivia.
+fn section_lines(
+    section: &Section,
+    trivia: &Trivia,
+    mut item: usize,
+    options: &StringifyOptions,
+) -> Vec<(String, usize)> {
+    let mut lines: Vec<(String, usize)> = section
+        .meta_infos
+        .iter()
+        .map(|meta_info| (stringify_section_meta(meta_info), 1))
+        .collect();
+
+    // the chord blocks of the section with `@use` are cloned from the referenced section
+    if section
+        .meta_infos
+        .iter()
+        .any(|meta_info| matches!(meta_info, SectionMeta::Use(_)))
+    {
+        return lines;
+    }
+    item += lines.len();
+
+    let bar_lines = section
+        .chord_blocks
+        .split(|chord_block| *chord_block == ChordBlock::Br)
+        .filter(|bars| !bars.is_empty());
+
+    for bars in bar_lines {
+        // bars are separated by " - ", and navigation marks by " "
+        let mut line = String::new();
+        let mut items = 0;
+        let mut is_previous_bar = false;
+        for chord_block in bars.iter() {
+            match chord_block {
+                ChordBlock::Bar(bar) => {
+                    if is_previous_bar {
+                        line.push_str(" - ");
+                    } else if !line.is_empty() {
+                        line.push(' ');
+                    }
+                    line.push_str(
+                        &bar.iter()
+                            .enumerate()
+                            .map(|(index, chord_info)| {
+                                stringify_chord(chord_info, trivia, item + items + index, options)
+                            })
+                            .collect::<Vec<String>>()
+                            .join(","),
+                    );
+                    items += bar.len();
+                    is_previous_bar = true;
+                }
+                ChordBlock::Navigation(mark) => {
+                    if !line.is_empty() {
+                        line.push(' ');
+                    }
+                    line.push_str(&mark.to_string());
+                    items += 1;
+                    is_previous_bar = false;
+                }
+                ChordBlock::Br => {}
+            }
+        }
+        lines.push((line, items));
+        item += items;
+    }
+
+    lines
+}
+
+/// the chord at `item`, written as the reference to its chord macro (like `[key=C]$X`)
+/// while it is the chord of the definition, with the meta infos of the reference before it
+fn stringify_chord(
+    chord_info: &ChordInfo,
+    trivia: &Trivia,
+    item: usize,
+    options: &StringifyOptions,
+) -> String {
+    let definition = trivia
+        .reference(item)
+        .and_then(|name| trivia.definition(name))
+        .filter(|definition| {
+            chord_info.chord_expression == definition.value.chord_expression
+                && chord_info.denominator == definition.value.denominator
+                && chord_info
+                    .meta_infos
+                    .ends_with(&definition.value.meta_infos)
+        });
+
+    match definition {
+        Some(definition) => {
+            let meta_infos = &chord_info.meta_infos
+                [..chord_info.meta_infos.len() - definition.value.meta_infos.len()];
+            format!(
+                "{}${}",
+                stringify_meta_infos(meta_infos, chord_info, options),
+                definition.name
+            )
+        }
+        None => stringify_chord_info_with_options(chord_info, options),
+    }
+}
+
+pub fn stringify_section_meta(section_meta: &SectionMeta) -> String {
+    match section_meta {
+        SectionMeta::Section(value) => format!("@section={}", value),
+        SectionMeta::Repeat(value) => format!("@repeat={}", value),
+        SectionMeta::Use(reference) => format!("@use={}", reference),
+        SectionMeta::Include(path) => format!("@include={}", path),
+        SectionMeta::Custom { key, value } => format!("@{}={}", key, value),
+    }
+}
+
+pub fn stringify_chord_info_meta(chord_info_meta: &ChordInfoMeta) -> String {
+    stringify_chord_info_meta_with_options(chord_info_meta, &StringifyOptions::default())
+}
+
+fn stringify_chord_info_meta_with_options(
+    chord_info_meta: &ChordInfoMeta,
+    options: &StringifyOptions,
+) -> String {
+    match chord_info_meta {
+        ChordInfoMeta::Key(key) => format!("[key={}]", notate(&key.to_string(), options)),
+        ChordInfoMeta::Time(time_signature) => format!("[time={}]", time_signature),
+        ChordInfoMeta::Tempo(tempo) => format!("[tempo={}]", tempo),
+        ChordInfoMeta::Ritardando => "[rit]".to_string(),
+        ChordInfoMeta::Accelerando => "[accel]".to_string(),
+        ChordInfoMeta::Fermata => "[fermata]".to_string(),
+        ChordInfoMeta::Dynamics(dynamics) => format!("[dynamics={}]", dynamics),
+        ChordInfoMeta::Articulation(articulation) => format!("[articulation={}]", articulation),
+        ChordInfoMeta::Custom { key, value } => format!("[{}={}]", key, value),
+    }
+}
+
+pub fn stringify_chord_expression(chord_expression: &ChordExpression) -> String {
+    stringify_chord_expression_with_options(chord_expression, &StringifyOptions::default())
+}
+
+fn stringify_chord_expression_with_options(
+    chord_expression: &ChordExpression,
+    options: &StringifyOptions,
+) -> String {
+    match chord_expression {
+        ChordExpression::Chord(chord) => notate(&chord.plain, options),
+        ChordExpression::PolyChord(poly_chord) => format!(
+            "{{{}|{}}}",
+            notate(&poly_chord.upper.plain, options),
+            notate(&poly_chord.lower.plain, options)
+        ),
+        ChordExpression::UnIdentified => "?".to_string(),
+        ChordExpression::NoChord => "_".to_string(),
+        ChordExpression::Same => "%".to_string(),
+    }
+}
+
+pub fn stringify_chord_info(chord_info: &ChordInfo) -> String {
+    stringify_chord_info_with_options(chord_info, &StringifyOptions::default())
+}
+
+/// notation the chord is written in, ASCII unless it has Unicode accidentals like `E♭`
+fn written_notation(chord_info: &ChordInfo) -> AccidentalNotation {
+    let written = match &chord_info.chord_expression {
+        ChordExpression::Chord(chord) => chord.plain.as_str(),
+        ChordExpression::PolyChord(poly_chord) => poly_chord.upper.plain.as_str(),
+        _ => "",
+    };
+    let is_unicode = |s: &str| s.contains(['♯', '♭', '𝄪', '𝄫', '♮']);
+
+    if is_unicode(written) || chord_info.denominator.as_deref().is_some_and(is_unicode) {
+        AccidentalNotation::Unicode
+    } else {
+        AccidentalNotation::Ascii
+    }
+}
+
+/// the meta infos of the chord, whose keys are in the notation of the chord
+fn stringify_meta_infos(
+    meta_infos: &[ChordInfoMeta],
+    chord_info: &ChordInfo,
+    options: &StringifyOptions,
+) -> String {
+    let meta_options = StringifyOptions {
+        accidental_notation: Some(
+            options
+                .accidental_notation
+                .unwrap_or_else(|| written_notation(chord_info)),
+        ),
+    };
+
+    meta_infos
+        .iter()
+        .map(|meta_info| stringify_chord_info_meta_with_options(meta_info, &meta_options))
+        .collect::<Vec<String>>()
+        .join("")
+}
+
+pub fn stringify_chord_info_with_options(
+    chord_info: &ChordInfo,
+    options: &StringifyOptions,
+) -> String {
+    let meta_infos = stringify_meta_infos(&chord_info.meta_infos, chord_info, options);
+    let denominator = match &chord_info.denominator {
+        Some(denominator) => format!("/{}", notate(denominator, options)),
+        None => "".to_string(),
+    };
+
+    [
+        meta_infos,
+        stringify_chord_expression_with_options(&chord_info.chord_expression, options),
+        denominator,
+    ]
+    .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_chord_progression_string;
+    use trivia::collect_trivia;
+
+    #[test]
+    fn format_sections_and_bars() {
+        let input: &str = "
+@section=Intro
+[key=E]E-C#m(7)-Bm(7)-C#(7)
+F#m(7)-Am(7)-F#(7)-B
+
+@section=Verse
+@repeat=2
+E-C#m(7,9) - ?/C -%
+";
+        let ast = parse_chord_progression_string(input).unwrap();
+
+        assert_eq!(
+            stringify(&ast),
+            "@section=Intro
+[key=E]E - C#m(7) - Bm(7) - C#(7)
+F#m(7) - Am(7) - F#(7) - B
+
+@section=Verse
+@repeat=2
+E - C#m(7,9) - ?/C - %
+"
+        );
+    }
+
+    #[test]
+    fn comma_separated_chords() {
+        let ast = parse_chord_progression_string("C/E,F(add9),_ - [key=C]G").unwrap();
+
+        assert_eq!(stringify(&ast), "C/E,F(add9),_ - [key=C]G\n");
+    }
+
+    #[test]
+    fn poly_chords() {
+        let ast = parse_chord_progression_string("{D | C7}-{B♭m(7)|E♭}/G").unwrap();
+
+        assert_eq!(stringify(&ast), "{D|C7} - {B♭m(7)|E♭}/G\n");
+    }
+
+    #[test]
+    fn navigation_marks() {
+        let ast =
+            parse_chord_progression_string("<segno>|:C-F<1.>G:|<2.>Am\n<d.s. al coda>-<coda>C")
+                .unwrap();
+
+        assert_eq!(
+            stringify(&ast),
+            "<segno> |: C - F <1.> G :| <2.> Am\n<D.S. al Coda> <coda> C\n"
+        );
+    }
+
+    #[test]
+    fn section_reference_is_kept() {
+        let input = "@section=A\nC - F\n\n@section=A2\n@use=A:-2\n\n@section=B\nG\n";
+        let ast = parse_chord_progression_string(input).unwrap();
+
+        assert_eq!(stringify(&ast), input);
+        assert_eq!(
+            parse_chord_progression_string(&stringify(&ast)).unwrap(),
+            ast
+        );
+    }
+
+    #[test]
+    fn sections_without_section_meta() {
+        let ast = parse_chord_progression_string("C - F\n\nG - C").unwrap();
+
+        assert_eq!(stringify(&ast), "C - F\n\nG - C\n");
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(stringify(&vec![]), "");
+    }
+
+    #[test]
+    fn accidentals_are_kept_as_written_by_default() {
+        let ast = parse_chord_progression_string("[key=E♭]E♭ - C♯m(7)/G𝄪").unwrap();
+
+        assert_eq!(stringify(&ast), "[key=E♭]E♭ - C♯m(7)/G𝄪\n");
+
+        // the key follows the chord it is written on
+        let ast = parse_chord_progression_string("[key=E♭]C - [key=Bb]B♭").unwrap();
+        assert_eq!(stringify(&ast), "[key=Eb]C - [key=B♭]B♭\n");
+    }
+
+    #[test]
+    fn accidental_notation() {
+        let ast = parse_chord_progression_string("[key=Bb]Bb - C♯m(7)/Gbb - F♮").unwrap();
+
+        let ascii = StringifyOptions {
+            accidental_notation: Some(AccidentalNotation::Ascii),
+        };
+        assert_eq!(
+            stringify_with_options(&ast, &ascii),
+            "[key=Bb]Bb - C#m(7)/Gbb - F\n"
+        );
+
+        let unicode = StringifyOptions {
+            accidental_notation: Some(AccidentalNotation::Unicode),
+        };
+        assert_eq!(
+            stringify_with_options(&ast, &unicode),
+            "[key=B♭]B♭ - C♯m(7)/G𝄫 - F♮\n"
+        );
+    }
+
+    fn format(input: &str) -> String {
+        let ast = parse_chord_progression_string(input).unwrap();
+        let trivia = collect_trivia(input).unwrap();
+
+        stringify_with_trivia(&ast, &trivia, &StringifyOptions::default())
+    }
+
+    #[test]
+    fn comments_are_kept() {
+        let input = "// intro\n@section=A // the first\nC-? // lint-disable-line unknown-chord\n// lint-disable-next-line\nG\n// end of A\n\n// lint-disable\n@section=B\nF // last\n// end\n";
+
+        assert_eq!(
+            format(input),
+            "// intro\n@section=A // the first\nC - ? // lint-disable-line unknown-chord\n// lint-disable-next-line\nG\n// end of A\n\n// lint-disable\n@section=B\nF // last\n// end\n"
+        );
+        // the comments without the items around them
+        assert_eq!(format("// a\n\n// b\nC"), "// a\n\n// b\nC\n");
+        assert_eq!(format("// a\n// b\n\nC"), "// a\n// b\n\nC\n");
+        assert_eq!(format("// only"), "// only\n");
+        // "//" in a value is not a comment
+        assert_eq!(format("@section=A//B\nC"), "@section=A//B\nC\n");
+    }
+
+    #[test]
+    fn chord_macros_are_kept() {
+        let input = "@def X=F#m(b5,7,11) // half-diminished\n@def Y=C/E\n\n@section=A\n$X-[key=C]$Y,$X\n@def Z=G\n$Z";
+
+        assert_eq!(
+            format(input),
+            "@def X=F#m(b5,7,11) // half-diminished\n@def Y=C/E\n\n@section=A\n$X - [key=C]$Y,$X\n@def Z=G\n$Z\n"
+        );
+        // the definition is written wherever it is, like the comments
+        assert_eq!(format("C\n\n@def X=D\n$X"), "C\n\n@def X=D\n$X\n");
+        assert_eq!(format("C\n@def X=D\n\n$X"), "C\n@def X=D\n\n$X\n");
+
+        // the chord changed from the definition is written as it is
+        let ast = parse_chord_progression_string(input).unwrap();
+        let mut trivia = collect_trivia(input).unwrap();
+        trivia.lines.retain(|(_, line)| match line {
+            TriviaLine::Definition(definition) => definition.name != "X",
+            TriviaLine::Comment(_) => true,
+        });
+        assert_eq!(
+            stringify_with_trivia(&ast, &trivia, &StringifyOptions::default()),
+            "@def Y=C/E\n\n@section=A\nF#m(b5,7,11) - [key=C]$Y,F#m(b5,7,11)\n@def Z=G\n$Z\n"
+        );
+    }
+
+    #[test]
+    fn formatting_with_comments_is_stable() {
+        const PIECES: [&str; 22] = [
+            "@section=A\n",
+            "@repeat=2\n",
+            "C",
+            "G7(9)",
+            "[key=D]Am",
+            "{D|C}",
+            " - ",
+            ",",
+            "<segno>",
+            "|:",
+            ":|",
+            "%",
+            "\n",
+            "\n\n",
+            "// own\n",
+            " // trailing\n",
+            "\n// after\n\n",
+            "   ",
+            "\n@def X=[key=E]B/F#\n",
+            "\n@def Y=Am // macro\n",
+            "$X",
+            "[key=C]$Y",
+        ];
+
+        // pseudo random inputs of the pieces, some of which are valid
+        let mut seed: u32 = 1;
+        let mut random = |max: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as usize % max
+        };
+        for _ in 0..20000 {
+            let input: String = (0..random(12))
+                .map(|_| PIECES[random(PIECES.len())])
+                .collect();
+            let Ok(ast) = parse_chord_progression_string(&input) else {
+                continue;
+            };
+            let trivia = collect_trivia(&input).unwrap();
+            let formatted = format(&input);
+
+            // the comments and the definitions are written between the lines written without them
+            // (with the blank line after them at the start), and the references are expanded in the AST
+            let lines_without_comments: String = formatted
+                .lines()
+                .filter(|line| !line.starts_with("//") && !line.starts_with("@def"))
+                .map(|line| match line.find(" //") {
+                    Some(index) => format!("{}\n", &line[..index]),
+                    None => format!("{}\n", line),
+                })
+                .collect();
+            let formatted_trivia = collect_trivia(&formatted).unwrap();
+            assert_eq!(
+                parse_chord_progression_string(
+                    lines_without_comments
+                        .replace("$X", "[key=E]B/F#")
+                        .replace("$Y", "Am")
+                        .trim_start()
+                ),
+                parse_chord_progression_string(stringify(&ast).trim_start()),
+                "{:?}",
+                input
+            );
+            let lines = |trivia: &Trivia| {
+                trivia
+                    .lines
+                    .iter()
+                    .map(|(_, line)| line.clone())
+                    .collect::<Vec<TriviaLine>>()
+            };
+            assert_eq!(
+                (
+                    lines(&formatted_trivia),
+                    formatted_trivia.trailing_comments.len(),
+                    &formatted_trivia.references
+                ),
+                (
+                    lines(&trivia),
+                    trivia.trailing_comments.len(),
+                    &trivia.references
+                ),
+                "{:?}",
+                input
+            );
+
+            // the formatted input is formatted, unless the AST is not kept without the comments
+            // (like the empty section before a blank line at the start)
+            if parse_chord_progression_string(&stringify(&ast)) == Ok(ast) {
+                assert_eq!(format(&formatted), formatted, "{:?}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn reparse_result_is_same() {
+        let input: &str = "
+@section=Chorus
+[key=C]C-C(7)-FM(7)-Fm(7)
+C-C(7)-FM(7)-Dm(7)
+Em(7)-E(7)
+
+@section=Interlude
+C-A,B
+
+[key=C]C(M9)-CM(9)
+";
+        let ast = parse_chord_progression_string(input).unwrap();
+
+        assert_eq!(
+            parse_chord_progression_string(&stringify(&ast)).unwrap(),
+            ast
+        );
+    }
+}
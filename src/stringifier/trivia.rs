@@ -0,0 +1,317 @@
+use std::cmp::Ordering;
+
+use crate::error_code::ErrorInfoWithPosition;
+use crate::parse_chord_progression_string;
+use crate::parser::types::chord_block::ChordBlock;
+use crate::parser::types::chord_info::ChordInfo;
+use crate::parser::types::section::Section;
+use crate::parser::types::section_meta::SectionMeta;
+use crate::tokenizer::lexer::Lexer;
+use crate::tokenizer::types::lexeme::Lexeme;
+use crate::tokenizer::types::token::TokenKind;
+
+/// Where a line of the trivia is written, by the index of an item.
+/// The items are the section meta infos, the chords and the navigation marks, in the order of the input.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum Anchor {
+    /// at the start, followed by a blank line
+    Header,
+    /// before the line of the item (or at the end, for the index after the last item)
+    Before(usize),
+    /// after the line of the item, before the blank line following it
+    After(usize),
+}
+
+impl Anchor {
+    /// order of the places in the output
+    fn order(&self) -> usize {
+        match self {
+            Anchor::Header => 0,
+            Anchor::Before(index) => 2 * index + 1,
+            Anchor::After(index) => 2 * index + 2,
+        }
+    }
+}
+
+/// Line of the trivia
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) enum TriviaLine {
+    /// comment on its own line
+    Comment(String),
+    /// definition of the chord macro (like `@def X=C/E`)
+    Definition(Definition),
+}
+
+/// Definition of the chord macro, like `@def X=C/E // slash`
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Definition {
+    pub name: String,
+    /// the chord the references are expanded to
+    pub value: ChordInfo,
+    /// comment at the end of the line
+    pub comment: Option<String>,
+}
+
+/// What the AST doesn't keep of the input, which [`stringify_with_trivia`](super::stringify_with_trivia) writes back:
+/// the comments (like `// intro` and `// lint-disable-line unknown-chord`)
+/// and the chord macros (like `@def X=F#m(b5,7,11)` and `$X`)
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Trivia {
+    /// comments and definitions on their own lines, in the order of the input
+    pub(crate) lines: Vec<(Anchor, TriviaLine)>,
+    /// comments at the end of the lines, by the index of the last item before them
+    pub(crate) trailing_comments: Vec<(usize, String)>,
+    /// names of the chord macros, by the index of the chord referring to them
+    pub(crate) references: Vec<(usize, String)>,
+    /// number of the items of the input
+    pub(crate) items: usize,
+}
+
+impl Trivia {
+    /// definition of the chord macro named `name`
+    pub(crate) fn definition(&self, name: &str) -> Option<&Definition> {
+        self.lines.iter().find_map(|(_, line)| match line {
+            TriviaLine::Definition(definition) if definition.name == name => Some(definition),
+            _ => None,
+        })
+    }
+
+    /// name of the chord macro the chord at `item` refers to
+    pub(crate) fn reference(&self, item: usize) -> Option<&str> {
+        self.references
+            .binary_search_by_key(&item, |(index, _)| *index)
+            .ok()
+            .map(|index| self.references[index].1.as_str())
+    }
+
+    /// Replace the item at `index` (like the section meta info `@include=intro.txt`) with the items of `trivia`.
+    /// The comments around the item are kept around the items replacing it.
+    /// The chord macros of `trivia` are not kept, since they are of its own file, and their chords are written instead.
+    pub(crate) fn replace_item(&mut self, index: usize, trivia: Trivia) {
+        let shift = |item: usize| item + trivia.items - 1;
+        // the comments after the item are after the items replacing it
+        let after_items = match trivia.items {
+            0 => Anchor::Before(index),
+            items => Anchor::After(index + items - 1),
+        };
+
+        for (anchor, _) in self.lines.iter_mut() {
+            *anchor = match *anchor {
+                Anchor::After(item) if item == index => after_items,
+                Anchor::Before(item) if item > index => Anchor::Before(shift(item)),
+                Anchor::After(item) if item > index => Anchor::After(shift(item)),
+                anchor => anchor,
+            };
+        }
+        let mut trailing_comments = vec![];
+        for (item, comment) in std::mem::take(&mut self.trailing_comments) {
+            match item.cmp(&index) {
+                Ordering::Less => trailing_comments.push((item, comment)),
+                Ordering::Equal => self.lines.push((after_items, TriviaLine::Comment(comment))),
+                Ordering::Greater => trailing_comments.push((shift(item), comment)),
+            }
+        }
+
+        self.lines
+            .extend(trivia.lines.into_iter().filter_map(|(anchor, line)| {
+                let anchor = match anchor {
+                    Anchor::Header => Anchor::Before(index),
+                    Anchor::Before(item) => Anchor::Before(index + item),
+                    Anchor::After(item) => Anchor::After(index + item),
+                };
+                let comment = match line {
+                    TriviaLine::Comment(comment) => comment,
+                    TriviaLine::Definition(definition) => definition.comment?,
+                };
+                Some((anchor, TriviaLine::Comment(comment)))
+            }));
+        trailing_comments.extend(
+            trivia
+                .trailing_comments
+                .into_iter()
+                .map(|(item, comment)| (index + item, comment)),
+        );
+        for (item, _) in self.references.iter_mut() {
+            if *item > index {
+                *item = shift(*item);
+            }
+        }
+        // stable, so the lines at the same place keep their order
+        self.lines.sort_by_key(|(anchor, _)| anchor.order());
+        trailing_comments.sort_by_key(|(item, _)| *item);
+        self.trailing_comments = trailing_comments;
+        self.items = shift(self.items);
+    }
+}
+
+/// whether the token is an item, which the AST keeps
+fn is_item(lexeme: &Lexeme) -> bool {
+    matches!(
+        lexeme.kind,
+        TokenKind::SectionMetaInfoStart
+            | TokenKind::Chord
+            | TokenKind::NavigationMark
+            | TokenKind::RepeatStart
+            | TokenKind::RepeatEnd
+    )
+}
+
+/// number of the items of the section written by [`stringify`](super::stringify)
+pub(crate) fn section_items(section: &Section) -> usize {
+    let is_reference = section
+        .meta_infos
+        .iter()
+        .any(|meta_info| matches!(meta_info, SectionMeta::Use(_)));
+    if is_reference {
+        return section.meta_infos.len();
+    }
+
+    section.meta_infos.len()
+        + section
+            .chord_blocks
+            .iter()
+            .map(|chord_block| match chord_block {
+                ChordBlock::Bar(bar) => bar.len(),
+                ChordBlock::Navigation(_) => 1,
+                ChordBlock::Br => 0,
+            })
+            .sum::<usize>()
+}
+
+/// the chord of the value of the definition (like `F#m(b5,7,11)`)
+fn definition_value(value: &str) -> Option<ChordInfo> {
+    let ast = parse_chord_progression_string(value).ok()?;
+    match ast.first()?.chord_blocks.first()? {
+        ChordBlock::Bar(bar) => bar.first().cloned(),
+        _ => None,
+    }
+}
+
+/// Collect the trivia of the input, which should be parsed without errors.
+/// A comment at the end of a line stays at the end of the line of the item before it,
+/// and a comment or a definition on its own line stays before the line of the item after it
+/// (or after the line of the item before it, when a blank line follows it).
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::parse_chord_progression_string;
+/// use chord_progression_parser::stringifier::stringify_with_trivia;
+/// use chord_progression_parser::stringifier::trivia::collect_trivia;
+///
+/// let input = "// intro\n@def X=F#m(b5,7,11)\n@section=A\n$X-? // lint-disable-line unknown-chord";
+/// let ast = parse_chord_progression_string(input).unwrap();
+/// let trivia = collect_trivia(input).unwrap();
+/// assert_eq!(
+///     stringify_with_trivia(&ast, &trivia, &Default::default()),
+///     "// intro\n@def X=F#m(b5,7,11)\n@section=A\n$X - ? // lint-disable-line unknown-chord\n"
+/// );
+/// ```
+pub fn collect_trivia(input: &str) -> Result<Trivia, ErrorInfoWithPosition> {
+    let mut lexer = Lexer::new(input);
+    let lexemes = lexer.by_ref().collect::<Result<Vec<Lexeme>, _>>()?;
+    let mut comments = lexer.comments().iter().peekable();
+
+    let mut trivia = Trivia::default();
+    // line number of the last item or definition
+    let mut item_line_number: Option<usize> = None;
+    let mut definition_line_number: Option<usize> = None;
+    // the line after a line of only a comment or a definition is blank, if its first token is a line break
+    let is_blank_line_at = |index: usize, line_number: usize| {
+        lexemes.get(index).is_some_and(|lexeme| {
+            lexeme.kind == TokenKind::LineBreak && lexeme.line_number > line_number
+        })
+    };
+    let anchor = |is_blank_line_next: bool, items: usize| match (is_blank_line_next, items) {
+        (false, items) => Anchor::Before(items),
+        (true, 0) => Anchor::Header,
+        (true, items) => Anchor::After(items - 1),
+    };
+    // the lines before the line followed by a blank line are also before the blank line
+    let push_line = |trivia: &mut Trivia, anchor: Anchor, line: TriviaLine| {
+        if anchor != Anchor::Before(trivia.items) {
+            let before = Anchor::Before(trivia.items);
+            for (previous, _) in trivia.lines.iter_mut().rev() {
+                if *previous != before {
+                    break;
+                }
+                *previous = anchor;
+            }
+        }
+        trivia.lines.push((anchor, line));
+    };
+
+    let mut index = 0;
+    loop {
+        let lexeme = lexemes.get(index);
+        if let Some(comment) = comments
+            .next_if(|comment| lexeme.is_none_or(|lexeme| comment.span.start < lexeme.span.start))
+        {
+            let text = comment.text.to_string();
+            if item_line_number == Some(comment.line_number) {
+                trivia.trailing_comments.push((trivia.items - 1, text));
+            } else if let (Some(line_number), Some((_, TriviaLine::Definition(definition)))) =
+                (definition_line_number, trivia.lines.last_mut())
+            {
+                if line_number == comment.line_number {
+                    definition.comment = Some(text);
+                } else {
+                    let anchor = anchor(is_blank_line_at(index, comment.line_number), trivia.items);
+                    push_line(&mut trivia, anchor, TriviaLine::Comment(text));
+                }
+            } else {
+                // the line break of the line of only a comment is skipped
+                let anchor = anchor(is_blank_line_at(index, comment.line_number), trivia.items);
+                push_line(&mut trivia, anchor, TriviaLine::Comment(text));
+            }
+            continue;
+        }
+        let Some(lexeme) = lexeme else {
+            break;
+        };
+
+        // the tokens of the definition are `@`, `def`, the name, `=` and the value
+        let definition = match lexemes.get(index..index + 5) {
+            Some([start, key, name, _, value])
+                if start.kind == TokenKind::SectionMetaInfoStart
+                    && key.kind == TokenKind::SectionMetaInfoKey
+                    && key.text == "def" =>
+            {
+                Some((name.text, value.text))
+            }
+            _ => None,
+        };
+        if let Some((name, value)) = definition {
+            index += 5;
+            let Some(value) = definition_value(value) else {
+                continue;
+            };
+            // the line break after the definition is removed with it
+            let anchor = anchor(
+                is_blank_line_at(index + 1, lexeme.line_number),
+                trivia.items,
+            );
+            push_line(
+                &mut trivia,
+                anchor,
+                TriviaLine::Definition(Definition {
+                    name: name.to_string(),
+                    value,
+                    comment: None,
+                }),
+            );
+            definition_line_number = Some(lexeme.line_number);
+            continue;
+        }
+
+        if is_item(lexeme) {
+            if let Some(name) = lexeme.text.strip_prefix('$') {
+                trivia.references.push((trivia.items, name.to_string()));
+            }
+            trivia.items += 1;
+            item_line_number = Some(lexeme.line_number);
+        }
+        index += 1;
+    }
+
+    Ok(trivia)
+}
@@ -0,0 +1,298 @@
+use std::str::FromStr;
+
+use strum::{IntoEnumIterator, VariantNames};
+
+use crate::error_code::{ErrorCode, ErrorInfo};
+use crate::parser::types::accidental::{Accidental, AccidentalNotation};
+use crate::parser::types::base::Base;
+use crate::parser::types::extension::Extension;
+use crate::parser::types::key::Key;
+
+/// max number of suggestions for one error
+const MAX_SUGGESTIONS: usize = 3;
+
+/// commonly used spellings of extensions which are not accepted as aliases, and the canonical ones
+const EXTENSION_ALIASES: [(&str, &str); 2] = [("dom7", "7"), ("m7b5", "o")];
+
+/// commonly used spellings of chord qualities and the canonical ones.
+/// longer ones come first so that "min7" is not split as "m" + "in7".
+const CHORD_TYPE_ALIASES: [(&str, &str); 12] = [
+    ("min", "m"),
+    ("maj", ""),
+    ("Maj", ""),
+    ("aug", "aug"),
+    ("dim", "dim"),
+    ("mi", "m"),
+    ("m", "m"),
+    ("M", ""),
+    ("-", "m"),
+    ("+", "aug"),
+    ("°", "dim"),
+    ("o", "dim"),
+];
+
+/// Suggestions to fix the error, ranked from the most probable one
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::parse_chord_progression_string;
+/// use chord_progression_parser::suggestion::suggestions;
+///
+/// let error_info = parse_chord_progression_string("C(dom7)").unwrap_err();
+/// assert_eq!(suggestions(&error_info.error), vec!["7"]);
+/// ```
+pub fn suggestions(error_info: &ErrorInfo) -> Vec<String> {
+    let additional_info = match &error_info.additional_info {
+        Some(additional_info) => additional_info.as_str(),
+        None => return vec![],
+    };
+
+    match error_info.code {
+        ErrorCode::Ext1 => suggest_extensions(additional_info),
+        ErrorCode::Cimv4 => suggest_keys(additional_info),
+        ErrorCode::Cho1 => suggest_chords(additional_info),
+        _ => vec![],
+    }
+}
+
+/// Levenshtein distance between two strings (counted by chars)
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b_chars.len()]
+}
+
+/// rank candidates by edit distance. candidates which are too far are dropped.
+fn rank_by_edit_distance(input: &str, candidates: &[&str]) -> Vec<String> {
+    let max_distance = (input.chars().count() / 2).clamp(1, 2);
+
+    let mut ranked: Vec<(usize, usize, &str)> = candidates
+        .iter()
+        .enumerate()
+        .map(|(index, candidate)| (edit_distance(input, candidate), index, *candidate))
+        .filter(|(distance, _, _)| *distance <= max_distance)
+        .collect();
+    ranked.sort();
+
+    ranked
+        .into_iter()
+        .map(|(_, _, candidate)| candidate.to_string())
+        .collect()
+}
+
+fn push_unique(suggestions: &mut Vec<String>, suggestion: String) {
+    if !suggestions.contains(&suggestion) && suggestions.len() < MAX_SUGGESTIONS {
+        suggestions.push(suggestion);
+    }
+}
+
+/// e.g. "maj7" -> ["M7"], "#10" -> ["#11", "#13", ...]
+pub fn suggest_extensions(input: &str) -> Vec<String> {
+    let mut suggestions: Vec<String> = vec![];
+
+    for (alias, canonical) in EXTENSION_ALIASES.iter() {
+        if alias.eq_ignore_ascii_case(input) {
+            push_unique(&mut suggestions, canonical.to_string());
+        }
+    }
+    for (spelling, extension) in Extension::spellings() {
+        if spelling.eq_ignore_ascii_case(input) {
+            push_unique(&mut suggestions, extension.to_string());
+        }
+    }
+
+    // the same extension written in other notation is surely what is meant
+    if !suggestions.is_empty() {
+        return suggestions;
+    }
+
+    for candidate in rank_by_edit_distance(input, Extension::VARIANTS) {
+        push_unique(&mut suggestions, candidate);
+    }
+
+    suggestions
+}
+
+/// e.g. "Hm" -> ["Bm"], "Cmaj" -> ["C"], "Fminor" -> ["Fm"]
+pub fn suggest_keys(input: &str) -> Vec<String> {
+    let mut suggestions: Vec<String> = vec![];
+
+    if let Some(normalized) = normalize_key(input) {
+        if Key::from_str(&normalized).is_ok() {
+            push_unique(&mut suggestions, normalized);
+        }
+    }
+
+    // major and minor keys of all tonics
+    let keys: Vec<String> = Base::iter()
+        .flat_map(|tonic| {
+            [None, Some(Accidental::Sharp), Some(Accidental::Flat)]
+                .into_iter()
+                .flat_map(move |accidental| {
+                    [
+                        Key::major(tonic.clone(), accidental.clone()),
+                        Key::minor(tonic.clone(), accidental),
+                    ]
+                })
+        })
+        .map(|key| key.to_string())
+        .collect();
+    let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+
+    for candidate in rank_by_edit_distance(input, &keys) {
+        push_unique(&mut suggestions, candidate);
+    }
+
+    suggestions
+}
+
+/// e.g. "H7" -> ["B(7)"], "Cmaj7" -> ["C(M7)"], "Cm7" -> ["Cm(7)"]
+pub fn suggest_chords(input: &str) -> Vec<String> {
+    let (root, rest) = match split_root(input) {
+        Some(split) => split,
+        None => return vec![],
+    };
+
+    // the whole rest is an extension written without parenthesis, like "Cmaj7"
+    if let Some(extension) = canonical_extension(rest) {
+        return vec![format!("{}({})", root, extension)];
+    }
+
+    // the quality is written like "min", "-", "+"
+    for (alias, chord_type) in CHORD_TYPE_ALIASES.iter() {
+        let extensions = match rest.strip_prefix(alias) {
+            Some(extensions) => extensions,
+            None => continue,
+        };
+
+        if extensions.is_empty() {
+            return vec![format!("{}{}", root, chord_type)];
+        }
+        if let Some(extension) = canonical_extension(extensions) {
+            return vec![format!("{}{}({})", root, chord_type, extension)];
+        }
+    }
+
+    if rest.is_empty() {
+        return vec![root];
+    }
+
+    vec![]
+}
+
+/// canonical form of one extension written with or without parenthesis
+fn canonical_extension(input: &str) -> Option<String> {
+    let input = input.trim_start_matches('(').trim_end_matches(')');
+
+    if let Ok(extension) = Extension::from_str(input) {
+        return Some(extension.to_string());
+    }
+
+    EXTENSION_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == input)
+        .map(|(_, canonical)| canonical.to_string())
+}
+
+/// split the root note ("H" and lower case are corrected) and the rest
+fn split_root(input: &str) -> Option<(String, &str)> {
+    let mut chars = input.chars();
+    let base = match chars.next()? {
+        'H' | 'h' => 'B',
+        c if ('A'..='G').contains(&c.to_ascii_uppercase()) => c.to_ascii_uppercase(),
+        _ => return None,
+    };
+    let rest = chars.as_str();
+
+    let (accidental, rest) = Accidental::split_prefix(rest);
+    let accidental = accidental.map_or("", |accidental| {
+        accidental.to_string_with(AccidentalNotation::Ascii)
+    });
+
+    Some((format!("{}{}", base, accidental), rest))
+}
+
+/// normalize the key written like "Hm", "cmaj", "F#minor"
+fn normalize_key(input: &str) -> Option<String> {
+    let (root, rest) = split_root(input)?;
+
+    let mode = match rest {
+        "" | "M" | "maj" | "major" | "Maj" | "Major" => "",
+        "m" | "min" | "minor" | "Min" | "Minor" | "-" => "m",
+        _ => return None,
+    };
+
+    Some(format!("{}{}", root, mode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_chord_progression_string;
+
+    #[test]
+    fn edit_distance_of_strings() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("sus4", "sus2"), 1);
+        assert_eq!(edit_distance("add9", "ad9"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("Δ7", "M7"), 1);
+    }
+
+    #[test]
+    fn extension_alias_is_first() {
+        assert_eq!(suggest_extensions("maj7"), vec!["M7"]);
+        assert_eq!(suggest_extensions("MAJ7"), vec!["M7"]);
+        assert_eq!(suggest_extensions("dom7"), vec!["7"]);
+        assert_eq!(suggest_extensions("ADD9"), vec!["add9"]);
+    }
+
+    #[test]
+    fn extension_typo() {
+        assert_eq!(suggest_extensions("sus"), vec!["sus4"]);
+        assert_eq!(suggest_extensions("sus3"), vec!["sus2", "sus4"]);
+        assert_eq!(suggest_extensions("ad9"), vec!["add9"]);
+        assert_eq!(suggest_extensions("zzzzzz"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn key_alias_is_first() {
+        assert_eq!(suggest_keys("Hm")[0], "Bm");
+        assert_eq!(suggest_keys("H")[0], "B");
+        assert_eq!(suggest_keys("F#minor"), vec!["F#m"]);
+        assert_eq!(suggest_keys("cmaj")[0], "C");
+    }
+
+    #[test]
+    fn chord_written_in_other_notation() {
+        assert_eq!(suggest_chords("Cmaj7"), vec!["C(M7)"]);
+        assert_eq!(suggest_chords("Cm7"), vec!["Cm(7)"]);
+        assert_eq!(suggest_chords("C-7"), vec!["Cm(7)"]);
+        assert_eq!(suggest_chords("Hmin"), vec!["Bm"]);
+        assert_eq!(suggest_chords("h7"), vec!["B(7)"]);
+        assert_eq!(suggest_chords("?"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn suggestions_of_parse_error() {
+        let error_info = parse_chord_progression_string("C(dom7)").unwrap_err();
+        assert_eq!(suggestions(&error_info.error), vec!["7"]);
+
+        let error_info = parse_chord_progression_string("[key=Hm]C").unwrap_err();
+        assert_eq!(error_info.error.code, ErrorCode::Cimv4);
+        assert_eq!(suggestions(&error_info.error)[0], "Bm");
+
+        let error_info = parse_chord_progression_string("Hm7").unwrap_err();
+        assert_eq!(suggestions(&error_info.error), vec!["Bm(7)"]);
+    }
+}
@@ -0,0 +1,307 @@
+use crate::parser::types::ast::Ast;
+use crate::parser::types::bar::Bar;
+use crate::parser::types::chord_block::ChordBlock;
+use crate::parser::types::navigation_mark::NavigationMark;
+use crate::parser::types::section_meta::SectionMeta;
+use crate::parser::types::time_signature::TimeSignature;
+
+#[derive(Debug, PartialEq, Clone)]
+enum Item<'a> {
+    /// bar and its time signature
+    Bar(&'a Bar, TimeSignature),
+    Mark(&'a NavigationMark),
+}
+
+/// Bars in the order they are played
+///
+/// A section with `@repeat=N` is played N times, and the navigation marks are followed:
+/// - `|: ... :|` is played twice, or as many times as the largest volta number in it.
+/// - `<1.>`, `<2.>` ... are played only in the passes of the numbers.
+/// - `<D.C.>` and `<D.S.>` jump back to the beginning or `<segno>` once.
+///   After the jump, repeats are not taken and only the last volta is played.
+/// - `<fine>` ends the play after `al Fine`, and `<to coda>` jumps to `<coda>` after `al Coda`.
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::parse_chord_progression_string;
+/// use chord_progression_parser::stringifier::stringify_chord_info;
+/// use chord_progression_parser::timeline::played_bars;
+///
+/// let ast = parse_chord_progression_string("|: C <1.> F :| <2.> G").unwrap();
+/// let chords: Vec<String> = played_bars(&ast)
+///     .iter()
+///     .map(|bar| stringify_chord_info(&bar[0]))
+///     .collect();
+/// assert_eq!(chords, ["C", "F", "C", "G"]);
+/// ```
+pub fn played_bars(ast: &Ast) -> Vec<&Bar> {
+    played_bars_with_time_signature(ast)
+        .into_iter()
+        .map(|(bar, _)| bar)
+        .collect()
+}
+
+/// Bars in the order they are played (see [`played_bars`]), with the time signature of each bar.
+/// The time signature written like `[time=3/4]` is carried forward to the following bars as written, and is 4/4 until then.
+pub fn played_bars_with_time_signature(ast: &Ast) -> Vec<(&Bar, TimeSignature)> {
+    let items = items(ast);
+    let mut bars: Vec<(&Bar, TimeSignature)> = vec![];
+
+    let mut index = 0;
+    let mut repeat_start = 0;
+    let mut pass = 1;
+    // D.C. or D.S. already taken
+    let mut jump: Option<&NavigationMark> = None;
+    // every item is played at most once in each pass, before and after the jump,
+    // so the marks not validated by the parser cannot loop forever
+    let max_steps = items.len() * (max_repeat_times(&items) as usize + 1) * 2;
+    let mut steps = 0;
+
+    while let Some(item) = items.get(index) {
+        index += 1;
+        steps += 1;
+        if steps > max_steps {
+            break;
+        }
+
+        let mark = match item {
+            Item::Bar(bar, time_signature) => {
+                bars.push((bar, time_signature.clone()));
+                continue;
+            }
+            Item::Mark(mark) => *mark,
+        };
+
+        match mark {
+            NavigationMark::RepeatStart => {
+                repeat_start = index;
+                pass = 1;
+            }
+            NavigationMark::RepeatEnd => {
+                if jump.is_none() && pass < repeat_times(&items, repeat_start) {
+                    pass += 1;
+                    index = repeat_start;
+                }
+            }
+            NavigationMark::Volta(numbers) => {
+                let is_skipped = match jump {
+                    None => !numbers.contains(&pass),
+                    Some(_) => is_volta_repeated(&items, index),
+                };
+                if is_skipped {
+                    index = next_volta(&items, index);
+                }
+            }
+            NavigationMark::Fine => {
+                if matches!(
+                    jump,
+                    Some(NavigationMark::DaCapoAlFine | NavigationMark::DalSegnoAlFine)
+                ) {
+                    break;
+                }
+            }
+            NavigationMark::ToCoda => {
+                if matches!(
+                    jump,
+                    Some(NavigationMark::DaCapoAlCoda | NavigationMark::DalSegnoAlCoda)
+                ) {
+                    match find_from(&items, index, &NavigationMark::Coda) {
+                        Some(coda) => index = coda + 1,
+                        None => break,
+                    }
+                }
+            }
+            NavigationMark::DaCapo
+            | NavigationMark::DaCapoAlFine
+            | NavigationMark::DaCapoAlCoda => {
+                if jump.is_none() {
+                    jump = Some(mark);
+                    index = 0;
+                }
+            }
+            NavigationMark::DalSegno
+            | NavigationMark::DalSegnoAlFine
+            | NavigationMark::DalSegnoAlCoda => {
+                if jump.is_none() {
+                    jump = Some(mark);
+                    index =
+                        find_from(&items, 0, &NavigationMark::Segno).map_or(0, |segno| segno + 1);
+                }
+            }
+            NavigationMark::Segno | NavigationMark::Coda => {}
+        }
+    }
+
+    bars
+}
+
+/// bars and navigation marks of all the sections, with `@repeat` expanded
+fn items(ast: &Ast) -> Vec<Item<'_>> {
+    let mut items: Vec<Item> = vec![];
+    let mut time_signature = TimeSignature::common();
+
+    for section in ast.iter() {
+        let repeat = section
+            .meta_infos
+            .iter()
+            .find_map(|meta_info| match meta_info {
+                SectionMeta::Repeat(count) => Some(*count),
+                _ => None,
+            })
+            .unwrap_or(1);
+
+        for _ in 0..repeat {
+            for chord_block in section.chord_blocks.iter() {
+                match chord_block {
+                    ChordBlock::Bar(bar) => {
+                        if let Some(written) = TimeSignature::of_bar(bar) {
+                            time_signature = written.clone();
+                        }
+                        items.push(Item::Bar(bar, time_signature.clone()));
+                    }
+                    ChordBlock::Navigation(mark) => items.push(Item::Mark(mark)),
+                    ChordBlock::Br => {}
+                }
+            }
+        }
+    }
+
+    items
+}
+
+/// index of the first `target` at or after `start`
+fn find_from(items: &[Item], start: usize, target: &NavigationMark) -> Option<usize> {
+    items[start..]
+        .iter()
+        .position(|item| *item == Item::Mark(target))
+        .map(|position| start + position)
+}
+
+/// how many times the repeat starting at `start` is played: the largest volta number in it, or 2
+fn repeat_times(items: &[Item], start: usize) -> u32 {
+    items[start..]
+        .iter()
+        .take_while(|item| **item != Item::Mark(&NavigationMark::RepeatStart))
+        .filter_map(|item| match item {
+            Item::Mark(NavigationMark::Volta(numbers)) => numbers.iter().max().copied(),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(2)
+        .max(2)
+}
+
+/// the largest [`repeat_times`] of all the repeats
+fn max_repeat_times(items: &[Item]) -> u32 {
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| **item == Item::Mark(&NavigationMark::RepeatStart))
+        .map(|(index, _)| repeat_times(items, index + 1))
+        .max()
+        .unwrap_or(1)
+}
+
+/// index of the next volta (or the next repeat start) after `index`
+fn next_volta(items: &[Item], index: usize) -> usize {
+    items[index..]
+        .iter()
+        .position(|item| {
+            matches!(
+                item,
+                Item::Mark(NavigationMark::Volta(_) | NavigationMark::RepeatStart)
+            )
+        })
+        .map_or(items.len(), |position| index + position)
+}
+
+/// whether the volta ending at `:|` (not the last ending) starts at `index`
+fn is_volta_repeated(items: &[Item], index: usize) -> bool {
+    items[index..next_volta(items, index)].contains(&Item::Mark(&NavigationMark::RepeatEnd))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_code::ErrorCode;
+    use crate::parse_chord_progression_string;
+    use crate::stringifier::stringify_chord_info;
+
+    fn played_chords(input: &str) -> Vec<String> {
+        let ast = parse_chord_progression_string(input).unwrap();
+
+        played_bars(&ast)
+            .iter()
+            .map(|bar| stringify_chord_info(&bar[0]))
+            .collect()
+    }
+
+    #[test]
+    fn without_navigation_marks() {
+        assert_eq!(
+            played_chords("@repeat=2\nC - G\n\nF"),
+            ["C", "G", "C", "G", "F"]
+        );
+    }
+
+    #[test]
+    fn repeat() {
+        assert_eq!(
+            played_chords("C |: F - G :| Am"),
+            ["C", "F", "G", "F", "G", "Am"]
+        );
+    }
+
+    #[test]
+    fn voltas() {
+        assert_eq!(
+            played_chords("|: C <1.> F :| <2.> G - Am"),
+            ["C", "F", "C", "G", "Am"]
+        );
+        assert_eq!(
+            played_chords("|: C <1.2.> F :| <3.> G"),
+            ["C", "F", "C", "F", "C", "G"]
+        );
+    }
+
+    #[test]
+    fn da_capo_al_fine() {
+        assert_eq!(
+            played_chords("C - F <fine> G <D.C. al Fine>"),
+            ["C", "F", "G", "C", "F"]
+        );
+    }
+
+    #[test]
+    fn dal_segno_al_coda() {
+        assert_eq!(
+            played_chords("C <segno> F <to coda> G <D.S. al Coda> <coda> Am"),
+            ["C", "F", "G", "F", "Am"]
+        );
+    }
+
+    #[test]
+    fn coda_before_to_coda_is_not_jumped_back_to() {
+        let error =
+            parse_chord_progression_string("<coda> C - <to coda> D - <D.C. al Coda>").unwrap_err();
+        assert_eq!(error.error.code, ErrorCode::Nav6);
+
+        // built without the validation of the parser
+        let mut ast =
+            parse_chord_progression_string("<coda> C - <segno> D - <D.C. al Coda>").unwrap();
+        ast[0].chord_blocks[2] = ChordBlock::Navigation(NavigationMark::ToCoda);
+        let chords: Vec<String> = played_bars(&ast)
+            .iter()
+            .map(|bar| stringify_chord_info(&bar[0]))
+            .collect();
+        assert_eq!(chords, ["C", "D", "C"]);
+    }
+
+    #[test]
+    fn repeats_are_not_taken_after_jump() {
+        assert_eq!(
+            played_chords("|: C <1.> F :| <2.> G <D.C.>"),
+            ["C", "F", "C", "G", "C", "G"]
+        );
+    }
+}
@@ -0,0 +1,527 @@
+use crate::error_code::{ErrorCode, ErrorInfo, ErrorInfoWithPosition};
+use crate::util::position::Position;
+
+use super::types::comment::Comment;
+use super::types::lexeme::Lexeme;
+use super::types::token::TokenKind;
+use super::util::{
+    is_chord_info_end_char, is_minor_dash, is_navigation_mark_start, is_six_nine_slash,
+    is_token_char,
+};
+
+fn is_white_space(ch: char) -> bool {
+    matches!(ch, ' ' | '　' | '\t')
+}
+
+fn error_at(
+    code: ErrorCode,
+    additional_info: Option<String>,
+    line_number: usize,
+    column_number: usize,
+    length: usize,
+) -> ErrorInfoWithPosition {
+    ErrorInfoWithPosition {
+        error: ErrorInfo {
+            code,
+            additional_info,
+            cause: None,
+        },
+        position: Position {
+            line_number,
+            column_number,
+            length,
+        },
+    }
+}
+
+/// Lexer of the chord progression string, which yields the tokens one by one, borrowing their texts from the input.
+/// It stops after the first error, which is the same as the one of [`tokenize`](super::tokenize).
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::tokenizer::lexer::Lexer;
+/// use chord_progression_parser::tokenizer::types::token::TokenKind;
+///
+/// let lexemes = Lexer::new("C7-F/A").collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(lexemes[0].kind, TokenKind::Chord);
+/// assert_eq!(lexemes[0].text, "C7");
+/// assert_eq!(lexemes[4].kind, TokenKind::Denominator);
+/// assert_eq!(lexemes[4].span, 5..6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Lexer<'a> {
+    input: &'a str,
+    /// byte offset of the next character
+    offset: usize,
+    line_number: usize,
+    column_number: usize,
+    /// "-" in parenthesis is a part of extension (like "C(-5)"), not a separator
+    is_in_extension: bool,
+    /// the last two tokens, which decide the kind of the next value
+    last: Option<Lexeme<'a>>,
+    before_last: Option<Lexeme<'a>>,
+    /// the last token telling whether the next value is a chord or an extension,
+    /// skipping the commas and the meta infos (like "," of "C(9,13)")
+    context: Option<TokenKind>,
+    /// the comments skipped so far, which are not tokens
+    comments: Vec<Comment<'a>>,
+    is_finished: bool,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Lexer<'a> {
+        Lexer {
+            input,
+            offset: 0,
+            line_number: 1,
+            column_number: 1,
+            is_in_extension: false,
+            last: None,
+            before_last: None,
+            context: None,
+            comments: vec![],
+            is_finished: false,
+        }
+    }
+
+    /// comments (like "// intro") in the input lexed so far, in the order of the input
+    pub fn comments(&self) -> &[Comment<'a>] {
+        &self.comments
+    }
+
+    /// the input from the next character
+    fn rest(&self) -> &'a str {
+        &self.input[self.offset..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.offset += ch.len_utf8();
+        if ch == '\n' {
+            self.line_number += 1;
+            self.column_number = 1;
+        } else {
+            self.column_number += 1;
+        }
+        Some(ch)
+    }
+
+    /// the token from `start` to the next character
+    fn lexeme(
+        &self,
+        kind: TokenKind,
+        start: usize,
+        line_number: usize,
+        column_number: usize,
+    ) -> Lexeme<'a> {
+        Lexeme {
+            kind,
+            text: &self.input[start..self.offset],
+            span: start..self.offset,
+            line_number,
+            column_number,
+        }
+    }
+
+    fn next_lexeme(&mut self) -> Option<Result<Lexeme<'a>, ErrorInfoWithPosition>> {
+        loop {
+            let (start, line_number, column_number) =
+                (self.offset, self.line_number, self.column_number);
+
+            let kind = match self.bump()? {
+                '@' => TokenKind::SectionMetaInfoStart,
+                '[' => TokenKind::MetaInfoStart,
+                ']' => TokenKind::MetaInfoEnd,
+                '(' => {
+                    self.is_in_extension = true;
+                    TokenKind::ExtensionStart
+                }
+                ')' => {
+                    self.is_in_extension = false;
+                    TokenKind::ExtensionEnd
+                }
+                '-' if !self.is_in_extension => TokenKind::ChordBlockSeparator,
+                '=' => TokenKind::Equal,
+                ',' => TokenKind::Comma,
+                '/' if self.peek() == Some('/') => {
+                    self.skip_comment(start, line_number, column_number);
+                    continue;
+                }
+                '/' => TokenKind::Slash,
+                '|' if self.peek() == Some(':') => {
+                    self.bump();
+                    TokenKind::RepeatStart
+                }
+                ':' if self.peek() == Some('|') => {
+                    self.bump();
+                    TokenKind::RepeatEnd
+                }
+                '<' => return Some(self.navigation_mark(start, line_number, column_number)),
+                ' ' | '　' | '\t' => continue,
+                '\n' | '\r' => {
+                    self.is_in_extension = false;
+                    if let Err(error) = self.validate_line_break(line_number, column_number) {
+                        return Some(Err(error));
+                    }
+                    TokenKind::LineBreak
+                }
+                _ => return Some(self.value(start, line_number, column_number)),
+            };
+
+            return Some(Ok(self.lexeme(kind, start, line_number, column_number)));
+        }
+    }
+
+    /// "//" starts a comment to the end of the line (like the lint suppressions),
+    /// and a line of only a comment is skipped with its line break
+    fn skip_comment(&mut self, start: usize, line_number: usize, column_number: usize) {
+        while self.peek().is_some_and(|ch| ch != '\n' && ch != '\r') {
+            self.bump();
+        }
+        self.comments.push(Comment {
+            text: &self.input[start..self.offset],
+            span: start..self.offset,
+            line_number,
+            column_number,
+        });
+
+        if matches!(
+            self.last.as_ref().map(|last| last.kind),
+            None | Some(TokenKind::LineBreak)
+        ) {
+            for line_break in ['\r', '\n'] {
+                if self.peek() == Some(line_break) {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    /// read to ">" (like "<D.S. al Coda>"), in the same line
+    fn navigation_mark(
+        &mut self,
+        start: usize,
+        line_number: usize,
+        column_number: usize,
+    ) -> Result<Lexeme<'a>, ErrorInfoWithPosition> {
+        while let Some(ch) = self.peek() {
+            if ch == '\n' || ch == '\r' {
+                break;
+            }
+            self.bump();
+            if ch == '>' {
+                break;
+            }
+        }
+
+        let lexeme = self.lexeme(TokenKind::NavigationMark, start, line_number, column_number);
+        if !lexeme.text.ends_with('>') {
+            return Err(error_at(
+                ErrorCode::Nav1,
+                Some(lexeme.text.to_string()),
+                line_number,
+                column_number,
+                lexeme.text.len(),
+            ));
+        }
+
+        Ok(lexeme)
+    }
+
+    /// the line can't break in the middle of the meta infos or the bar
+    fn validate_line_break(
+        &self,
+        line_number: usize,
+        column_number: usize,
+    ) -> Result<(), ErrorInfoWithPosition> {
+        let code = match self.last.as_ref().map(|last| last.kind) {
+            Some(TokenKind::SectionMetaInfoKey | TokenKind::SectionMetaInfoName) => {
+                ErrorCode::Smik2
+            }
+            Some(TokenKind::MetaInfoKey) => ErrorCode::Cimk1,
+            Some(TokenKind::MetaInfoValue) => ErrorCode::Cimv1,
+            Some(TokenKind::Comma) => ErrorCode::Chb2,
+            _ => return Ok(()),
+        };
+
+        Err(error_at(code, None, line_number, column_number, 1))
+    }
+
+    /// kind of the value starting here, decided by the tokens before it
+    fn value_kind(
+        &self,
+        line_number: usize,
+        column_number: usize,
+    ) -> Result<TokenKind, ErrorInfoWithPosition> {
+        let Some(last) = &self.last else {
+            return Ok(TokenKind::Chord);
+        };
+
+        match last.kind {
+            TokenKind::SectionMetaInfoStart => Ok(TokenKind::SectionMetaInfoKey),
+            TokenKind::SectionMetaInfoKey => Ok(TokenKind::SectionMetaInfoName),
+            TokenKind::ExtensionStart => Ok(TokenKind::Extension),
+            TokenKind::MetaInfoStart => Ok(TokenKind::MetaInfoKey),
+            // the value after "=" is of the section meta info or the chord meta info, by the key before it
+            TokenKind::Equal => match &self.before_last {
+                Some(before_equal) => match before_equal.kind {
+                    TokenKind::SectionMetaInfoKey | TokenKind::SectionMetaInfoName => {
+                        Ok(TokenKind::SectionMetaInfoValue)
+                    }
+                    TokenKind::MetaInfoKey => Ok(TokenKind::MetaInfoValue),
+                    _ => {
+                        let token = before_equal.to_token().to_string();
+                        Err(error_at(
+                            ErrorCode::Tkn1,
+                            Some(token.clone()),
+                            line_number,
+                            column_number - 1,
+                            token.len(),
+                        ))
+                    }
+                },
+                None => Err(error_at(
+                    ErrorCode::Tkn1,
+                    None,
+                    line_number,
+                    column_number,
+                    1,
+                )),
+            },
+            TokenKind::Slash => Ok(TokenKind::Denominator),
+            _ => match self.context {
+                Some(TokenKind::ExtensionStart | TokenKind::Extension) => Ok(TokenKind::Extension),
+                Some(_) => Ok(TokenKind::Chord),
+                None => Err(error_at(
+                    ErrorCode::Tkn1,
+                    None,
+                    line_number,
+                    column_number,
+                    1,
+                )),
+            },
+        }
+    }
+
+    /// read the value (like a chord or a meta info key) whose first character is already read
+    fn value(
+        &mut self,
+        start: usize,
+        line_number: usize,
+        column_number: usize,
+    ) -> Result<Lexeme<'a>, ErrorInfoWithPosition> {
+        let kind = self.value_kind(line_number, column_number)?;
+
+        if kind == TokenKind::Denominator {
+            // NOTE: for preventing break in the middle of extension
+            let mut is_reading_extension = false;
+            while let Some(ch) = self.peek() {
+                if ch == '(' {
+                    is_reading_extension = true;
+                } else if ch == ')' {
+                    is_reading_extension = false;
+                }
+
+                if !is_reading_extension
+                    && (is_chord_info_end_char(ch)
+                        || is_navigation_mark_start(self.rest())
+                        || is_white_space(ch))
+                {
+                    break;
+                }
+                self.bump();
+            }
+        } else {
+            while let Some(ch) = self.peek() {
+                let value = &self.input[start..self.offset];
+
+                // polychord (like "{D|C7(9)}") is read to the closing brace
+                if kind == TokenKind::Chord && value.starts_with('{') && !value.ends_with('}') {
+                    if ch == '\n' || ch == '\r' {
+                        break;
+                    }
+                    self.bump();
+                    continue;
+                }
+
+                // section meta info value (like "@use=Verse:-2" or "@include=parts/intro.txt")
+                // is read to the white space or the line end
+                if kind == TokenKind::SectionMetaInfoValue
+                    && !matches!(ch, '\n' | '\r')
+                    && !is_white_space(ch)
+                {
+                    self.bump();
+                    continue;
+                }
+
+                // "/" of the time signature (like "[time=3/4]") is a part of the meta info value
+                if kind == TokenKind::MetaInfoValue && ch == '/' {
+                    self.bump();
+                    continue;
+                }
+
+                // "-" right after the root is minor (like "C-7"), not a separator
+                if ch == '-' && kind == TokenKind::Chord && is_minor_dash(value, self.rest()) {
+                    self.bump();
+                    continue;
+                }
+
+                // "/" in parenthesis (like "C(6/9)") or "C6/9" is not a denominator
+                let is_extension_slash = ch == '/'
+                    && match kind {
+                        TokenKind::Extension => true,
+                        TokenKind::Chord => is_six_nine_slash(value, self.rest()),
+                        _ => false,
+                    };
+
+                if (is_token_char(ch) && !is_extension_slash)
+                    // navigation mark right after the chord (like "C:|")
+                    || (kind == TokenKind::Chord && is_navigation_mark_start(self.rest()))
+                    || is_white_space(ch)
+                {
+                    break;
+                }
+                self.bump();
+            }
+        }
+
+        let lexeme = self.lexeme(kind, start, line_number, column_number);
+
+        // If the chord is invalid (starts with some number or o), an error occurs.
+        if kind == TokenKind::Chord
+            && lexeme
+                .text
+                .starts_with(|c: char| c.is_numeric() || c == 'o')
+        {
+            return Err(error_at(
+                ErrorCode::Cho1,
+                Some(lexeme.text.to_string()),
+                line_number,
+                column_number,
+                lexeme.text.len(),
+            ));
+        }
+
+        Ok(lexeme)
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<Lexeme<'a>, ErrorInfoWithPosition>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_finished {
+            return None;
+        }
+
+        match self.next_lexeme() {
+            Some(Ok(lexeme)) => {
+                if matches!(
+                    lexeme.kind,
+                    TokenKind::MetaInfoEnd
+                        | TokenKind::ChordBlockSeparator
+                        | TokenKind::ExtensionStart
+                        | TokenKind::ExtensionEnd
+                        | TokenKind::Extension
+                        | TokenKind::LineBreak
+                        | TokenKind::Chord
+                        | TokenKind::RepeatStart
+                        | TokenKind::RepeatEnd
+                        | TokenKind::NavigationMark
+                ) {
+                    self.context = Some(lexeme.kind);
+                }
+                self.before_last = self.last.replace(lexeme.clone());
+                Some(Ok(lexeme))
+            }
+            result => {
+                self.is_finished = true;
+                result
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrowed_slices_with_byte_spans() {
+        let input = "@section=𝄪\n[key=B♭]B♭m7(9)/D♭ <segno>";
+        let lexemes = Lexer::new(input).collect::<Result<Vec<_>, _>>().unwrap();
+
+        for lexeme in &lexemes {
+            assert_eq!(&input[lexeme.span.clone()], lexeme.text);
+            // the texts point into the input
+            assert!(std::ptr::eq(
+                &input.as_bytes()[lexeme.span.start],
+                lexeme.text.as_ptr()
+            ));
+        }
+        assert_eq!(
+            lexemes
+                .iter()
+                .map(|lexeme| (lexeme.kind, lexeme.text))
+                .collect::<Vec<_>>(),
+            vec![
+                (TokenKind::SectionMetaInfoStart, "@"),
+                (TokenKind::SectionMetaInfoKey, "section"),
+                (TokenKind::Equal, "="),
+                (TokenKind::SectionMetaInfoValue, "𝄪"),
+                (TokenKind::LineBreak, "\n"),
+                (TokenKind::MetaInfoStart, "["),
+                (TokenKind::MetaInfoKey, "key"),
+                (TokenKind::Equal, "="),
+                (TokenKind::MetaInfoValue, "B♭"),
+                (TokenKind::MetaInfoEnd, "]"),
+                (TokenKind::Chord, "B♭m7"),
+                (TokenKind::ExtensionStart, "("),
+                (TokenKind::Extension, "9"),
+                (TokenKind::ExtensionEnd, ")"),
+                (TokenKind::Slash, "/"),
+                (TokenKind::Denominator, "D♭"),
+                (TokenKind::NavigationMark, "<segno>"),
+            ]
+        );
+        // the column is in characters, and the span is in bytes
+        assert_eq!(
+            (lexemes[15].column_number, lexemes[15].span.clone()),
+            (17, 34..38)
+        );
+    }
+
+    #[test]
+    fn comments_are_recorded() {
+        let mut lexer = Lexer::new("// intro\nC - F♯ // lint-disable-line");
+        let texts: Vec<&str> = lexer.by_ref().map(|lexeme| lexeme.unwrap().text).collect();
+
+        assert_eq!(texts, vec!["C", "-", "F♯"]);
+        assert_eq!(
+            lexer
+                .comments()
+                .iter()
+                .map(|comment| (comment.text, comment.line_number, comment.column_number))
+                .collect::<Vec<_>>(),
+            vec![("// intro", 1, 1), ("// lint-disable-line", 2, 8)]
+        );
+        assert_eq!(lexer.comments()[1].span, 18..38);
+    }
+
+    #[test]
+    fn stops_at_error() {
+        let mut lexer = Lexer::new("C - <segno\nG");
+
+        assert_eq!(lexer.next().unwrap().unwrap().text, "C");
+        assert_eq!(lexer.next().unwrap().unwrap().text, "-");
+        assert_eq!(
+            lexer.next().unwrap().unwrap_err().error.code,
+            ErrorCode::Nav1
+        );
+        assert_eq!(lexer.next(), None);
+    }
+}
@@ -1,478 +1,24 @@
 use crate::error_code::ErrorInfoWithPosition;
-use crate::error_code::{ErrorCode, ErrorInfo};
-use crate::util::position::Position;
 
+pub mod lexer;
 pub mod types;
 pub mod util;
 
-use types::token::Token;
-use types::value_token::ValueToken;
-use util::is_token_char;
-use util::next_char_with_position;
-
-use self::types::token_with_position::TokenWithPosition;
-use self::util::is_chord_info_end_char;
+use lexer::Lexer;
+use types::token_with_position::TokenWithPosition;
 
+/// Tokenize the input, collecting the tokens of [`Lexer`] with their values copied
 pub fn tokenize(input: &str) -> Result<Vec<TokenWithPosition>, ErrorInfoWithPosition> {
-    let mut tokens = Vec::new();
-    let mut chars = input.chars().peekable();
-
-    let mut origin_line_number = 1;
-    let mut origin_column_number = 1;
-
-    // while let Some(ch) = chars.next() {
-    while let Some((ch, pos)) = next_char_with_position(
-        &mut chars,
-        &mut origin_line_number,
-        &mut origin_column_number,
-    ) {
-        match ch {
-            '@' => tokens.push(TokenWithPosition {
-                token: Token::SectionMetaInfoStart,
-                position: Position {
-                    line_number: pos.line_number,
-                    column_number: pos.column_number,
-                    length: 1,
-                },
-            }),
-            '[' => tokens.push(TokenWithPosition {
-                token: Token::MetaInfoStart,
-                position: Position {
-                    line_number: pos.line_number,
-                    column_number: pos.column_number,
-                    length: 1,
-                },
-            }),
-            ']' => tokens.push(TokenWithPosition {
-                token: Token::MetaInfoEnd,
-                position: Position {
-                    line_number: pos.line_number,
-                    column_number: pos.column_number,
-                    length: 1,
-                },
-            }),
-            '(' => tokens.push(TokenWithPosition {
-                token: Token::ExtensionStart,
-                position: Position {
-                    line_number: pos.line_number,
-                    column_number: pos.column_number,
-                    length: 1,
-                },
-            }),
-            ')' => tokens.push(TokenWithPosition {
-                token: Token::ExtensionEnd,
-                position: Position {
-                    line_number: pos.line_number,
-                    column_number: pos.column_number,
-                    length: 1,
-                },
-            }),
-            '-' => tokens.push(TokenWithPosition {
-                token: Token::ChordBlockSeparator,
-                position: Position {
-                    line_number: pos.line_number,
-                    column_number: pos.column_number,
-                    length: 1,
-                },
-            }),
-            '=' => tokens.push(TokenWithPosition {
-                token: Token::Equal,
-                position: Position {
-                    line_number: pos.line_number,
-                    column_number: pos.column_number,
-                    length: 1,
-                },
-            }),
-            ',' => tokens.push(TokenWithPosition {
-                token: Token::Comma,
-                position: Position {
-                    line_number: pos.line_number,
-                    column_number: pos.column_number,
-                    length: 1,
-                },
-            }),
-            '/' => tokens.push(TokenWithPosition {
-                token: Token::Slash,
-                position: Position {
-                    line_number: pos.line_number,
-                    column_number: pos.column_number,
-                    length: 1,
-                },
-            }),
-            ' ' | '　' | '\t' => {}
-            '\n' | '\r' => {
-                // first line line-break
-                if tokens.is_empty() {
-                    tokens.push(TokenWithPosition {
-                        token: Token::LineBreak,
-                        position: Position {
-                            line_number: pos.line_number,
-                            column_number: pos.column_number,
-                            length: 1,
-                        },
-                    });
-                    continue;
-                }
-
-                // validations for line break
-                match tokens.last().unwrap().token {
-                    Token::SectionMetaInfoKey(_) => {
-                        return Err(ErrorInfoWithPosition {
-                            error: ErrorInfo {
-                                code: ErrorCode::Smik2,
-                                additional_info: None,
-                            },
-                            position: Position {
-                                line_number: pos.line_number,
-                                column_number: pos.column_number,
-                                length: 1,
-                            },
-                        });
-                    }
-                    Token::MetaInfoKey(_) => {
-                        return Err(ErrorInfoWithPosition {
-                            error: ErrorInfo {
-                                code: ErrorCode::Cimk1,
-                                additional_info: None,
-                            },
-                            position: Position {
-                                line_number: pos.line_number,
-                                column_number: pos.column_number,
-                                length: 1,
-                            },
-                        });
-                    }
-                    Token::MetaInfoValue(_) => {
-                        return Err(ErrorInfoWithPosition {
-                            error: ErrorInfo {
-                                code: ErrorCode::Cimv1,
-                                additional_info: None,
-                            },
-                            position: Position {
-                                line_number: pos.line_number,
-                                column_number: pos.column_number,
-                                length: 1,
-                            },
-                        });
-                    }
-                    Token::Comma => {
-                        return Err(ErrorInfoWithPosition {
-                            error: ErrorInfo {
-                                code: ErrorCode::Chb2,
-                                additional_info: None,
-                            },
-                            position: Position {
-                                line_number: pos.line_number,
-                                column_number: pos.column_number,
-                                length: 1,
-                            },
-                        });
-                    }
-                    _ => tokens.push(TokenWithPosition {
-                        token: Token::LineBreak,
-                        position: Position {
-                            line_number: pos.line_number,
-                            column_number: pos.column_number,
-                            length: 1,
-                        },
-                    }),
-                }
-            }
-            non_functional_char => {
-                let mut token = String::new();
-                token.push(non_functional_char);
-
-                let get_token_type_result = match tokens.last() {
-                    None => Ok(Some(ValueToken::Chord)),
-                    Some(token_with_position) => match token_with_position.token {
-                        Token::SectionMetaInfoStart => Ok(Some(ValueToken::SectionMetaInfoKey)),
-                        Token::ExtensionStart => Ok(Some(ValueToken::Extension)),
-                        Token::MetaInfoStart => Ok(Some(ValueToken::MetaInfoKey)),
-                        Token::Equal => {
-                            // NOTE: In the case of equal, depending on the character before equal,
-                            //       it may be a section meta or a code meta, so get it in advance.
-                            let token_before_equal = if tokens.len() >= 2 {
-                                tokens.get(tokens.len() - 2)
-                            } else {
-                                None
-                            };
-
-                            if token_before_equal.is_none() {
-                                return Err(ErrorInfoWithPosition {
-                                    error: ErrorInfo {
-                                        code: ErrorCode::Tkn1,
-                                        additional_info: None,
-                                    },
-                                    position: Position {
-                                        line_number: pos.line_number,
-                                        column_number: pos.column_number,
-                                        length: 1,
-                                    },
-                                });
-                            }
-
-                            match token_before_equal.unwrap().token {
-                                Token::SectionMetaInfoKey(_) => {
-                                    Ok(Some(ValueToken::SectionMetaInfoValue))
-                                }
-                                Token::MetaInfoKey(_) => Ok(Some(ValueToken::MetaInfoValue)),
-                                _ => {
-                                    let position = pos.clone();
-                                    return Err(ErrorInfoWithPosition {
-                                        error: ErrorInfo {
-                                            code: ErrorCode::Tkn1,
-                                            additional_info: Some(
-                                                token_before_equal.unwrap().token.to_string(),
-                                            ),
-                                        },
-                                        position: Position {
-                                            line_number: position.line_number,
-                                            column_number: position.column_number - 1,
-                                            length: token_before_equal
-                                                .unwrap()
-                                                .token
-                                                .to_string()
-                                                .len(),
-                                        },
-                                    });
-                                }
-                            }
-                        }
-                        Token::Slash => Ok(Some(ValueToken::Denominator)),
-                        _ => {
-                            // NOTE:
-                            //   If the result of tracing back is "]" or "-", it is a chord,
-                            //   and if it is "(", it is an extension.
-                            //   and if it is ",", chord or extension. ( and more prev is chord then chord, extension then extension)
-                            let mut is_code = false;
-                            let mut is_extension = false;
-                            let mut prev_token_index = tokens.len();
-
-                            // get previous token type
-                            // To take into account line feed codes, use while
-                            while prev_token_index > 0 {
-                                prev_token_index -= 1;
-                                let prev_token = tokens.get(prev_token_index).unwrap();
-                                match prev_token.token {
-                                    Token::MetaInfoEnd => {
-                                        is_code = true;
-                                        break;
-                                    }
-                                    Token::ChordBlockSeparator => {
-                                        is_code = true;
-                                        break;
-                                    }
-                                    Token::ExtensionEnd => {
-                                        is_code = true;
-                                        break;
-                                    }
-                                    Token::LineBreak => {
-                                        is_code = true;
-                                        break;
-                                    }
-                                    Token::ExtensionStart => {
-                                        is_extension = true;
-                                        break;
-                                    }
-                                    Token::Comma => {
-                                        // look back more
-                                        continue;
-                                    }
-                                    Token::Chord(_) => {
-                                        is_code = true;
-                                        break;
-                                    }
-                                    Token::Extension(_) => {
-                                        is_extension = true;
-                                        break;
-                                    }
-                                    _ => {}
-                                };
-                            }
-
-                            if is_code {
-                                Ok(Some(ValueToken::Chord))
-                            } else if is_extension {
-                                Ok(Some(ValueToken::Extension))
-                            } else {
-                                Err(ErrorInfoWithPosition {
-                                    error: ErrorInfo {
-                                        code: ErrorCode::Tkn1,
-                                        additional_info: None,
-                                    },
-                                    position: Position {
-                                        line_number: pos.line_number,
-                                        column_number: pos.column_number,
-                                        length: 1,
-                                    },
-                                })
-                            }
-                        }
-                    },
-                };
-
-                // if error, return
-                let token_type = if let Ok(token_type) = get_token_type_result {
-                    token_type
-                } else {
-                    return Err(get_token_type_result.unwrap_err());
-                };
-
-                // get token
-                match token_type {
-                    Some(ValueToken::Denominator) => {
-                        let mut is_reading_extension = false;
-                        while let Some(&next_ch) = chars.peek() {
-                            // check is reading extension
-                            // NOTE: for preventing break in the middle of extension
-                            if next_ch == '(' {
-                                is_reading_extension = true;
-                            } else if next_ch == ')' {
-                                is_reading_extension = false;
-                            }
-
-                            // if not reading extension
-                            if !is_reading_extension {
-                                // loop while next char is token char
-                                if is_chord_info_end_char(next_ch) {
-                                    break;
-                                }
-
-                                // if white space, break
-                                if next_ch == ' ' || next_ch == '　' || next_ch == '\t' {
-                                    break;
-                                }
-                            }
-
-                            token.push(next_ch);
-                            next_char_with_position(
-                                &mut chars,
-                                &mut origin_line_number,
-                                &mut origin_column_number,
-                            );
-                        }
-                    }
-                    _ => {
-                        while let Some(&next_ch) = chars.peek() {
-                            // loop while next char is token char
-                            if is_token_char(next_ch) {
-                                break;
-                            }
-
-                            // if white space, break
-                            if next_ch == ' ' || next_ch == '　' || next_ch == '\t' {
-                                break;
-                            }
-
-                            token.push(next_ch);
-                            next_char_with_position(
-                                &mut chars,
-                                &mut origin_line_number,
-                                &mut origin_column_number,
-                            );
-                        }
-                    }
-                }
-
-                // push token
-                let borrowed_token = token.clone();
-                match token_type {
-                    Some(ValueToken::SectionMetaInfoKey) => tokens.push(TokenWithPosition {
-                        token: Token::SectionMetaInfoKey(token),
-                        position: Position {
-                            line_number: pos.line_number,
-                            column_number: pos.column_number,
-                            length: borrowed_token.len(),
-                        },
-                    }),
-                    Some(ValueToken::SectionMetaInfoValue) => tokens.push(TokenWithPosition {
-                        token: Token::SectionMetaInfoValue(token),
-                        position: Position {
-                            line_number: pos.line_number,
-                            column_number: pos.column_number,
-                            length: borrowed_token.len(),
-                        },
-                    }),
-                    Some(ValueToken::Extension) => tokens.push(TokenWithPosition {
-                        token: Token::Extension(token),
-                        position: Position {
-                            line_number: pos.line_number,
-                            column_number: pos.column_number,
-                            length: borrowed_token.len(),
-                        },
-                    }),
-                    Some(ValueToken::MetaInfoKey) => tokens.push(TokenWithPosition {
-                        token: Token::MetaInfoKey(token),
-                        position: Position {
-                            line_number: pos.line_number,
-                            column_number: pos.column_number,
-                            length: borrowed_token.len(),
-                        },
-                    }),
-                    Some(ValueToken::MetaInfoValue) => tokens.push(TokenWithPosition {
-                        token: Token::MetaInfoValue(token),
-                        position: Position {
-                            line_number: pos.line_number,
-                            column_number: pos.column_number,
-                            length: borrowed_token.len(),
-                        },
-                    }),
-                    Some(ValueToken::Chord) => {
-                        // If the chord is invalid (contains some number or o), an error occurs.
-                        if token.chars().any(|c| c.is_numeric() || c == 'o') {
-                            return Err(ErrorInfoWithPosition {
-                                error: ErrorInfo {
-                                    code: ErrorCode::Cho1,
-                                    additional_info: Some(token),
-                                },
-                                position: Position {
-                                    line_number: pos.line_number,
-                                    column_number: pos.column_number,
-                                    length: borrowed_token.len(),
-                                },
-                            });
-                        }
-                        tokens.push(TokenWithPosition {
-                            token: Token::Chord(token),
-                            position: Position {
-                                line_number: pos.line_number,
-                                column_number: pos.column_number,
-                                length: borrowed_token.len(),
-                            },
-                        })
-                    }
-                    Some(ValueToken::Denominator) => tokens.push(TokenWithPosition {
-                        token: Token::Denominator(token),
-                        position: Position {
-                            line_number: pos.line_number,
-                            column_number: pos.column_number,
-                            length: borrowed_token.len(),
-                        },
-                    }),
-                    None => {
-                        return Err(ErrorInfoWithPosition {
-                            error: ErrorInfo {
-                                code: ErrorCode::Tkn1,
-                                additional_info: Some(token_type.unwrap().to_string()),
-                            },
-                            position: Position {
-                                line_number: pos.line_number,
-                                column_number: pos.column_number,
-                                length: borrowed_token.len(),
-                            },
-                        });
-                    }
-                }
-            }
-        }
-    }
-
-    Ok(tokens)
+    Lexer::new(input)
+        .map(|lexeme| lexeme.map(|lexeme| lexeme.to_token_with_position()))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error_code::ErrorCode;
+    use types::token::Token;
 
     #[cfg(test)]
     mod success {
@@ -480,6 +26,166 @@ mod tests {
 
         use super::*;
 
+        #[test]
+        fn dash_after_root_is_minor() {
+            let tokens = |input: &str| -> Vec<Token> {
+                tokenize(input)
+                    .unwrap()
+                    .into_iter()
+                    .map(|token_with_position| token_with_position.token)
+                    .collect()
+            };
+
+            assert_eq!(
+                tokens("C-7-Bb-(9)"),
+                vec![
+                    Token::Chord("C-7".to_string()),
+                    Token::ChordBlockSeparator,
+                    Token::Chord("Bb-".to_string()),
+                    Token::ExtensionStart,
+                    Token::Extension("9".to_string()),
+                    Token::ExtensionEnd,
+                ]
+            );
+            assert_eq!(
+                tokens("C-D - Am-G7"),
+                vec![
+                    Token::Chord("C".to_string()),
+                    Token::ChordBlockSeparator,
+                    Token::Chord("D".to_string()),
+                    Token::ChordBlockSeparator,
+                    Token::Chord("Am".to_string()),
+                    Token::ChordBlockSeparator,
+                    Token::Chord("G7".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn dash_in_parenthesis_is_extension() {
+            let tokens: Vec<Token> = tokenize("C(-5,+5)-D")
+                .unwrap()
+                .into_iter()
+                .map(|token_with_position| token_with_position.token)
+                .collect();
+
+            assert_eq!(
+                tokens,
+                vec![
+                    Token::Chord("C".to_string()),
+                    Token::ExtensionStart,
+                    Token::Extension("-5".to_string()),
+                    Token::Comma,
+                    Token::Extension("+5".to_string()),
+                    Token::ExtensionEnd,
+                    Token::ChordBlockSeparator,
+                    Token::Chord("D".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn slash_of_six_nine_is_extension() {
+            let tokens: Vec<Token> = tokenize("C(6/9)-C6/9/E-C6/E")
+                .unwrap()
+                .into_iter()
+                .map(|token_with_position| token_with_position.token)
+                .collect();
+
+            assert_eq!(
+                tokens,
+                vec![
+                    Token::Chord("C".to_string()),
+                    Token::ExtensionStart,
+                    Token::Extension("6/9".to_string()),
+                    Token::ExtensionEnd,
+                    Token::ChordBlockSeparator,
+                    Token::Chord("C6/9".to_string()),
+                    Token::Slash,
+                    Token::Denominator("E".to_string()),
+                    Token::ChordBlockSeparator,
+                    Token::Chord("C6".to_string()),
+                    Token::Slash,
+                    Token::Denominator("E".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn poly_chord_is_one_token() {
+            let tokens: Vec<Token> = tokenize("{D|C7(9)}/E-{B♭ | Ab-7}")
+                .unwrap()
+                .into_iter()
+                .map(|token_with_position| token_with_position.token)
+                .collect();
+
+            assert_eq!(
+                tokens,
+                vec![
+                    Token::Chord("{D|C7(9)}".to_string()),
+                    Token::Slash,
+                    Token::Denominator("E".to_string()),
+                    Token::ChordBlockSeparator,
+                    Token::Chord("{B♭ | Ab-7}".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn navigation_marks() {
+            let tokens: Vec<Token> = tokenize("|:C/E - F:| <2.>G <D.S. al Coda>")
+                .unwrap()
+                .into_iter()
+                .map(|token_with_position| token_with_position.token)
+                .collect();
+
+            assert_eq!(
+                tokens,
+                vec![
+                    Token::RepeatStart,
+                    Token::Chord("C".to_string()),
+                    Token::Slash,
+                    Token::Denominator("E".to_string()),
+                    Token::ChordBlockSeparator,
+                    Token::Chord("F".to_string()),
+                    Token::RepeatEnd,
+                    Token::NavigationMark("<2.>".to_string()),
+                    Token::Chord("G".to_string()),
+                    Token::NavigationMark("<D.S. al Coda>".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn comments_are_skipped() {
+            let tokens = tokenize("// intro\nC/E - F // lint-disable-line\n// end\nG").unwrap();
+
+            assert_eq!(
+                tokens
+                    .iter()
+                    .map(|token_with_position| token_with_position.token.clone())
+                    .collect::<Vec<Token>>(),
+                vec![
+                    Token::Chord("C".to_string()),
+                    Token::Slash,
+                    Token::Denominator("E".to_string()),
+                    Token::ChordBlockSeparator,
+                    Token::Chord("F".to_string()),
+                    Token::LineBreak,
+                    Token::Chord("G".to_string()),
+                ]
+            );
+            // the positions are of the lines as written
+            assert_eq!(
+                tokens.last().unwrap().position,
+                Position {
+                    line_number: 4,
+                    column_number: 1,
+                    length: 1,
+                }
+            );
+        }
+
         #[test]
         fn complex_chord_expression() {
             let input = "?/C(5) - C";
@@ -878,6 +584,46 @@ mod tests {
             assert_eq!(tokens, expected);
         }
 
+        #[test]
+        fn section_meta_info_value_keeps_slashes_and_dots() {
+            let tokens = tokenize("@include=../parts/intro-1.txt\nC").unwrap();
+
+            assert_eq!(
+                tokens[3],
+                TokenWithPosition {
+                    token: Token::SectionMetaInfoValue("../parts/intro-1.txt".to_string()),
+                    position: Position {
+                        line_number: 1,
+                        column_number: 10,
+                        length: 20,
+                    },
+                }
+            );
+            assert_eq!(tokens[4].token, Token::LineBreak);
+        }
+
+        #[test]
+        fn section_meta_info_with_name() {
+            let tokens: Vec<Token> = tokenize("@def X=F#m(b5,7,11)\n$X")
+                .unwrap()
+                .into_iter()
+                .map(|token_with_position| token_with_position.token)
+                .collect();
+
+            assert_eq!(
+                tokens,
+                vec![
+                    Token::SectionMetaInfoStart,
+                    Token::SectionMetaInfoKey("def".to_string()),
+                    Token::SectionMetaInfoName("X".to_string()),
+                    Token::Equal,
+                    Token::SectionMetaInfoValue("F#m(b5,7,11)".to_string()),
+                    Token::LineBreak,
+                    Token::Chord("$X".to_string()),
+                ]
+            );
+        }
+
         #[test]
         fn multiple_section_meta_info() {
             let input = "
@@ -2015,5 +1761,13 @@ Gm-Gm
             assert!(lex_result.is_err());
             assert_eq!(lex_result.unwrap_err().error.code, ErrorCode::Chb2,);
         }
+
+        #[test]
+        fn navigation_mark_should_be_closed() {
+            let error = tokenize("C - <segno\nF").unwrap_err();
+
+            assert_eq!(error.error.code, ErrorCode::Nav1);
+            assert_eq!(error.position.column_number, 5);
+        }
     }
 }
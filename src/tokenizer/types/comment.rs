@@ -0,0 +1,27 @@
+use std::ops::Range;
+
+use crate::util::position::Position;
+
+/// Comment skipped by the lexer (like `// intro`), borrowing its text from the input
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Comment<'a> {
+    /// text from `//` to the end of the line, without the line break
+    pub text: &'a str,
+    /// byte range of the text in the input
+    pub span: Range<usize>,
+    /// line number of the start, starting from 1
+    pub line_number: usize,
+    /// column number of the start in characters, starting from 1
+    pub column_number: usize,
+}
+
+impl Comment<'_> {
+    /// position of the comment, whose length is in bytes like the ones of the tokens
+    pub fn position(&self) -> Position {
+        Position {
+            line_number: self.line_number,
+            column_number: self.column_number,
+            length: self.span.len(),
+        }
+    }
+}
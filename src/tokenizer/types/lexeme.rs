@@ -0,0 +1,56 @@
+use std::ops::Range;
+
+use crate::util::position::Position;
+
+use super::token::{Token, TokenKind};
+use super::token_ref::{TokenRef, TokenRefWithPosition};
+use super::token_with_position::TokenWithPosition;
+
+/// Token of the lexer, borrowing its text from the input
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Lexeme<'a> {
+    pub kind: TokenKind,
+    /// text of the token as written, like `C7`, `|:` or `<segno>` (`\n` or `\r` for the line breaks)
+    pub text: &'a str,
+    /// byte range of the text in the input
+    pub span: Range<usize>,
+    /// line number of the start, starting from 1
+    pub line_number: usize,
+    /// column number of the start in characters, starting from 1
+    pub column_number: usize,
+}
+
+impl<'a> Lexeme<'a> {
+    /// position of the token, whose length is in bytes like the one of [`TokenWithPosition`]
+    pub fn position(&self) -> Position {
+        Position {
+            line_number: self.line_number,
+            column_number: self.column_number,
+            length: self.span.len(),
+        }
+    }
+
+    /// the token borrowing its value from the input
+    pub fn to_token_ref(&self) -> TokenRef<'a> {
+        TokenRef::new(self.kind, self.text)
+    }
+
+    pub fn to_token_ref_with_position(&self) -> TokenRefWithPosition<'a> {
+        TokenRefWithPosition {
+            token: self.to_token_ref(),
+            position: self.position(),
+        }
+    }
+
+    /// the token with its value copied
+    pub fn to_token(&self) -> Token {
+        self.to_token_ref().to_token()
+    }
+
+    pub fn to_token_with_position(&self) -> TokenWithPosition {
+        TokenWithPosition {
+            token: self.to_token(),
+            position: self.position(),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub mod comment;
+pub mod lexeme;
 pub mod token;
+pub mod token_ref;
 pub mod token_with_position;
-pub mod value_token;
@@ -1,6 +1,24 @@
-use strum_macros::{Display, EnumString, EnumVariantNames};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumDiscriminants, EnumString, VariantNames};
+use typeshare::typeshare;
 
-#[derive(Debug, PartialEq, Clone, Display, EnumString, EnumVariantNames)]
+/// Token of the chord progression string, serialized with the names of the variants as the kinds, like `{ "type": "Chord", "value": "C" }`
+///
+/// [`TokenKind`] is the kind without the value, used by the lexer which borrows the values from the input.
+#[typeshare]
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Display,
+    EnumDiscriminants,
+    EnumString,
+    VariantNames,
+    Serialize,
+    Deserialize,
+)]
+#[strum_discriminants(name(TokenKind))]
+#[serde(tag = "type", content = "value")]
 pub enum Token {
     // Common
     #[strum(serialize = "=")]
@@ -16,6 +34,7 @@ pub enum Token {
     #[strum(serialize = "@")]
     SectionMetaInfoStart,
     SectionMetaInfoKey(String),
+    SectionMetaInfoName(String), // "X" of "@def X=C"
     SectionMetaInfoValue(String),
 
     // ChordBlockElement
@@ -38,4 +57,11 @@ pub enum Token {
     #[strum(serialize = ")")]
     ExtensionEnd,
     Extension(String),
+
+    // NavigationMark
+    #[strum(serialize = "|:")]
+    RepeatStart,
+    #[strum(serialize = ":|")]
+    RepeatEnd,
+    NavigationMark(String), // <segno>, <1.>, ...
 }
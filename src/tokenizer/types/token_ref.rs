@@ -0,0 +1,205 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::util::position::Position;
+
+use super::token::{Token, TokenKind};
+use super::token_with_position::TokenWithPosition;
+
+/// [`Token`] borrowing its value, which the parser reads from the lexer or from the tokens without copying.
+/// The value is owned only if it is rewritten, like by the chord aliases or the chord macros.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TokenRef<'a> {
+    // Common
+    Equal,
+    Comma,
+    LineBreak,
+    Slash,
+
+    // SectionMetaInfoElement
+    SectionMetaInfoStart,
+    SectionMetaInfoKey(Cow<'a, str>),
+    SectionMetaInfoName(Cow<'a, str>),
+    SectionMetaInfoValue(Cow<'a, str>),
+
+    // ChordBlockElement
+    ChordBlockSeparator,
+    Chord(Cow<'a, str>),
+    Denominator(Cow<'a, str>),
+
+    // MetaInfoElement
+    MetaInfoStart,
+    MetaInfoEnd,
+    MetaInfoKey(Cow<'a, str>),
+    MetaInfoValue(Cow<'a, str>),
+
+    // Extension
+    ExtensionStart,
+    ExtensionEnd,
+    Extension(Cow<'a, str>),
+
+    // NavigationMark
+    RepeatStart,
+    RepeatEnd,
+    NavigationMark(Cow<'a, str>),
+}
+
+impl<'a> TokenRef<'a> {
+    /// the token of the kind, with the value if the kind has one
+    pub fn new(kind: TokenKind, value: impl Into<Cow<'a, str>>) -> TokenRef<'a> {
+        let value = value.into();
+
+        match kind {
+            TokenKind::Equal => TokenRef::Equal,
+            TokenKind::Comma => TokenRef::Comma,
+            TokenKind::LineBreak => TokenRef::LineBreak,
+            TokenKind::Slash => TokenRef::Slash,
+            TokenKind::SectionMetaInfoStart => TokenRef::SectionMetaInfoStart,
+            TokenKind::SectionMetaInfoKey => TokenRef::SectionMetaInfoKey(value),
+            TokenKind::SectionMetaInfoName => TokenRef::SectionMetaInfoName(value),
+            TokenKind::SectionMetaInfoValue => TokenRef::SectionMetaInfoValue(value),
+            TokenKind::ChordBlockSeparator => TokenRef::ChordBlockSeparator,
+            TokenKind::Chord => TokenRef::Chord(value),
+            TokenKind::Denominator => TokenRef::Denominator(value),
+            TokenKind::MetaInfoStart => TokenRef::MetaInfoStart,
+            TokenKind::MetaInfoEnd => TokenRef::MetaInfoEnd,
+            TokenKind::MetaInfoKey => TokenRef::MetaInfoKey(value),
+            TokenKind::MetaInfoValue => TokenRef::MetaInfoValue(value),
+            TokenKind::ExtensionStart => TokenRef::ExtensionStart,
+            TokenKind::ExtensionEnd => TokenRef::ExtensionEnd,
+            TokenKind::Extension => TokenRef::Extension(value),
+            TokenKind::RepeatStart => TokenRef::RepeatStart,
+            TokenKind::RepeatEnd => TokenRef::RepeatEnd,
+            TokenKind::NavigationMark => TokenRef::NavigationMark(value),
+        }
+    }
+
+    /// value of the token, like `C7` of [`TokenRef::Chord`] (empty for the kinds without values)
+    pub fn value(&self) -> &str {
+        match self {
+            TokenRef::SectionMetaInfoKey(value)
+            | TokenRef::SectionMetaInfoName(value)
+            | TokenRef::SectionMetaInfoValue(value)
+            | TokenRef::Chord(value)
+            | TokenRef::Denominator(value)
+            | TokenRef::MetaInfoKey(value)
+            | TokenRef::MetaInfoValue(value)
+            | TokenRef::Extension(value)
+            | TokenRef::NavigationMark(value) => value,
+            _ => "",
+        }
+    }
+
+    pub fn kind(&self) -> TokenKind {
+        match self {
+            TokenRef::Equal => TokenKind::Equal,
+            TokenRef::Comma => TokenKind::Comma,
+            TokenRef::LineBreak => TokenKind::LineBreak,
+            TokenRef::Slash => TokenKind::Slash,
+            TokenRef::SectionMetaInfoStart => TokenKind::SectionMetaInfoStart,
+            TokenRef::SectionMetaInfoKey(_) => TokenKind::SectionMetaInfoKey,
+            TokenRef::SectionMetaInfoName(_) => TokenKind::SectionMetaInfoName,
+            TokenRef::SectionMetaInfoValue(_) => TokenKind::SectionMetaInfoValue,
+            TokenRef::ChordBlockSeparator => TokenKind::ChordBlockSeparator,
+            TokenRef::Chord(_) => TokenKind::Chord,
+            TokenRef::Denominator(_) => TokenKind::Denominator,
+            TokenRef::MetaInfoStart => TokenKind::MetaInfoStart,
+            TokenRef::MetaInfoEnd => TokenKind::MetaInfoEnd,
+            TokenRef::MetaInfoKey(_) => TokenKind::MetaInfoKey,
+            TokenRef::MetaInfoValue(_) => TokenKind::MetaInfoValue,
+            TokenRef::ExtensionStart => TokenKind::ExtensionStart,
+            TokenRef::ExtensionEnd => TokenKind::ExtensionEnd,
+            TokenRef::Extension(_) => TokenKind::Extension,
+            TokenRef::RepeatStart => TokenKind::RepeatStart,
+            TokenRef::RepeatEnd => TokenKind::RepeatEnd,
+            TokenRef::NavigationMark(_) => TokenKind::NavigationMark,
+        }
+    }
+
+    /// the token with its value copied
+    pub fn to_token(&self) -> Token {
+        let value = || self.value().to_string();
+
+        match self.kind() {
+            TokenKind::Equal => Token::Equal,
+            TokenKind::Comma => Token::Comma,
+            TokenKind::LineBreak => Token::LineBreak,
+            TokenKind::Slash => Token::Slash,
+            TokenKind::SectionMetaInfoStart => Token::SectionMetaInfoStart,
+            TokenKind::SectionMetaInfoKey => Token::SectionMetaInfoKey(value()),
+            TokenKind::SectionMetaInfoName => Token::SectionMetaInfoName(value()),
+            TokenKind::SectionMetaInfoValue => Token::SectionMetaInfoValue(value()),
+            TokenKind::ChordBlockSeparator => Token::ChordBlockSeparator,
+            TokenKind::Chord => Token::Chord(value()),
+            TokenKind::Denominator => Token::Denominator(value()),
+            TokenKind::MetaInfoStart => Token::MetaInfoStart,
+            TokenKind::MetaInfoEnd => Token::MetaInfoEnd,
+            TokenKind::MetaInfoKey => Token::MetaInfoKey(value()),
+            TokenKind::MetaInfoValue => Token::MetaInfoValue(value()),
+            TokenKind::ExtensionStart => Token::ExtensionStart,
+            TokenKind::ExtensionEnd => Token::ExtensionEnd,
+            TokenKind::Extension => Token::Extension(value()),
+            TokenKind::RepeatStart => Token::RepeatStart,
+            TokenKind::RepeatEnd => Token::RepeatEnd,
+            TokenKind::NavigationMark => Token::NavigationMark(value()),
+        }
+    }
+
+    /// the token owning its value, which outlives the input
+    pub fn into_owned(self) -> TokenRef<'static> {
+        let kind = self.kind();
+        TokenRef::new(kind, self.value().to_string())
+    }
+}
+
+impl<'a> From<&'a Token> for TokenRef<'a> {
+    fn from(token: &'a Token) -> TokenRef<'a> {
+        let value = match token {
+            Token::SectionMetaInfoKey(value)
+            | Token::SectionMetaInfoName(value)
+            | Token::SectionMetaInfoValue(value)
+            | Token::Chord(value)
+            | Token::Denominator(value)
+            | Token::MetaInfoKey(value)
+            | Token::MetaInfoValue(value)
+            | Token::Extension(value)
+            | Token::NavigationMark(value) => value.as_str(),
+            _ => "",
+        };
+
+        TokenRef::new(TokenKind::from(token), value)
+    }
+}
+
+/// Same as the one of [`Token`], like `,` or `Chord`
+impl fmt::Display for TokenRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_token())
+    }
+}
+
+/// [`TokenWithPosition`] borrowing its value
+#[derive(Debug, PartialEq, Clone)]
+pub struct TokenRefWithPosition<'a> {
+    pub token: TokenRef<'a>,
+    pub position: Position,
+}
+
+impl TokenRefWithPosition<'_> {
+    /// the token with its value copied
+    pub fn to_token_with_position(&self) -> TokenWithPosition {
+        TokenWithPosition {
+            token: self.token.to_token(),
+            position: self.position.clone(),
+        }
+    }
+}
+
+impl<'a> From<&'a TokenWithPosition> for TokenRefWithPosition<'a> {
+    fn from(token_with_position: &'a TokenWithPosition) -> TokenRefWithPosition<'a> {
+        TokenRefWithPosition {
+            token: TokenRef::from(&token_with_position.token),
+            position: token_with_position.position.clone(),
+        }
+    }
+}
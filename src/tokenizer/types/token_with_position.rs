@@ -1,7 +1,11 @@
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
 use super::super::super::util::position::Position;
 use super::token::Token;
 
-#[derive(Debug, PartialEq, Clone)]
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct TokenWithPosition {
     pub token: Token,
     pub position: Position,
@@ -1,10 +0,0 @@
-#[derive(Debug, PartialEq, Clone, strum_macros::Display)]
-pub enum ValueToken {
-    SectionMetaInfoKey,
-    SectionMetaInfoValue,
-    MetaInfoKey,
-    MetaInfoValue,
-    Chord,
-    Extension,
-    Denominator,
-}
@@ -1,3 +1,5 @@
+use crate::util::note::split_note;
+
 pub fn is_token_char(ch: char) -> bool {
     matches!(
         ch,
@@ -9,38 +11,24 @@ pub fn is_chord_info_end_char(ch: char) -> bool {
     matches!(ch, '\n' | '\r' | '@' | '[' | ']' | '-' | '=' | '/' | ',')
 }
 
-#[derive(Debug, PartialEq, Clone)]
-pub struct LineColumn {
-    pub line_number: usize,
-    pub column_number: usize,
+/// Whether "-" after the chord being read means minor, like "C-7", "Bb-(9)".
+/// It is so only right after the root, and followed by a number or an extension.
+/// `rest` is the input from the "-".
+pub fn is_minor_dash(chord: &str, rest: &str) -> bool {
+    let is_root = matches!(split_note(chord), Some((_, _, "")));
+    let is_followed_by_extension =
+        matches!(rest.chars().nth(1), Some(c) if c.is_ascii_digit() || c == '(');
+
+    is_root && is_followed_by_extension
+}
+
+/// Whether "/" after the chord being read is a part of "6/9" (like "C6/9"), not a denominator.
+/// `rest` is the input from the "/".
+pub fn is_six_nine_slash(chord: &str, rest: &str) -> bool {
+    chord.ends_with('6') && rest.chars().nth(1) == Some('9')
 }
 
-pub fn next_char_with_position<I>(
-    iter: &mut std::iter::Peekable<I>,
-    line: &mut usize,
-    column: &mut usize,
-) -> Option<(char, LineColumn)>
-where
-    I: Iterator<Item = char>,
-{
-    match iter.next() {
-        Some('\n') => {
-            let position = LineColumn {
-                line_number: *line,
-                column_number: *column,
-            };
-            *line += 1;
-            *column = 1;
-            Some(('\n', position))
-        }
-        Some(ch) => {
-            let position = LineColumn {
-                line_number: *line,
-                column_number: *column,
-            };
-            *column += 1;
-            Some((ch, position))
-        }
-        None => None,
-    }
+/// Whether `rest` starts with a navigation mark (`|:`, `:|` or `<...>`), which ends the chord being read.
+pub fn is_navigation_mark_start(rest: &str) -> bool {
+    rest.starts_with('<') || rest.starts_with("|:") || rest.starts_with(":|")
 }
@@ -0,0 +1,446 @@
+use crate::error_code::{ErrorCode, ErrorInfo};
+use crate::parser::types::accidental::Accidental;
+use crate::parser::types::ast::Ast;
+use crate::parser::types::base::Base;
+use crate::parser::types::chord::Chord;
+use crate::parser::types::chord_block::ChordBlock;
+use crate::parser::types::chord_detailed::ChordDetailed;
+use crate::parser::types::chord_expression::ChordExpression;
+use crate::parser::types::chord_info::ChordInfo;
+use crate::parser::types::chord_info_meta::ChordInfoMeta;
+use crate::parser::types::key::Key;
+use crate::parser::types::poly_chord::PolyChord;
+use crate::stringifier::trivia::{Trivia, TriviaLine};
+use crate::util::note::{note_to_string, pitch_class, split_note};
+
+/// Which accidental is used to spell transposed notes
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Spelling {
+    Sharp,
+    Flat,
+}
+
+const SHARP_SPELLINGS: [(Base, Option<Accidental>); 12] = [
+    (Base::C, None),
+    (Base::C, Some(Accidental::Sharp)),
+    (Base::D, None),
+    (Base::D, Some(Accidental::Sharp)),
+    (Base::E, None),
+    (Base::F, None),
+    (Base::F, Some(Accidental::Sharp)),
+    (Base::G, None),
+    (Base::G, Some(Accidental::Sharp)),
+    (Base::A, None),
+    (Base::A, Some(Accidental::Sharp)),
+    (Base::B, None),
+];
+
+const FLAT_SPELLINGS: [(Base, Option<Accidental>); 12] = [
+    (Base::C, None),
+    (Base::D, Some(Accidental::Flat)),
+    (Base::D, None),
+    (Base::E, Some(Accidental::Flat)),
+    (Base::E, None),
+    (Base::F, None),
+    (Base::G, Some(Accidental::Flat)),
+    (Base::G, None),
+    (Base::A, Some(Accidental::Flat)),
+    (Base::A, None),
+    (Base::B, Some(Accidental::Flat)),
+    (Base::B, None),
+];
+
+/// Transpose every chord, denominator and key in the AST by `semitones`
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::parse_chord_progression_string;
+/// use chord_progression_parser::stringifier::stringify;
+/// use chord_progression_parser::transposer::{transpose, Spelling};
+///
+/// let ast = parse_chord_progression_string("[key=C]C-Am(7)/G").unwrap();
+/// let transposed = transpose(&ast, 3, Spelling::Flat);
+/// assert_eq!(stringify(&transposed), "[key=Eb]Eb - Cm(7)/Bb\n");
+/// ```
+pub fn transpose(ast: &Ast, semitones: i32, spelling: Spelling) -> Ast {
+    let mut transposed = ast.clone();
+
+    for section in transposed.iter_mut() {
+        for chord_block in section.chord_blocks.iter_mut() {
+            if let ChordBlock::Bar(bar) = chord_block {
+                for chord_info in bar.iter_mut() {
+                    transpose_chord_info(chord_info, semitones, spelling);
+                }
+            }
+        }
+    }
+
+    transposed
+}
+
+/// Transpose the chords of the chord macros in the trivia by `semitones`, like [`transpose`] for the AST
+///
+/// # Example
+/// ```rust
+/// use chord_progression_parser::parse_chord_progression_string;
+/// use chord_progression_parser::stringifier::stringify_with_trivia;
+/// use chord_progression_parser::stringifier::trivia::collect_trivia;
+/// use chord_progression_parser::transposer::{transpose, transpose_trivia, Spelling};
+///
+/// let input = "@def X=Am(7)/G\n[key=C]C-$X";
+/// let ast = parse_chord_progression_string(input).unwrap();
+/// let trivia = collect_trivia(input).unwrap();
+/// assert_eq!(
+///     stringify_with_trivia(
+///         &transpose(&ast, 3, Spelling::Flat),
+///         &transpose_trivia(&trivia, 3, Spelling::Flat),
+///         &Default::default()
+///     ),
+///     "@def X=Cm(7)/Bb\n[key=Eb]Eb - $X\n"
+/// );
+/// ```
+pub fn transpose_trivia(trivia: &Trivia, semitones: i32, spelling: Spelling) -> Trivia {
+    let mut transposed = trivia.clone();
+
+    for (_, line) in transposed.lines.iter_mut() {
+        if let TriviaLine::Definition(definition) = line {
+            transpose_chord_info(&mut definition.value, semitones, spelling);
+        }
+    }
+
+    transposed
+}
+
+/// Transpose the AST so that its first `[key=...]` becomes `to`.
+/// Transposing doesn't change the mode, so `to` should be in the mode of the first key (like `Cm` for `[key=Am]`).
+pub fn transpose_to_key(ast: &Ast, to: &Key) -> Result<Ast, ErrorInfo> {
+    let (semitones, spelling) = interval_to_key(ast, to)?;
+
+    Ok(transpose(ast, semitones, spelling))
+}
+
+/// The semitones and the spelling [`transpose_to_key`] transposes the AST by
+pub fn interval_to_key(ast: &Ast, to: &Key) -> Result<(i32, Spelling), ErrorInfo> {
+    let from = first_key(ast).ok_or(ErrorInfo {
+        code: ErrorCode::Trp1,
+        additional_info: None,
+        cause: None,
+    })?;
+
+    let (from_pitch_class, to_pitch_class) =
+        match (from.tonic_pitch_class(), to.tonic_pitch_class()) {
+            (Some(from_pitch_class), Some(to_pitch_class)) => (from_pitch_class, to_pitch_class),
+            (None, _) => {
+                return Err(ErrorInfo {
+                    code: ErrorCode::Trp1,
+                    additional_info: Some(from.to_string()),
+                    cause: None,
+                })
+            }
+            (_, None) => {
+                return Err(ErrorInfo {
+                    code: ErrorCode::Trp2,
+                    additional_info: Some(to.to_string()),
+                    cause: None,
+                })
+            }
+        };
+    if from.mode() != to.mode() {
+        return Err(ErrorInfo {
+            code: ErrorCode::Trp3,
+            additional_info: Some(format!("{} to {}", from, to)),
+            cause: None,
+        });
+    }
+
+    Ok((
+        to_pitch_class as i32 - from_pitch_class as i32,
+        key_spelling(to),
+    ))
+}
+
+/// the first key specified in the AST
+pub fn first_key(ast: &Ast) -> Option<Key> {
+    ast.iter()
+        .flat_map(|section| section.chord_blocks.iter())
+        .filter_map(|chord_block| match chord_block {
+            ChordBlock::Bar(bar) => Some(bar),
+            ChordBlock::Br | ChordBlock::Navigation(_) => None,
+        })
+        .flatten()
+        .flat_map(|chord_info| chord_info.meta_infos.iter())
+        .find_map(|meta_info| match meta_info {
+            ChordInfoMeta::Key(key) => Some(key.clone()),
+            _ => None,
+        })
+}
+
+/// spelling conventionally used in the key (flat keys are F, Bb, Eb, ... and Dm, Gm, Cm, ...)
+pub fn key_spelling(key: &Key) -> Spelling {
+    match key.key_signature() {
+        Some(key_signature) if key_signature < 0 => Spelling::Flat,
+        _ => Spelling::Sharp,
+    }
+}
+
+/// spelling of the key which the AST is transposed to by `semitones`, the one with fewer accidentals.
+/// the AST without keys is regarded as in C major.
+pub fn transposed_spelling(ast: &Ast, semitones: i32) -> Spelling {
+    let key = first_key(ast).unwrap_or(Key::major(Base::C, None));
+    let (tonic_pitch_class, mode) = match (key.tonic_pitch_class(), key.mode()) {
+        (Some(tonic_pitch_class), Some(mode)) => (tonic_pitch_class, mode),
+        _ => return Spelling::Sharp,
+    };
+
+    let pitch_class = (tonic_pitch_class as i32 + semitones).rem_euclid(12) as u8;
+    let accidentals = |spelling: Spelling| {
+        let (tonic, accidental) = spell(pitch_class, spelling);
+        Key::new(tonic, accidental, mode)
+            .key_signature()
+            .map_or(i8::MAX, |key_signature| key_signature.abs())
+    };
+
+    if accidentals(Spelling::Flat) < accidentals(Spelling::Sharp) {
+        Spelling::Flat
+    } else {
+        Spelling::Sharp
+    }
+}
+
+fn spell(pitch_class: u8, spelling: Spelling) -> (Base, Option<Accidental>) {
+    match spelling {
+        Spelling::Sharp => SHARP_SPELLINGS[pitch_class as usize % 12].clone(),
+        Spelling::Flat => FLAT_SPELLINGS[pitch_class as usize % 12].clone(),
+    }
+}
+
+fn transpose_note_string(s: &str, semitones: i32, spelling: Spelling) -> String {
+    match split_note(s) {
+        Some((base, accidental, rest)) => {
+            let pitch_class = (pitch_class(&base, &accidental) as i32 + semitones).rem_euclid(12);
+            let (base, accidental) = spell(pitch_class as u8, spelling);
+            [note_to_string(&base, &accidental), rest.to_string()].concat()
+        }
+        None => s.to_string(),
+    }
+}
+
+fn transpose_chord_info(chord_info: &mut ChordInfo, semitones: i32, spelling: Spelling) {
+    for meta_info in chord_info.meta_infos.iter_mut() {
+        match meta_info {
+            ChordInfoMeta::Key(key) => {
+                if let (Some(tonic_pitch_class), Some(mode)) = (key.tonic_pitch_class(), key.mode())
+                {
+                    let pitch_class = (tonic_pitch_class as i32 + semitones).rem_euclid(12);
+                    let (tonic, accidental) = spell(pitch_class as u8, spelling);
+                    *key = Key::new(tonic, accidental, mode);
+                }
+            }
+            // not related to the pitch
+            ChordInfoMeta::Time(_)
+            | ChordInfoMeta::Tempo(_)
+            | ChordInfoMeta::Ritardando
+            | ChordInfoMeta::Accelerando
+            | ChordInfoMeta::Fermata
+            | ChordInfoMeta::Dynamics(_)
+            | ChordInfoMeta::Articulation(_)
+            | ChordInfoMeta::Custom { .. } => {}
+        }
+    }
+
+    match &chord_info.chord_expression {
+        ChordExpression::Chord(chord) => {
+            chord_info.chord_expression =
+                ChordExpression::Chord(transpose_chord(chord, semitones, spelling));
+        }
+        ChordExpression::PolyChord(poly_chord) => {
+            chord_info.chord_expression = ChordExpression::PolyChord(PolyChord {
+                upper: transpose_chord(&poly_chord.upper, semitones, spelling),
+                lower: transpose_chord(&poly_chord.lower, semitones, spelling),
+            });
+        }
+        ChordExpression::UnIdentified | ChordExpression::NoChord | ChordExpression::Same => {}
+    }
+
+    if let Some(denominator) = &chord_info.denominator {
+        chord_info.denominator = Some(transpose_note_string(denominator, semitones, spelling));
+    }
+}
+
+fn transpose_chord(chord: &Chord, semitones: i32, spelling: Spelling) -> Chord {
+    let pitch_class = (chord.detailed.root_pitch_class() as i32 + semitones).rem_euclid(12);
+    let (base, accidental) = spell(pitch_class as u8, spelling);
+    let plain = match split_note(&chord.plain) {
+        Some((_, _, rest)) => [note_to_string(&base, &accidental), rest.to_string()].concat(),
+        None => chord.plain.clone(),
+    };
+
+    Chord {
+        plain,
+        detailed: ChordDetailed {
+            base,
+            accidental,
+            chord_type: chord.detailed.chord_type.clone(),
+            extensions: chord.detailed.extensions.clone(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_chord_progression_string;
+    use crate::parser::types::key::Mode;
+    use crate::stringifier::stringify;
+    use crate::stringifier::stringify_with_trivia;
+    use crate::stringifier::trivia::collect_trivia;
+
+    #[test]
+    fn transpose_up_with_sharp() {
+        let ast = parse_chord_progression_string("[key=C]C-Am(7)-F/A-G(7)").unwrap();
+
+        assert_eq!(
+            stringify(&transpose(&ast, 2, Spelling::Sharp)),
+            "[key=D]D - Bm(7) - G/B - A(7)\n"
+        );
+    }
+
+    #[test]
+    fn spelling_with_fewer_accidentals() {
+        let ast = parse_chord_progression_string("[key=C]C-G").unwrap();
+        assert_eq!(transposed_spelling(&ast, 3), Spelling::Flat);
+        assert_eq!(transposed_spelling(&ast, 4), Spelling::Sharp);
+
+        let ast = parse_chord_progression_string("[key=Am]Am-E").unwrap();
+        assert_eq!(transposed_spelling(&ast, 1), Spelling::Flat);
+
+        let ast = parse_chord_progression_string("C-G").unwrap();
+        assert_eq!(transposed_spelling(&ast, -2), Spelling::Flat);
+    }
+
+    #[test]
+    fn transpose_down_wraps_around() {
+        let ast = parse_chord_progression_string("C#m(7)-?/C-%").unwrap();
+
+        assert_eq!(
+            stringify(&transpose(&ast, -3, Spelling::Flat)),
+            "Bbm(7) - ?/A - %\n"
+        );
+    }
+
+    #[test]
+    fn detailed_is_transposed() {
+        let ast = parse_chord_progression_string("Bb").unwrap();
+        let transposed = transpose(&ast, 1, Spelling::Sharp);
+
+        match &transposed[0].chord_blocks[0] {
+            ChordBlock::Bar(bar) => match &bar[0].chord_expression {
+                ChordExpression::Chord(chord) => {
+                    assert_eq!(chord.detailed.base, Base::B);
+                    assert_eq!(chord.detailed.accidental, None);
+                }
+                _ => panic!("chord expected"),
+            },
+            _ => panic!("bar expected"),
+        }
+    }
+
+    #[test]
+    fn transpose_to_flat_key() {
+        let ast = parse_chord_progression_string("[key=G]G-D/F#-Em-C").unwrap();
+
+        assert_eq!(
+            stringify(&transpose_to_key(&ast, &Key::major(Base::F, None)).unwrap()),
+            "[key=F]F - C/E - Dm - Bb\n"
+        );
+    }
+
+    #[test]
+    fn both_parts_of_poly_chord_are_transposed() {
+        let ast = parse_chord_progression_string("{D|C7}/E").unwrap();
+
+        assert_eq!(
+            stringify(&transpose(&ast, -2, Spelling::Flat)),
+            "{C|Bb7}/D\n"
+        );
+    }
+
+    #[test]
+    fn mode_of_key_is_kept() {
+        let ast = parse_chord_progression_string("[key=Ddorian]Dm(7)-G(7)").unwrap();
+
+        assert_eq!(
+            stringify(&transpose(&ast, 2, Spelling::Sharp)),
+            "[key=Edorian]Em(7) - A(7)\n"
+        );
+    }
+
+    #[test]
+    fn transpose_to_key_without_key() {
+        let ast = parse_chord_progression_string("C-G").unwrap();
+
+        assert_eq!(
+            transpose_to_key(&ast, &Key::major(Base::D, None))
+                .unwrap_err()
+                .code,
+            ErrorCode::Trp1
+        );
+    }
+
+    #[test]
+    fn transpose_to_key_in_other_mode() {
+        let ast = parse_chord_progression_string("[key=Am]Am-Dm-E(7)").unwrap();
+
+        let error_info = transpose_to_key(&ast, &Key::major(Base::C, None)).unwrap_err();
+        assert_eq!(error_info.code, ErrorCode::Trp3);
+        assert_eq!(error_info.additional_info, Some("Am to C".to_string()));
+        assert_eq!(
+            stringify(&transpose_to_key(&ast, &Key::minor(Base::C, None)).unwrap()),
+            "[key=Cm]Cm - Fm - G(7)\n"
+        );
+    }
+
+    #[test]
+    fn transpose_to_unidentified_key() {
+        let ast = parse_chord_progression_string("[key=C]C-G").unwrap();
+
+        assert_eq!(
+            transpose_to_key(&ast, &Key::UnIdentified).unwrap_err().code,
+            ErrorCode::Trp2
+        );
+    }
+
+    #[test]
+    fn transpose_chord_macros() {
+        let input = "@def X=Am(7)/G // minor\n@section=A\n[key=C]C - $X - [key=G]$X";
+        let ast = parse_chord_progression_string(input).unwrap();
+        let trivia = collect_trivia(input).unwrap();
+        let (semitones, spelling) = interval_to_key(&ast, &Key::major(Base::D, None)).unwrap();
+
+        assert_eq!((semitones, spelling), (2, Spelling::Sharp));
+        assert_eq!(
+            stringify_with_trivia(
+                &transpose(&ast, semitones, spelling),
+                &transpose_trivia(&trivia, semitones, spelling),
+                &Default::default()
+            ),
+            "@def X=Bm(7)/A // minor\n@section=A\n[key=D]D - $X - [key=A]$X\n"
+        );
+    }
+
+    #[test]
+    fn spelling_of_keys() {
+        assert_eq!(
+            key_spelling(&Key::major(Base::B, Some(Accidental::Flat))),
+            Spelling::Flat
+        );
+        assert_eq!(key_spelling(&Key::minor(Base::D, None)), Spelling::Flat);
+        assert_eq!(key_spelling(&Key::major(Base::D, None)), Spelling::Sharp);
+        assert_eq!(key_spelling(&Key::major(Base::F, None)), Spelling::Flat);
+        assert_eq!(key_spelling(&Key::major(Base::C, None)), Spelling::Sharp);
+        assert_eq!(
+            key_spelling(&Key::new(Base::G, None, Mode::Dorian)),
+            Spelling::Flat
+        );
+    }
+}
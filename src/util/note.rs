@@ -0,0 +1,126 @@
+use crate::parser::types::{
+    accidental::{Accidental, AccidentalNotation},
+    base::Base,
+};
+
+/// split a string starting with a note name into the note and the rest
+///
+/// e.g. "C#m(7)" -> (C, Some(#), "m(7)"), "B♭" -> (B, Some(b), "")
+pub fn split_note(s: &str) -> Option<(Base, Option<Accidental>, &str)> {
+    let base = match s.chars().next() {
+        Some('A') => Base::A,
+        Some('B') => Base::B,
+        Some('C') => Base::C,
+        Some('D') => Base::D,
+        Some('E') => Base::E,
+        Some('F') => Base::F,
+        Some('G') => Base::G,
+        _ => return None,
+    };
+
+    let (accidental, rest) = Accidental::split_prefix(&s[1..]);
+    Some((base, accidental, rest))
+}
+
+/// pitch class of the note (C = 0, ..., B = 11)
+pub fn pitch_class(base: &Base, accidental: &Option<Accidental>) -> u8 {
+    let accidental = accidental.as_ref().map_or(0, |a| a.semitones());
+    (base.pitch_class() as i8 + accidental).rem_euclid(12) as u8
+}
+
+/// accidental to spell the pitch class with the base, e.g. (B, 10) -> Some(b), (F, 7) -> Some(##).
+/// `None` when the base is the pitch class itself (or it is more than two semitones away).
+pub fn accidental_to_spell(base: &Base, pitch_class: u8) -> Option<Accidental> {
+    match (pitch_class as i8 - base.pitch_class() as i8 + 6).rem_euclid(12) - 6 {
+        -2 => Some(Accidental::DoubleFlat),
+        -1 => Some(Accidental::Flat),
+        1 => Some(Accidental::Sharp),
+        2 => Some(Accidental::DoubleSharp),
+        _ => None,
+    }
+}
+
+/// e.g. (C, Some(#)) -> "C#"
+pub fn note_to_string(base: &Base, accidental: &Option<Accidental>) -> String {
+    note_to_string_with(base, accidental, AccidentalNotation::Ascii)
+}
+
+/// e.g. (C, Some(#), Unicode) -> "C♯"
+pub fn note_to_string_with(
+    base: &Base,
+    accidental: &Option<Accidental>,
+    notation: AccidentalNotation,
+) -> String {
+    let accidental = accidental
+        .as_ref()
+        .map_or("", |accidental| accidental.to_string_with(notation));
+    format!("{:?}{}", base, accidental)
+}
+
+/// rewrite the note at the start of the string with the notation, e.g. ("B♭m", Ascii) -> "Bbm"
+pub fn renotate_note(s: &str, notation: AccidentalNotation) -> String {
+    match split_note(s) {
+        Some((base, accidental, rest)) => [
+            note_to_string_with(&base, &accidental, notation),
+            rest.to_string(),
+        ]
+        .concat(),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_note_with_accidental() {
+        assert_eq!(
+            split_note("C#m(7)"),
+            Some((Base::C, Some(Accidental::Sharp), "m(7)"))
+        );
+        assert_eq!(
+            split_note("Bb"),
+            Some((Base::B, Some(Accidental::Flat), ""))
+        );
+        assert_eq!(split_note("E"), Some((Base::E, None, "")));
+    }
+
+    #[test]
+    fn split_note_with_unicode_and_double_accidental() {
+        assert_eq!(
+            split_note("C♯m"),
+            Some((Base::C, Some(Accidental::Sharp), "m"))
+        );
+        assert_eq!(
+            split_note("Bbb7"),
+            Some((Base::B, Some(Accidental::DoubleFlat), "7"))
+        );
+        assert_eq!(
+            split_note("F𝄪"),
+            Some((Base::F, Some(Accidental::DoubleSharp), ""))
+        );
+    }
+
+    #[test]
+    fn renotate() {
+        assert_eq!(renotate_note("B♭m", AccidentalNotation::Ascii), "Bbm");
+        assert_eq!(renotate_note("C#(7)", AccidentalNotation::Unicode), "C♯(7)");
+        assert_eq!(renotate_note("E♮", AccidentalNotation::Ascii), "E");
+        assert_eq!(renotate_note("?", AccidentalNotation::Unicode), "?");
+    }
+
+    #[test]
+    fn split_note_without_note() {
+        assert_eq!(split_note("?"), None);
+        assert_eq!(split_note(""), None);
+    }
+
+    #[test]
+    fn pitch_class_wraps_around() {
+        assert_eq!(pitch_class(&Base::C, &Some(Accidental::Flat)), 11);
+        assert_eq!(pitch_class(&Base::B, &Some(Accidental::Sharp)), 0);
+        assert_eq!(pitch_class(&Base::C, &Some(Accidental::DoubleFlat)), 10);
+        assert_eq!(pitch_class(&Base::B, &Some(Accidental::DoubleSharp)), 1);
+    }
+}
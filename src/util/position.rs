@@ -1,4 +1,10 @@
-#[derive(Debug, PartialEq, Clone)]
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+/// Position in the input. The line and column numbers start from 1, and the column is counted in characters.
+#[typeshare]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Position {
     pub line_number: usize,
     pub column_number: usize,
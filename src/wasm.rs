@@ -0,0 +1,946 @@
+//! Exports to JavaScript. The results are plain objects, typed by typeshare and the TypeScript section below.
+
+use std::fmt::Display;
+use std::ops::Range;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use typeshare::typeshare;
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+
+use crate::diagnostic::{render, Diagnostic, RenderOptions, Severity};
+use crate::error_code::{ErrorCode, ErrorInfo, ErrorInfoWithPosition};
+use crate::highlight::{encode_semantic_tokens, legend, semantic_tokens, SemanticToken};
+use crate::include::{
+    parse_with_includes_and_warnings, JsCallbackLoader, MemoryLoader, SourceDiagnostic, SourceError,
+};
+use crate::linter::{LintConfig, LintRule, LintWarning};
+use crate::parser::incremental::{Changes, IncrementalParse, TextEdit};
+use crate::parser::meta_registry::MetaRegistry;
+use crate::parser::options::ParseOptions;
+use crate::parser::Ast;
+use crate::stringifier::trivia::collect_trivia;
+use crate::stringifier::{stringify, stringify_with_trivia, StringifyOptions};
+use crate::suggestion::suggestions;
+use crate::tokenizer::tokenize;
+use crate::tokenizer::types::token_with_position::TokenWithPosition;
+use crate::transposer::{transpose, transpose_trivia, transposed_spelling};
+use crate::util::position::Position;
+use crate::{
+    lint_chord_progression_string, parse_chord_progression_string,
+    parse_chord_progression_string_with_warnings,
+};
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPESCRIPT_TYPES: &str = r#"
+import type {
+  Ast,
+  Diagnostic,
+  EditChanges,
+  ErrorResult,
+  Highlighted,
+  LintConfig,
+  LintWarning,
+  MetaRegistry,
+  ParseOptions,
+  Parsed,
+  SemanticTokensLegend,
+  Tokenized,
+  Transposed,
+  Warnings,
+} from "./generatedTypes";
+
+/** `success` tells which of the two the result is */
+export type JsResult<T> =
+  | ({ success: true } & T)
+  | { success: false; error: ErrorResult };
+
+export type ParsedResult = JsResult<Parsed>;
+
+export type ValidatedResult = JsResult<Warnings<Diagnostic>>;
+
+export type LintResult = JsResult<Warnings<LintWarning>>;
+
+export type TokenizedResult = JsResult<Tokenized>;
+
+export type TransposedResult = JsResult<Transposed>;
+
+export type HighlightedResult = JsResult<Highlighted>;
+
+/** `ParsedResult` after an edit, with what the edit changed */
+export type EditedResult = ParsedResult & EditChanges;
+"#;
+
+/// serializes `None` as `null` and maps as plain objects, like JSON
+const SERIALIZER: serde_wasm_bindgen::Serializer =
+    serde_wasm_bindgen::Serializer::json_compatible();
+
+/// The error which stops parsing
+#[typeshare]
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ErrorResult {
+    #[typeshare(serialized_as = "ErrorCode")]
+    code: ErrorCode,
+    additional_info: Option<String>,
+    /// inner error which caused this one, like `BS-1` of the invalid chord (`CHO-1`)
+    #[typeshare(serialized_as = "Option<ErrorCode>")]
+    cause: Option<ErrorCode>,
+    /// corrections of the input, like the chord names close to the wrong one
+    suggestions: Vec<String>,
+    /// the included file the error is in (only with parseChordProgressionStringWithFiles or parseChordProgressionStringWithLoader)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_name: Option<Option<String>>,
+    position: Position,
+}
+
+impl From<ErrorInfoWithPosition> for ErrorResult {
+    fn from(error_info: ErrorInfoWithPosition) -> ErrorResult {
+        ErrorResult {
+            code: error_info.error.code,
+            suggestions: suggestions(&error_info.error),
+            additional_info: error_info.error.additional_info,
+            cause: error_info.error.cause,
+            file_name: None,
+            position: error_info.position,
+        }
+    }
+}
+
+impl From<SourceError> for ErrorResult {
+    fn from(source_error: SourceError) -> ErrorResult {
+        ErrorResult {
+            file_name: Some(source_error.file_name),
+            ..ErrorResult::from(source_error.error_info)
+        }
+    }
+}
+
+/// A warning of the valid input, which doesn't stop parsing
+#[typeshare]
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename = "Diagnostic", rename_all = "camelCase")]
+struct DiagnosticResult {
+    severity: Severity,
+    #[typeshare(serialized_as = "ErrorCode")]
+    code: ErrorCode,
+    additional_info: Option<String>,
+    /// the file the warning is in (only with parseChordProgressionStringWithFiles or parseChordProgressionStringWithLoader)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_name: Option<Option<String>>,
+    position: Position,
+}
+
+impl From<Diagnostic> for DiagnosticResult {
+    fn from(diagnostic: Diagnostic) -> DiagnosticResult {
+        DiagnosticResult {
+            severity: diagnostic.severity,
+            code: diagnostic.error.code,
+            additional_info: diagnostic.error.additional_info,
+            file_name: None,
+            position: diagnostic.position,
+        }
+    }
+}
+
+impl From<SourceDiagnostic> for DiagnosticResult {
+    fn from(source_diagnostic: SourceDiagnostic) -> DiagnosticResult {
+        DiagnosticResult {
+            file_name: Some(source_diagnostic.file_name),
+            ..DiagnosticResult::from(source_diagnostic.diagnostic)
+        }
+    }
+}
+
+/// A warning of the lint rule, with the message in the language asked for
+#[typeshare]
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename = "LintWarning", rename_all = "camelCase")]
+struct LintWarningResult {
+    rule: LintRule,
+    severity: Severity,
+    message: String,
+    additional_info: Option<String>,
+    position: Position,
+}
+
+impl LintWarningResult {
+    fn new(warning: LintWarning, lang: &str) -> LintWarningResult {
+        LintWarningResult {
+            rule: warning.rule,
+            severity: warning.severity,
+            message: warning.rule.message(lang),
+            additional_info: warning.additional_info,
+            position: warning.position,
+        }
+    }
+}
+
+/// `{ success: true, ...value }` or `{ success: false, error }`
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+enum JsResult<T> {
+    Success {
+        success: bool,
+        #[serde(flatten)]
+        value: T,
+    },
+    Failure {
+        success: bool,
+        error: ErrorResult,
+    },
+}
+
+impl<T: Serialize, E: Into<ErrorResult>> From<Result<T, E>> for JsResult<T> {
+    fn from(result: Result<T, E>) -> JsResult<T> {
+        match result {
+            Ok(value) => JsResult::Success {
+                success: true,
+                value,
+            },
+            Err(error) => JsResult::Failure {
+                success: false,
+                error: error.into(),
+            },
+        }
+    }
+}
+
+fn to_js<T: Serialize>(value: &T) -> JsValue {
+    value.serialize(&SERIALIZER).unwrap()
+}
+
+/// `OTHER-3` for the argument of the wrong type, with its name and the reason
+fn invalid_argument(name: &str, reason: impl Display) -> ErrorResult {
+    ErrorResult::from(ErrorInfoWithPosition {
+        error: ErrorInfo {
+            code: ErrorCode::Other3,
+            additional_info: Some(format!("{}: {}", name, reason)),
+            cause: None,
+        },
+        position: Position {
+            line_number: 1,
+            column_number: 1,
+            length: 0,
+        },
+    })
+}
+
+fn from_js<T: DeserializeOwned>(name: &str, value: JsValue) -> Result<T, ErrorResult> {
+    serde_wasm_bindgen::from_value(value).map_err(|error| invalid_argument(name, error))
+}
+
+/// The parsed AST with its warnings
+#[typeshare]
+#[derive(Debug, PartialEq, Serialize)]
+struct Parsed {
+    ast: Ast,
+    /// in the order of the positions
+    /// (with the included files, the ones of the input, then the ones of the included files in the order of `@include`)
+    warnings: Vec<DiagnosticResult>,
+}
+
+impl From<(Ast, Vec<Diagnostic>)> for Parsed {
+    fn from((ast, warnings): (Ast, Vec<Diagnostic>)) -> Parsed {
+        Parsed {
+            ast,
+            warnings: warnings.into_iter().map(DiagnosticResult::from).collect(),
+        }
+    }
+}
+
+/// The warnings only, without the AST
+#[typeshare]
+#[derive(Debug, PartialEq, Serialize)]
+struct Warnings<T> {
+    /// in the order of the positions
+    warnings: Vec<T>,
+}
+
+#[typeshare]
+#[derive(Debug, PartialEq, Serialize)]
+struct Tokenized {
+    tokens: Vec<TokenWithPosition>,
+}
+
+#[typeshare]
+#[derive(Debug, PartialEq, Serialize)]
+struct Transposed {
+    ast: Ast,
+    /// the transposed chord progression string
+    output: String,
+}
+
+#[typeshare]
+#[derive(Debug, PartialEq, Serialize)]
+struct Highlighted {
+    /// in the order of the positions
+    tokens: Vec<SemanticToken>,
+    /// `tokens` encoded as `data` of the semantic tokens of LSP (`new Uint32Array(data)` for Monaco)
+    data: Vec<u32>,
+}
+
+/// Same as `SemanticTokensLegend` of LSP and Monaco
+#[typeshare]
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SemanticTokensLegend {
+    token_types: Vec<String>,
+    token_modifiers: Vec<String>,
+}
+
+/// Range of the indexes, with the end excluded
+#[typeshare]
+#[derive(Debug, PartialEq, Serialize)]
+struct IndexRange {
+    start: usize,
+    end: usize,
+}
+
+impl From<Range<usize>> for IndexRange {
+    fn from(range: Range<usize>) -> IndexRange {
+        IndexRange {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+/// What an edit changed
+#[typeshare]
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EditChanges {
+    /// indexes of the sections of the new AST which differ from the previous one
+    changed_sections: IndexRange,
+    /// line numbers of the new input which are re-parsed, starting from 1
+    changed_lines: IndexRange,
+}
+
+impl From<Changes> for EditChanges {
+    fn from(changes: Changes) -> EditChanges {
+        EditChanges {
+            changed_sections: changes.sections.into(),
+            changed_lines: changes.lines.into(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Edited {
+    #[serde(flatten)]
+    result: JsResult<Parsed>,
+    #[serde(flatten)]
+    changes: EditChanges,
+}
+
+impl Edited {
+    fn new(parse: &IncrementalParse, changes: Changes) -> Edited {
+        Edited {
+            result: JsResult::from(parse.result().clone().map(Parsed::from)),
+            changes: changes.into(),
+        }
+    }
+}
+
+fn parse_to_js(input: &str, options: Result<ParseOptions, ErrorResult>) -> JsValue {
+    to_js(&JsResult::from(options.and_then(|options| {
+        parse_chord_progression_string_with_warnings(input, &options)
+            .map(Parsed::from)
+            .map_err(ErrorResult::from)
+    })))
+}
+
+/// @param {string} input - The chord progression string to parse.
+/// @returns {ParsedResult} - The parsed result.
+#[wasm_bindgen(
+    js_name = "parseChordProgressionString",
+    skip_jsdoc,
+    unchecked_return_type = "ParsedResult"
+)]
+pub fn parse_chord_progression_string_js(input: &str) -> JsValue {
+    parse_to_js(input, Ok(ParseOptions::default()))
+}
+
+/// @param {string} input - The chord progression string to parse.
+/// @param {MetaRegistry} registry - The custom section and chord meta keys with their validators.
+/// @returns {ParsedResult} - The parsed result.
+#[wasm_bindgen(
+    js_name = "parseChordProgressionStringWithRegistry",
+    skip_jsdoc,
+    unchecked_return_type = "ParsedResult"
+)]
+pub fn parse_chord_progression_string_with_registry_js(
+    input: &str,
+    #[wasm_bindgen(unchecked_param_type = "MetaRegistry")] registry: JsValue,
+) -> JsValue {
+    let options = from_js("registry", registry).map(|registry: MetaRegistry| ParseOptions {
+        registry,
+        ..Default::default()
+    });
+
+    parse_to_js(input, options)
+}
+
+/// @param {string} input - The chord progression string to parse.
+/// @param {ParseOptions} options - The options of the parser, like the lenient rules and the custom meta keys.
+/// @returns {ParsedResult} - The parsed result.
+#[wasm_bindgen(
+    js_name = "parseChordProgressionStringWithOptions",
+    skip_jsdoc,
+    unchecked_return_type = "ParsedResult"
+)]
+pub fn parse_chord_progression_string_with_options_js(
+    input: &str,
+    #[wasm_bindgen(unchecked_param_type = "ParseOptions")] options: JsValue,
+) -> JsValue {
+    parse_to_js(input, from_js("options", options))
+}
+
+/// @param {string} input - The chord progression string to validate.
+/// @param {ParseOptions} options - The options of the parser.
+/// @returns {ValidatedResult} - The warnings, or the error if the input can't be parsed.
+#[wasm_bindgen(
+    js_name = "validateChordProgressionString",
+    skip_jsdoc,
+    unchecked_return_type = "ValidatedResult"
+)]
+pub fn validate_chord_progression_string_js(
+    input: &str,
+    #[wasm_bindgen(unchecked_param_type = "ParseOptions")] options: JsValue,
+) -> JsValue {
+    to_js(&JsResult::from(from_js("options", options).and_then(
+        |options: ParseOptions| {
+            parse_chord_progression_string_with_warnings(input, &options)
+                .map(|(_, warnings)| Warnings {
+                    warnings: warnings.into_iter().map(DiagnosticResult::from).collect(),
+                })
+                .map_err(ErrorResult::from)
+        },
+    )))
+}
+
+/// @param {string} input - The chord progression string to lint.
+/// @param {LintConfig} config - The rules to check and their severities.
+/// @param {string} lang - The language of the messages, like "en" or "ja". Unknown languages fall back to "en".
+/// @returns {LintResult} - The warnings, or the error if the input can't be parsed.
+#[wasm_bindgen(
+    js_name = "lintChordProgressionString",
+    skip_jsdoc,
+    unchecked_return_type = "LintResult"
+)]
+pub fn lint_chord_progression_string_js(
+    input: &str,
+    #[wasm_bindgen(unchecked_param_type = "LintConfig")] config: JsValue,
+    lang: &str,
+) -> JsValue {
+    to_js(&JsResult::from(from_js("config", config).and_then(
+        |config: LintConfig| {
+            lint_chord_progression_string(input, &ParseOptions::default(), &config)
+                .map(|warnings| Warnings {
+                    warnings: warnings
+                        .into_iter()
+                        .map(|warning| LintWarningResult::new(warning, lang))
+                        .collect(),
+                })
+                .map_err(ErrorResult::from)
+        },
+    )))
+}
+
+/// @param {string} input - The chord progression string to tokenize.
+/// @returns {TokenizedResult} - The tokens with their positions. The comments are not included.
+#[wasm_bindgen(
+    js_name = "tokenizeChordProgressionString",
+    skip_jsdoc,
+    unchecked_return_type = "TokenizedResult"
+)]
+pub fn tokenize_chord_progression_string_js(input: &str) -> JsValue {
+    to_js(&JsResult::from(
+        tokenize(input).map(|tokens| Tokenized { tokens }),
+    ))
+}
+
+/// @returns {SemanticTokensLegend} - The names of the token types of highlightChordProgressionString, in the order of the indexes.
+#[wasm_bindgen(
+    js_name = "semanticTokensLegend",
+    skip_jsdoc,
+    unchecked_return_type = "SemanticTokensLegend"
+)]
+pub fn semantic_tokens_legend_js() -> JsValue {
+    to_js(&SemanticTokensLegend {
+        token_types: legend(),
+        token_modifiers: vec![],
+    })
+}
+
+/// @param {string} input - The chord progression string to highlight.
+/// @returns {HighlightedResult} - The semantic tokens, or the error if the input can't be tokenized.
+#[wasm_bindgen(
+    js_name = "highlightChordProgressionString",
+    skip_jsdoc,
+    unchecked_return_type = "HighlightedResult"
+)]
+pub fn highlight_chord_progression_string_js(input: &str) -> JsValue {
+    to_js(&JsResult::from(semantic_tokens(input).map(|tokens| {
+        Highlighted {
+            data: encode_semantic_tokens(&tokens),
+            tokens,
+        }
+    })))
+}
+
+/// @param {Ast} ast - The AST to stringify, like the one of ParsedResult.
+/// @returns {string | undefined} - The chord progression string, or undefined if the AST is invalid.
+#[wasm_bindgen(js_name = "stringifyChordProgression", skip_jsdoc)]
+pub fn stringify_chord_progression_js(
+    #[wasm_bindgen(unchecked_param_type = "Ast")] ast: JsValue,
+) -> Option<String> {
+    let ast: Ast = serde_wasm_bindgen::from_value(ast).ok()?;
+
+    Some(stringify(&ast))
+}
+
+/// @param {string} input - The chord progression string to transpose.
+/// @param {number} semitones - The interval to transpose by, negative for down.
+/// @returns {TransposedResult} - The transposed AST and its string with the comments and the chord macros of the input, spelled in the key with fewer accidentals.
+#[wasm_bindgen(
+    js_name = "transposeChordProgressionString",
+    skip_jsdoc,
+    unchecked_return_type = "TransposedResult"
+)]
+pub fn transpose_chord_progression_string_js(input: &str, semitones: i32) -> JsValue {
+    let result =
+        parse_chord_progression_string(input).and_then(|ast| Ok((ast, collect_trivia(input)?)));
+    to_js(&JsResult::from(result.map(|(ast, trivia)| {
+        let spelling = transposed_spelling(&ast, semitones);
+        let ast = transpose(&ast, semitones, spelling);
+        let trivia = transpose_trivia(&trivia, semitones, spelling);
+        Transposed {
+            output: stringify_with_trivia(&ast, &trivia, &StringifyOptions::default()),
+            ast,
+        }
+    })))
+}
+
+/// Parser of the text in the editor, which re-parses only the sections changed by the edits
+#[wasm_bindgen(js_name = "IncrementalParser")]
+pub struct IncrementalParserJs {
+    parse: IncrementalParse,
+}
+
+/// byte offset of the UTF-16 offset (of JavaScript strings), clamped to the input
+fn byte_offset(input: &str, utf16_offset: usize) -> usize {
+    let mut utf16_count = 0;
+    for (index, char) in input.char_indices() {
+        if utf16_count >= utf16_offset {
+            return index;
+        }
+        utf16_count += char.len_utf16();
+    }
+    input.len()
+}
+
+#[wasm_bindgen(js_class = "IncrementalParser")]
+impl IncrementalParserJs {
+    /// @param {string} input - The chord progression string to parse.
+    /// @param {ParseOptions} options - The options of the parser.
+    /// @throws {ErrorResult} - The error if the options are invalid.
+    #[wasm_bindgen(constructor, skip_jsdoc)]
+    pub fn new(
+        input: &str,
+        #[wasm_bindgen(unchecked_param_type = "ParseOptions")] options: JsValue,
+    ) -> Result<IncrementalParserJs, JsValue> {
+        let options: ParseOptions = from_js("options", options).map_err(|error| to_js(&error))?;
+
+        Ok(IncrementalParserJs {
+            parse: IncrementalParse::new(input, options),
+        })
+    }
+
+    /// @returns {ParsedResult} - The parsed result of the current input.
+    #[wasm_bindgen(skip_jsdoc, unchecked_return_type = "ParsedResult")]
+    pub fn result(&self) -> JsValue {
+        to_js(&JsResult::from(
+            self.parse.result().clone().map(Parsed::from),
+        ))
+    }
+
+    /// @param {number} from - The start of the replaced text, in UTF-16 code units like the indexes of strings.
+    /// @param {number} to - The end of the replaced text (excluded).
+    /// @param {string} text - The new text.
+    /// @returns {EditedResult} - The parsed result of the new input, and what the edit changed.
+    #[wasm_bindgen(skip_jsdoc, unchecked_return_type = "EditedResult")]
+    pub fn edit(&mut self, from: usize, to: usize, text: &str) -> JsValue {
+        let start = byte_offset(self.parse.input(), from);
+        let end = byte_offset(self.parse.input(), to).max(start);
+        let changes = self.parse.edit(&TextEdit {
+            range: start..end,
+            text: text.to_string(),
+        });
+
+        to_js(&Edited::new(&self.parse, changes))
+    }
+}
+
+/// @param {string} input - The chord progression string to parse.
+/// @param {Record<string, string>} files - The contents of the files used in `@include`, keyed by the path.
+/// @returns {ParsedResult} - The parsed result. The error and the warnings have `fileName` (null for the input).
+#[wasm_bindgen(
+    js_name = "parseChordProgressionStringWithFiles",
+    skip_jsdoc,
+    unchecked_return_type = "ParsedResult"
+)]
+pub fn parse_chord_progression_string_with_files_js(
+    input: &str,
+    #[wasm_bindgen(unchecked_param_type = "Record<string, string>")] files: JsValue,
+) -> JsValue {
+    source_result_to_js(from_js("files", files).and_then(|files| {
+        parse_with_includes_and_warnings(input, None, &MemoryLoader::new(files))
+            .map_err(ErrorResult::from)
+    }))
+}
+
+/// @param {string} input - The chord progression string to parse.
+/// @param {(path: string) => string | undefined} load - Returns the content of the file used in `@include`, or undefined if not found.
+/// @returns {ParsedResult} - The parsed result. The error and the warnings have `fileName` (null for the input).
+#[wasm_bindgen(
+    js_name = "parseChordProgressionStringWithLoader",
+    skip_jsdoc,
+    unchecked_return_type = "ParsedResult"
+)]
+pub fn parse_chord_progression_string_with_loader_js(
+    input: &str,
+    #[wasm_bindgen(unchecked_param_type = "(path: string) => string | undefined")]
+    load: js_sys::Function,
+) -> JsValue {
+    let loader = JsCallbackLoader { load };
+
+    source_result_to_js(parse_with_includes_and_warnings(input, None, &loader))
+}
+
+fn source_result_to_js<E: Into<ErrorResult>>(
+    result: Result<(Ast, Vec<SourceDiagnostic>), E>,
+) -> JsValue {
+    to_js(&source_result(result))
+}
+
+fn source_result<E: Into<ErrorResult>>(
+    result: Result<(Ast, Vec<SourceDiagnostic>), E>,
+) -> JsResult<Parsed> {
+    JsResult::from(result.map(|(ast, warnings)| Parsed {
+        ast,
+        warnings: warnings.into_iter().map(DiagnosticResult::from).collect(),
+    }))
+}
+
+/// @param {string} input - The chord progression string to parse.
+/// @param {string} lang - The language of the message and the hint, like "en" or "ja". Unknown languages fall back to "en".
+/// @param {boolean} color - Whether to use ANSI colors.
+/// @returns {string | undefined} - The rendered error, or undefined if the input has no error.
+#[wasm_bindgen(js_name = "renderChordProgressionStringError", skip_jsdoc)]
+pub fn render_chord_progression_string_error_js(
+    input: &str,
+    lang: &str,
+    color: bool,
+) -> Option<String> {
+    let error_info = parse_chord_progression_string(input).err()?;
+
+    Some(render(
+        input,
+        &error_info,
+        &RenderOptions {
+            lang: lang.to_string(),
+            color,
+            file_name: None,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use serde_json::json;
+
+    use super::*;
+
+    const GENERATED_TYPES: &str = include_str!("../resources/generatedTypes.ts");
+
+    /// `TYPESCRIPT_TYPES`, which `#[wasm_bindgen]` doesn't keep outside of wasm
+    fn result_types() -> &'static str {
+        let source = include_str!("wasm.rs");
+        let start = source.find("const TYPESCRIPT_TYPES: &str = r#\"").unwrap();
+        let source = &source[start..];
+
+        &source[..source.find("\"#;").unwrap()]
+    }
+
+    /// keys of the object type declared in TS, with whether they are required
+    fn declared_keys(ts_type: &str) -> BTreeMap<String, bool> {
+        if ts_type.contains(" & ") {
+            return ts_type.split(" & ").flat_map(declared_keys).collect();
+        }
+        if let Some(inner) = ts_type
+            .strip_prefix("JsResult<")
+            .and_then(|inner| inner.strip_suffix('>'))
+        {
+            let mut keys = declared_keys(inner);
+            keys.insert("success".to_string(), true);
+            return keys;
+        }
+        let name = ts_type.split('<').next().unwrap();
+        if let Some(alias) = result_types()
+            .lines()
+            .find_map(|line| line.strip_prefix(&format!("export type {} = ", name)))
+        {
+            return declared_keys(alias.trim_end_matches(';'));
+        }
+        let start = GENERATED_TYPES
+            .find(&format!("export interface {} ", name))
+            .or_else(|| GENERATED_TYPES.find(&format!("export interface {}<", name)))
+            .unwrap_or_else(|| panic!("{} is not declared", name));
+        GENERATED_TYPES[start..]
+            .lines()
+            .skip(1)
+            .take_while(|line| *line != "}")
+            .filter_map(|line| line.trim().split_once(':'))
+            .filter(|(key, _)| !key.starts_with('/') && !key.starts_with('*'))
+            .map(|(key, _)| match key.strip_suffix('?') {
+                Some(key) => (key.to_string(), false),
+                None => (key.to_string(), true),
+            })
+            .collect()
+    }
+
+    fn assert_declared(ts_type: &str, value: &serde_json::Value) {
+        let declared = declared_keys(ts_type);
+        let keys = value.as_object().unwrap().keys().collect::<BTreeSet<_>>();
+
+        for key in &keys {
+            assert!(declared.contains_key(*key), "{} of {}", key, ts_type);
+        }
+        for (key, required) in &declared {
+            assert!(!required || keys.contains(key), "{} of {}", key, ts_type);
+        }
+    }
+
+    fn parsed(input: &str) -> serde_json::Value {
+        serde_json::to_value(JsResult::from(
+            parse_chord_progression_string_with_warnings(input, &ParseOptions::default())
+                .map(Parsed::from),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn success_result() {
+        let result = parsed("C,");
+
+        assert_eq!(result["success"], json!(true));
+        assert_eq!(result["ast"][0]["chordBlocks"][0]["type"], json!("bar"));
+        assert_eq!(
+            result["warnings"],
+            json!([{
+                "severity": "warning",
+                "code": "CHB-4",
+                "additionalInfo": null,
+                "position": { "lineNumber": 1, "columnNumber": 2, "length": 1 },
+            }])
+        );
+    }
+
+    #[test]
+    fn failure_result() {
+        let result = parsed("C - Cmn");
+
+        assert_eq!(result["success"], json!(false));
+        assert_eq!(result["error"]["code"], json!("CHO-1"));
+        assert_eq!(result["error"]["additionalInfo"], json!("Cmn"));
+        assert_eq!(result["error"]["cause"], json!("EXT-1"));
+        assert!(result["error"]["suggestions"].is_array());
+        // only the errors of parseChordProgressionStringWithFiles have `fileName`
+        assert!(result["error"].get("fileName").is_none());
+        assert!(result.get("ast").is_none());
+    }
+
+    #[test]
+    fn included_result() {
+        let loader = MemoryLoader::new(
+            [(
+                "intro.txt".to_string(),
+                "@section=Intro\nC - G,".to_string(),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        let result = serde_json::to_value(source_result(parse_with_includes_and_warnings(
+            "@include=intro.txt\n\n@section=Outro\nF - G,",
+            None,
+            &loader,
+        )))
+        .unwrap();
+
+        assert_eq!(result["success"], json!(true));
+        assert_eq!(result["warnings"][0]["fileName"], json!(null));
+        assert_eq!(result["warnings"][0]["position"]["lineNumber"], json!(4));
+        assert_eq!(result["warnings"][1]["fileName"], json!("intro.txt"));
+        assert_eq!(result["warnings"][1]["code"], json!("CHB-4"));
+    }
+
+    #[test]
+    fn invalid_argument_result() {
+        let result = serde_json::to_value(JsResult::<Parsed>::from(Err(invalid_argument(
+            "options",
+            "invalid type: string \"x\", expected struct ParseOptions",
+        ))))
+        .unwrap();
+
+        assert_eq!(result["success"], json!(false));
+        assert_eq!(result["error"]["code"], json!("OTHER-3"));
+        assert_eq!(
+            result["error"]["additionalInfo"],
+            json!("options: invalid type: string \"x\", expected struct ParseOptions")
+        );
+    }
+
+    #[test]
+    fn tokenized_result() {
+        let result = serde_json::to_value(JsResult::from(
+            tokenize("C(9)").map(|tokens| Tokenized { tokens }),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            result["tokens"][0],
+            json!({
+                "token": { "type": "Chord", "value": "C" },
+                "position": { "lineNumber": 1, "columnNumber": 1, "length": 1 },
+            })
+        );
+        assert_eq!(
+            result["tokens"][1]["token"],
+            json!({ "type": "ExtensionStart" })
+        );
+    }
+
+    #[test]
+    fn edited_result() {
+        let mut parse =
+            IncrementalParse::new("@section=♭\nC\n\n@section=B\nG", ParseOptions::default());
+        // "♭" is 1 code unit in UTF-16 and 3 bytes in UTF-8
+        let start = byte_offset(parse.input(), 25);
+        assert_eq!(start, 27);
+        let changes = parse.edit(&TextEdit {
+            range: start..start + 1,
+            text: "Am".to_string(),
+        });
+        let result = serde_json::to_value(Edited::new(&parse, changes)).unwrap();
+
+        assert_eq!(result["success"], json!(true));
+        assert_eq!(
+            result["ast"],
+            parsed("@section=♭\nC\n\n@section=B\nAm")["ast"]
+        );
+        assert_eq!(result["changedSections"], json!({ "start": 1, "end": 2 }));
+        assert_eq!(result["changedLines"], json!({ "start": 4, "end": 6 }));
+        assert_eq!(byte_offset(parse.input(), 100), parse.input().len());
+    }
+
+    #[test]
+    fn results_match_typescript_types() {
+        let mut parse = IncrementalParse::new("C", ParseOptions::default());
+        let changes = parse.edit(&TextEdit {
+            range: 0..1,
+            text: "G,".to_string(),
+        });
+        let results = [
+            ("ParsedResult", parsed("C,")),
+            (
+                "ValidatedResult",
+                serde_json::to_value(JsResult::from(
+                    parse_chord_progression_string_with_warnings("C,", &ParseOptions::default())
+                        .map(|(_, warnings)| Warnings {
+                            warnings: warnings.into_iter().map(DiagnosticResult::from).collect(),
+                        }),
+                ))
+                .unwrap(),
+            ),
+            (
+                "LintResult",
+                serde_json::to_value(JsResult::from(
+                    lint_chord_progression_string(
+                        "C",
+                        &ParseOptions::default(),
+                        &LintConfig::default(),
+                    )
+                    .map(|warnings| Warnings {
+                        warnings: warnings
+                            .into_iter()
+                            .map(|warning| LintWarningResult::new(warning, "en"))
+                            .collect(),
+                    }),
+                ))
+                .unwrap(),
+            ),
+            (
+                "TokenizedResult",
+                serde_json::to_value(JsResult::from(
+                    tokenize("C").map(|tokens| Tokenized { tokens }),
+                ))
+                .unwrap(),
+            ),
+            (
+                "TransposedResult",
+                serde_json::to_value(JsResult::from(parse_chord_progression_string("C").map(
+                    |ast| Transposed {
+                        ast,
+                        output: "D".to_string(),
+                    },
+                )))
+                .unwrap(),
+            ),
+            (
+                "HighlightedResult",
+                serde_json::to_value(JsResult::from(semantic_tokens("C").map(|tokens| {
+                    Highlighted {
+                        data: encode_semantic_tokens(&tokens),
+                        tokens,
+                    }
+                })))
+                .unwrap(),
+            ),
+            (
+                "EditedResult",
+                serde_json::to_value(Edited::new(&parse, changes)).unwrap(),
+            ),
+        ];
+
+        // every result type of the TS is checked
+        let declared = result_types()
+            .lines()
+            .filter_map(|line| line.strip_prefix("export type "))
+            .filter_map(|line| line.split_once(" = "))
+            .map(|(name, _)| name)
+            .filter(|name| name.ends_with("Result"))
+            .collect::<BTreeSet<_>>();
+        assert_eq!(
+            declared,
+            results
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<BTreeSet<_>>()
+        );
+        for (name, result) in &results {
+            assert_eq!(result["success"], json!(true), "{}", name);
+            assert_declared(name, result);
+        }
+
+        let failure = parsed("C - Cmn");
+        assert_eq!(
+            failure.as_object().unwrap().keys().collect::<Vec<_>>(),
+            ["error", "success"]
+        );
+        assert_declared("ErrorResult", &failure["error"]);
+    }
+}